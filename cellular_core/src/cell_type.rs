@@ -0,0 +1,336 @@
+//! Cell type identities, ids and display colors
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CellType {
+    // Primary Ecosystem (0-7)
+    Black,      // 0 - Dead
+    Green,      // 1 - Vegetation
+    Orange,     // 2 - Vitality
+    Gray,       // 3 - Sick
+    Purple,     // 4 - Plague
+    Red,        // 5 - Cure
+    White,      // 6 - Defender
+    Blue,       // 7 - Water
+
+    // Herbivores & Consumers (8-11)
+    Brown,      // 8  - Herbivore
+    Tan,        // 9  - Omnivore
+    Gold,       // 10 - Scavenger
+    Lime,       // 11 - Symbiote
+
+    // Predators & Aggressive (12-16)
+    Crimson,    // 12 - Predator
+    Maroon,     // 13 - Apex
+    Coral,      // 14 - Aggressive
+    Pink,       // 15 - Parasite
+    Magenta,    // 16 - Mutant
+
+    // Environmental & Resources (17-22)
+    Cyan,       // 17 - Nutrient
+    Yellow,     // 18 - Light
+    Teal,       // 19 - Moisture
+    Navy,       // 20 - Deep Water
+    Olive,      // 21 - Soil
+    Indigo,     // 22 - Mineral
+
+    // Decomposers & Recyclers (23-25)
+    Khaki,      // 23 - Fungus
+    Slate,      // 24 - Bacteria (invisible)
+    Rust,       // 25 - Decay
+
+    // Regulatory & Protective (26-29)
+    Mint,       // 26 - Healer
+    Peach,      // 27 - Insulator
+    Aqua,       // 28 - Stabilizer
+    Silver,     // 29 - Sentinel
+
+    // Rare & Exotic (30-34)
+    Violet,     // 30 - Void
+    Amber,      // 31 - Catalyst
+    Pearl,      // 32 - Barrier
+    Smoke,      // 33 - Toxic
+    Glint,      // 34 - Spark
+
+    // Behavioral Specialties (35-37)
+    Tint,       // 35 - Generalist
+    Shade,      // 36 - Strategist
+}
+
+/// Ecological group of a cell type, following the groupings of `CellType`'s variants
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CellCategory {
+    Primary,
+    Herbivore,
+    Predator,
+    Environmental,
+    Decomposer,
+    Regulatory,
+    Exotic,
+    Behavioral,
+}
+
+impl CellCategory {
+    /// Every category, in the order their first member appears among the ids
+    pub const ALL: [CellCategory; 8] = [
+        CellCategory::Primary,
+        CellCategory::Herbivore,
+        CellCategory::Predator,
+        CellCategory::Environmental,
+        CellCategory::Decomposer,
+        CellCategory::Regulatory,
+        CellCategory::Exotic,
+        CellCategory::Behavioral,
+    ];
+
+    /// Cell types in this category, in `to_u8` order
+    pub fn members(self) -> impl Iterator<Item = CellType> {
+        CellType::all().filter(move |t| t.category() == self)
+    }
+
+    /// Display name, identical to the variant name (e.g. "Predator")
+    pub fn as_name(self) -> &'static str {
+        match self {
+            CellCategory::Primary => "Primary",
+            CellCategory::Herbivore => "Herbivore",
+            CellCategory::Predator => "Predator",
+            CellCategory::Environmental => "Environmental",
+            CellCategory::Decomposer => "Decomposer",
+            CellCategory::Regulatory => "Regulatory",
+            CellCategory::Exotic => "Exotic",
+            CellCategory::Behavioral => "Behavioral",
+        }
+    }
+}
+
+impl CellType {
+    /// Number of cell types; valid `to_u8` values are `0..COUNT`
+    pub const COUNT: usize = 37;
+
+    pub fn from_u8(n: u8) -> Option<CellType> {
+        match n {
+            0 => Some(CellType::Black),
+            1 => Some(CellType::Green),
+            2 => Some(CellType::Orange),
+            3 => Some(CellType::Gray),
+            4 => Some(CellType::Purple),
+            5 => Some(CellType::Red),
+            6 => Some(CellType::White),
+            7 => Some(CellType::Blue),
+            8 => Some(CellType::Brown),
+            9 => Some(CellType::Tan),
+            10 => Some(CellType::Gold),
+            11 => Some(CellType::Lime),
+            12 => Some(CellType::Crimson),
+            13 => Some(CellType::Maroon),
+            14 => Some(CellType::Coral),
+            15 => Some(CellType::Pink),
+            16 => Some(CellType::Magenta),
+            17 => Some(CellType::Cyan),
+            18 => Some(CellType::Yellow),
+            19 => Some(CellType::Teal),
+            20 => Some(CellType::Navy),
+            21 => Some(CellType::Olive),
+            22 => Some(CellType::Indigo),
+            23 => Some(CellType::Khaki),
+            24 => Some(CellType::Slate),
+            25 => Some(CellType::Rust),
+            26 => Some(CellType::Mint),
+            27 => Some(CellType::Peach),
+            28 => Some(CellType::Aqua),
+            29 => Some(CellType::Silver),
+            30 => Some(CellType::Violet),
+            31 => Some(CellType::Amber),
+            32 => Some(CellType::Pearl),
+            33 => Some(CellType::Smoke),
+            34 => Some(CellType::Glint),
+            35 => Some(CellType::Tint),
+            36 => Some(CellType::Shade),
+            _ => None,
+        }
+    }
+
+    pub fn to_u8(&self) -> u8 {
+        match self {
+            CellType::Black => 0,
+            CellType::Green => 1,
+            CellType::Orange => 2,
+            CellType::Gray => 3,
+            CellType::Purple => 4,
+            CellType::Red => 5,
+            CellType::White => 6,
+            CellType::Blue => 7,
+            CellType::Brown => 8,
+            CellType::Tan => 9,
+            CellType::Gold => 10,
+            CellType::Lime => 11,
+            CellType::Crimson => 12,
+            CellType::Maroon => 13,
+            CellType::Coral => 14,
+            CellType::Pink => 15,
+            CellType::Magenta => 16,
+            CellType::Cyan => 17,
+            CellType::Yellow => 18,
+            CellType::Teal => 19,
+            CellType::Navy => 20,
+            CellType::Olive => 21,
+            CellType::Indigo => 22,
+            CellType::Khaki => 23,
+            CellType::Slate => 24,
+            CellType::Rust => 25,
+            CellType::Mint => 26,
+            CellType::Peach => 27,
+            CellType::Aqua => 28,
+            CellType::Silver => 29,
+            CellType::Violet => 30,
+            CellType::Amber => 31,
+            CellType::Pearl => 32,
+            CellType::Smoke => 33,
+            CellType::Glint => 34,
+            CellType::Tint => 35,
+            CellType::Shade => 36,
+        }
+    }
+
+    /// All `COUNT` variants in `to_u8` order
+    pub fn all() -> impl Iterator<Item = CellType> {
+        (0..Self::COUNT as u8).filter_map(CellType::from_u8)
+    }
+
+    /// Display name, identical to the variant name (e.g. "Green")
+    pub fn as_name(self) -> &'static str {
+        match self {
+            CellType::Black => "Black",
+            CellType::Green => "Green",
+            CellType::Orange => "Orange",
+            CellType::Gray => "Gray",
+            CellType::Purple => "Purple",
+            CellType::Red => "Red",
+            CellType::White => "White",
+            CellType::Blue => "Blue",
+            CellType::Brown => "Brown",
+            CellType::Tan => "Tan",
+            CellType::Gold => "Gold",
+            CellType::Lime => "Lime",
+            CellType::Crimson => "Crimson",
+            CellType::Maroon => "Maroon",
+            CellType::Coral => "Coral",
+            CellType::Pink => "Pink",
+            CellType::Magenta => "Magenta",
+            CellType::Cyan => "Cyan",
+            CellType::Yellow => "Yellow",
+            CellType::Teal => "Teal",
+            CellType::Navy => "Navy",
+            CellType::Olive => "Olive",
+            CellType::Indigo => "Indigo",
+            CellType::Khaki => "Khaki",
+            CellType::Slate => "Slate",
+            CellType::Rust => "Rust",
+            CellType::Mint => "Mint",
+            CellType::Peach => "Peach",
+            CellType::Aqua => "Aqua",
+            CellType::Silver => "Silver",
+            CellType::Violet => "Violet",
+            CellType::Amber => "Amber",
+            CellType::Pearl => "Pearl",
+            CellType::Smoke => "Smoke",
+            CellType::Glint => "Glint",
+            CellType::Tint => "Tint",
+            CellType::Shade => "Shade",
+        }
+    }
+
+    /// Ecological group this type belongs to
+    pub fn category(self) -> CellCategory {
+        match self {
+            CellType::Black | CellType::Green | CellType::Orange | CellType::Gray
+            | CellType::Purple | CellType::Red | CellType::White | CellType::Blue => CellCategory::Primary,
+            CellType::Brown | CellType::Tan | CellType::Gold | CellType::Lime => CellCategory::Herbivore,
+            CellType::Crimson | CellType::Maroon | CellType::Coral
+            | CellType::Pink | CellType::Magenta => CellCategory::Predator,
+            CellType::Cyan | CellType::Yellow | CellType::Teal
+            | CellType::Navy | CellType::Olive | CellType::Indigo => CellCategory::Environmental,
+            CellType::Khaki | CellType::Slate | CellType::Rust => CellCategory::Decomposer,
+            CellType::Mint | CellType::Peach | CellType::Aqua | CellType::Silver => CellCategory::Regulatory,
+            CellType::Violet | CellType::Amber | CellType::Pearl
+            | CellType::Smoke | CellType::Glint => CellCategory::Exotic,
+            CellType::Tint | CellType::Shade => CellCategory::Behavioral,
+        }
+    }
+
+    /// Inverse of `as_name`; None for anything that isn't an exact variant name
+    pub fn from_name(name: &str) -> Option<CellType> {
+        Self::all().find(|t| t.as_name() == name)
+    }
+
+    pub fn get_color(&self) -> (u8, u8, u8) {
+        match self {
+            CellType::Black => (0, 0, 0),
+            CellType::Green => (0, 204, 0),
+            CellType::Orange => (255, 136, 0),
+            CellType::Gray => (136, 136, 136),
+            CellType::Purple => (170, 0, 255),
+            CellType::Red => (255, 0, 0),
+            CellType::White => (255, 255, 255),
+            CellType::Blue => (0, 136, 255),
+            CellType::Brown => (165, 82, 0),
+            CellType::Tan => (210, 180, 140),
+            CellType::Gold => (255, 215, 0),
+            CellType::Lime => (50, 205, 50),
+            CellType::Crimson => (220, 20, 60),
+            CellType::Maroon => (128, 0, 0),
+            CellType::Coral => (255, 127, 80),
+            CellType::Pink => (255, 192, 203),
+            CellType::Magenta => (255, 0, 255),
+            CellType::Cyan => (0, 255, 255),
+            CellType::Yellow => (255, 255, 0),
+            CellType::Teal => (0, 128, 128),
+            CellType::Navy => (0, 0, 128),
+            CellType::Olive => (128, 128, 0),
+            CellType::Indigo => (75, 0, 130),
+            CellType::Khaki => (240, 230, 200),
+            CellType::Slate => (112, 128, 144),
+            CellType::Rust => (183, 65, 14),
+            CellType::Mint => (152, 251, 152),
+            CellType::Peach => (255, 218, 185),
+            CellType::Aqua => (127, 255, 212),
+            CellType::Silver => (192, 192, 192),
+            CellType::Violet => (238, 130, 238),
+            CellType::Amber => (255, 191, 0),
+            CellType::Pearl => (240, 240, 240),
+            CellType::Smoke => (100, 100, 100),
+            CellType::Glint => (255, 255, 150),
+            CellType::Tint => (200, 200, 200),
+            CellType::Shade => (64, 64, 64),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names_round_trip_for_every_variant() {
+        assert_eq!(CellType::all().count(), CellType::COUNT);
+        for (id, t) in CellType::all().enumerate() {
+            assert_eq!(t.to_u8() as usize, id);
+            assert_eq!(CellType::from_name(t.as_name()), Some(t));
+        }
+        assert_eq!(CellType::Crimson.as_name(), "Crimson");
+        assert_eq!(CellType::from_name("crimson"), None);
+    }
+
+    #[test]
+    fn test_categories_follow_id_groupings() {
+        assert_eq!(CellType::Crimson.category(), CellCategory::Predator);
+        assert_eq!(CellType::Brown.category(), CellCategory::Herbivore);
+        assert_eq!(CellType::Shade.category(), CellCategory::Behavioral);
+        assert!(CellCategory::Predator
+            .members()
+            .eq([CellType::Crimson, CellType::Maroon, CellType::Coral, CellType::Pink, CellType::Magenta]));
+        let total: usize = CellCategory::ALL.iter().map(|c| c.members().count()).sum();
+        assert_eq!(total, CellType::COUNT);
+    }
+}
@@ -0,0 +1,53 @@
+//! Ecosystem health formulas shared by `cellular_sim::stats`
+
+/// Shannon entropy in bits of the given populations out of `total` cells
+///
+/// Zero counts contribute nothing. Populations not passed in are simply
+/// left out of the sum, so callers can restrict it to the largest few.
+pub fn shannon_bits(counts: impl IntoIterator<Item = u32>, total: f64) -> f64 {
+    counts
+        .into_iter()
+        .map(|count| count as f64 / total)
+        .filter(|&p| p > 0.0)
+        .map(|p| -p * libm::log2(p))
+        .sum()
+}
+
+/// Vegetation contribution to health (0-50), best between 20% and 60% coverage
+pub fn green_score(green_coverage: f64) -> f64 {
+    if (20.0..=60.0).contains(&green_coverage) {
+        50.0
+    } else if green_coverage < 20.0 {
+        green_coverage * 2.5
+    } else {
+        50.0 - ((green_coverage - 60.0) * 0.5)
+    }
+}
+
+/// Overall health in 0.0-1.0
+///
+/// Green coverage (0-50) + orange share (0-30) + stability (0-20).
+pub fn health_score(green_coverage: f64, orange_fraction: f64, stability: f64) -> f64 {
+    let orange_score = (orange_fraction * 30.0 * 100.0).min(30.0);
+    let stability_score = stability * 20.0;
+    ((green_score(green_coverage) + orange_score + stability_score) / 100.0).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shannon_bits() {
+        assert_eq!(shannon_bits([10, 0], 10.0), 0.0);
+        assert!((shannon_bits([5, 5], 10.0) - 1.0).abs() < 1e-12);
+        assert!((shannon_bits([1, 1, 1, 1], 4.0) - 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_health_score_bounds() {
+        assert_eq!(health_score(0.0, 0.0, 0.0), 0.0);
+        assert_eq!(health_score(40.0, 0.5, 1.0), 1.0);
+        assert_eq!(green_score(80.0), 40.0);
+    }
+}
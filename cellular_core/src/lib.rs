@@ -0,0 +1,22 @@
+//! Simulation Core
+//!
+//! The parts of the simulation that are pure math over cell types: type
+//! ids and colors, neighborhood windows and counts over row-major `u8`
+//! grids, and the ecology formulas behind `stats`. The crate is `no_std`
+//! and needs neither `std` nor `alloc`, so it can be embedded in
+//! constrained targets; `cellular_sim` layers grids, RNG, serialization
+//! and the GUI on top.
+//!
+//! Enable the `serde` feature for `Serialize`/`Deserialize` on `CellType`.
+
+#![no_std]
+
+#[cfg(test)]
+extern crate std;
+
+pub mod cell_type;
+pub mod ecology;
+pub mod neighborhood;
+
+pub use cell_type::{CellCategory, CellType};
+pub use neighborhood::Neighborhood;
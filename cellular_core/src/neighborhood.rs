@@ -0,0 +1,82 @@
+//! Neighborhood windows and counts over row-major grids of type ids
+
+use core::ops::Range;
+
+/// Which cells count as the immediate neighbors of a cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Neighborhood {
+    /// The 8 surrounding cells, diagonals included
+    #[default]
+    Moore,
+    /// The 4 orthogonally adjacent cells
+    VonNeumann,
+}
+
+const MOORE: [(i32, i32); 8] = [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)];
+const VON_NEUMANN: [(i32, i32); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+impl Neighborhood {
+    /// (dx, dy) of every neighbor, in row-major order
+    pub const fn offsets(self) -> &'static [(i32, i32)] {
+        match self {
+            Neighborhood::Moore => &MOORE,
+            Neighborhood::VonNeumann => &VON_NEUMANN,
+        }
+    }
+}
+
+/// Square window of `radius` around (x, y), clipped to the grid
+///
+/// Returns half-open x and y ranges. The center is included.
+#[inline]
+pub fn window(x: u32, y: u32, radius: u32, width: u32, height: u32) -> (Range<u32>, Range<u32>) {
+    let xs = x.saturating_sub(radius)..x.saturating_add(radius).saturating_add(1).min(width);
+    let ys = y.saturating_sub(radius)..y.saturating_add(radius).saturating_add(1).min(height);
+    (xs, ys)
+}
+
+/// Cells equal to `target` in the radius window around (x, y), center included
+pub fn count_in_radius(types: &[u8], width: u32, height: u32, x: u32, y: u32, target: u8, radius: u32) -> usize {
+    let (xs, ys) = window(x, y, radius, width, height);
+    ys.map(|cy| {
+        let row = (cy * width) as usize;
+        xs.clone().filter(|&cx| types[row + cx as usize] == target).count()
+    })
+    .sum()
+}
+
+/// Cells equal to `target` among the 8 neighbors of (x, y)
+pub fn count_neighbors(types: &[u8], width: u32, height: u32, x: u32, y: u32, target: u8) -> usize {
+    let center = (x < width && y < height && types[(y * width + x) as usize] == target) as usize;
+    count_in_radius(types, width, height, x, y, target, 1) - center
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_clip_at_edges() {
+        // 4x3 grid, type 1 in the corners and the middle of the top row
+        let types = [
+            1, 0, 1, 1,
+            0, 0, 0, 0,
+            1, 0, 0, 1,
+        ];
+        assert_eq!(window(0, 0, 1, 4, 3), (0..2, 0..2));
+        assert_eq!(count_neighbors(&types, 4, 3, 0, 0, 1), 0);
+        assert_eq!(count_neighbors(&types, 4, 3, 1, 1, 1), 3);
+        assert_eq!(count_in_radius(&types, 4, 3, 3, 2, 1, 10), 5);
+    }
+
+    #[test]
+    fn test_von_neumann_is_moore_without_diagonals() {
+        let moore = Neighborhood::Moore.offsets();
+        let von_neumann = Neighborhood::VonNeumann.offsets();
+        assert_eq!(moore.len(), 8);
+        assert!(!moore.contains(&(0, 0)));
+        assert_eq!(von_neumann.len(), 4);
+        assert!(von_neumann.iter().all(|&(dx, dy)| (dx == 0) != (dy == 0) && moore.contains(&(dx, dy))));
+    }
+}
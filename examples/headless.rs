@@ -0,0 +1,34 @@
+//! Run a preset without the GUI and print sampled stats as CSV
+//!
+//!     cargo run --release --no-default-features --example headless -- [preset] [ticks] [sample_every] [seed]
+
+use cellular_sim::headless::BatchRunner;
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let preset = args.first().map_or("balanced", String::as_str);
+    let ticks = args.get(1).and_then(|a| a.parse().ok()).unwrap_or(1000);
+    let sample_every = args.get(2).and_then(|a| a.parse().ok()).unwrap_or(10);
+    let seed = args.get(3).and_then(|a| a.parse().ok()).unwrap_or(0);
+
+    let mut runner = BatchRunner::new(200, 200, seed);
+    if !runner.load_preset(preset) {
+        eprintln!("unknown preset: {}", preset);
+        std::process::exit(1);
+    }
+
+    println!("tick,health_score,green_coverage,orange_population,predator_count,disease_pressure,diversity_index,stability");
+    for (i, stats) in runner.run(ticks, sample_every).iter().enumerate() {
+        println!(
+            "{},{:.4},{:.4},{},{},{:.4},{:.4},{:.4}",
+            (i as u64 + 1) * sample_every.max(1),
+            stats.health_score,
+            stats.green_coverage,
+            stats.orange_population,
+            stats.predator_count,
+            stats.disease_pressure,
+            stats.diversity_index,
+            stats.stability,
+        );
+    }
+}
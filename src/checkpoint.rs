@@ -0,0 +1,249 @@
+//! Simulation Checkpoints
+//!
+//! Full-state snapshots for pausing and resuming long experiments. Unlike
+//! wire frames (cell types only), a checkpoint keeps every per-cell field
+//! (age, metadata, rng_seed, genes) plus the tick count and the tick RNG
+//! position, so a resumed run carries on exactly where it stopped.
+//!
+//! Layout (all integers little-endian):
+//!
+//! | Offset | Size | Field                              |
+//! |--------|------|------------------------------------|
+//! | 0      | 4    | magic `b"CASV"`                    |
+//! | 4      | 1    | format version (currently 1)       |
+//! | 5      | 4    | width (u32)                        |
+//! | 9      | 4    | height (u32)                       |
+//! | 13     | 8    | tick (u64)                         |
+//! | 21     | 1    | flags (bit 0: toroidal wrap)       |
+//! | 22     | 8    | RNG seed (u64)                     |
+//! | 30     | 16   | RNG word position (u128)           |
+//! | 46     | ..   | `CELL_RECORD_LEN` bytes per cell   |
+//!
+//! Each cell record, in row-major order: type, age, metadata (u8 each),
+//! rng_seed (u64), spread_tendency, aggression, vitality, mutatability
+//! (f64 each), generation, parent type ids (u8 each).
+
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+
+use crate::cell::{Cell, CellType, Genes};
+use crate::grid::Grid;
+use crate::Simulator;
+
+pub const CHECKPOINT_MAGIC: [u8; 4] = *b"CASV";
+pub const CHECKPOINT_VERSION: u8 = 1;
+pub const CHECKPOINT_HEADER_LEN: usize = 46;
+pub const CELL_RECORD_LEN: usize = 46;
+
+const FLAG_WRAP: u8 = 1;
+
+/// Serialize the full simulator state
+pub fn encode(sim: &Simulator) -> Vec<u8> {
+    let grid = &sim.grid;
+    let cells = grid.width as usize * grid.height as usize;
+    let mut out = Vec::with_capacity(CHECKPOINT_HEADER_LEN + cells * CELL_RECORD_LEN);
+
+    out.extend_from_slice(&CHECKPOINT_MAGIC);
+    out.push(CHECKPOINT_VERSION);
+    out.extend_from_slice(&grid.width.to_le_bytes());
+    out.extend_from_slice(&grid.height.to_le_bytes());
+    out.extend_from_slice(&sim.tick_count.to_le_bytes());
+    out.push(if grid.wrap { FLAG_WRAP } else { 0 });
+    out.extend_from_slice(&sim.seed.to_le_bytes());
+    out.extend_from_slice(&sim.rng.get_word_pos().to_le_bytes());
+
+    for y in 0..grid.height {
+        for x in 0..grid.width {
+            let cell = grid.get_cell(x, y).expect("in-bounds cell");
+            let genes = &cell.genes;
+            out.extend_from_slice(&[cell.cell_type.to_u8(), cell.age, cell.metadata]);
+            out.extend_from_slice(&cell.rng_seed.to_le_bytes());
+            for gene in [genes.spread_tendency, genes.aggression, genes.vitality, genes.mutatability] {
+                out.extend_from_slice(&gene.to_le_bytes());
+            }
+            out.extend_from_slice(&[genes.generation, genes.parent_types.0, genes.parent_types.1]);
+        }
+    }
+    out
+}
+
+/// Rebuild a simulator from `encode` output
+///
+/// The rule config isn't part of a checkpoint; the result starts with the
+/// default one.
+pub fn decode(bytes: &[u8]) -> io::Result<Simulator> {
+    if bytes.len() < CHECKPOINT_HEADER_LEN {
+        return Err(invalid(format!("checkpoint shorter than {}-byte header", CHECKPOINT_HEADER_LEN)));
+    }
+    if bytes[0..4] != CHECKPOINT_MAGIC {
+        return Err(invalid(format!("bad checkpoint magic {:?}", &bytes[0..4])));
+    }
+    if bytes[4] != CHECKPOINT_VERSION {
+        return Err(invalid(format!(
+            "unsupported checkpoint version {} (expected {})",
+            bytes[4], CHECKPOINT_VERSION
+        )));
+    }
+
+    let mut r = Reader { bytes, pos: 5 };
+    let width = u32::from_le_bytes(r.take());
+    let height = u32::from_le_bytes(r.take());
+    let tick_count = u64::from_le_bytes(r.take());
+    let [flags] = r.take();
+    let seed = u64::from_le_bytes(r.take());
+    let word_pos = u128::from_le_bytes(r.take());
+
+    // Dimensions are untrusted; a product that overflows can't match anyway
+    let expected = (width as usize)
+        .checked_mul(height as usize)
+        .and_then(|cells| cells.checked_mul(CELL_RECORD_LEN))
+        .ok_or_else(|| invalid(format!("checkpoint dimensions {}x{} are too large", width, height)))?;
+    if bytes.len() - CHECKPOINT_HEADER_LEN != expected {
+        return Err(invalid(format!(
+            "checkpoint holds {} bytes of cells, expected {} for {}x{}",
+            bytes.len() - CHECKPOINT_HEADER_LEN,
+            expected,
+            width,
+            height
+        )));
+    }
+
+    let mut cells = Vec::with_capacity(width as usize * height as usize);
+    while r.pos < bytes.len() {
+        let [type_id, age, metadata] = r.take();
+        let cell_type = CellType::from_u8(type_id)
+            .ok_or_else(|| invalid(format!("invalid cell type byte {}", type_id)))?;
+        let rng_seed = u64::from_le_bytes(r.take());
+        let spread_tendency = f64::from_le_bytes(r.take());
+        let aggression = f64::from_le_bytes(r.take());
+        let vitality = f64::from_le_bytes(r.take());
+        let mutatability = f64::from_le_bytes(r.take());
+        let [generation, parent_a, parent_b] = r.take();
+        cells.push(Cell {
+            cell_type,
+            age,
+            metadata,
+            rng_seed,
+            genes: Genes {
+                spread_tendency,
+                aggression,
+                vitality,
+                mutatability,
+                generation,
+                parent_types: (parent_a, parent_b),
+            },
+        });
+    }
+
+    let mut grid = Grid::with_topology(width, height, flags & FLAG_WRAP != 0);
+    grid.restore_cells(cells);
+    let mut sim = Simulator::with_grid(grid, seed);
+    sim.rng.set_word_pos(word_pos);
+    sim.tick_count = tick_count;
+    Ok(sim)
+}
+
+/// Write `encode(sim)` to `path`
+pub fn save(sim: &Simulator, path: &Path) -> io::Result<()> {
+    fs::write(path, encode(sim))
+}
+
+/// Read a checkpoint written by `save`
+pub fn load(path: &Path) -> io::Result<Simulator> {
+    decode(&fs::read(path)?)
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, message)
+}
+
+/// Fixed-size reads from a buffer whose length was checked up front
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> [u8; N] {
+        let out = self.bytes[self.pos..self.pos + N].try_into().unwrap();
+        self.pos += N;
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkpoint_round_trip_keeps_genes() {
+        let mut sim = Simulator::new_seeded(16, 12, 9);
+        sim.grid.set_cell(3, 4, CellType::Green);
+        sim.tick();
+        let evolved = Genes {
+            spread_tendency: 0.91,
+            aggression: 0.12,
+            vitality: 0.77,
+            mutatability: 0.33,
+            generation: 14,
+            parent_types: (CellType::Green.to_u8(), CellType::Blue.to_u8()),
+        };
+        let mut cell = Cell::with_genes(CellType::Blue, evolved);
+        cell.age = 42;
+        cell.metadata = 7;
+        let cells: Vec<Cell> = (0..16 * 12)
+            .map(|i| if i == 5 * 16 + 6 { cell.clone() } else { sim.grid.get_cell(i % 16, i / 16).unwrap() })
+            .collect();
+        sim.grid.restore_cells(cells);
+
+        let path = std::env::temp_dir().join(format!("cellular_sim_checkpoint_{}.bin", std::process::id()));
+        sim.save(&path).unwrap();
+        let loaded = Simulator::load(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(loaded.tick_count, 1);
+        let restored = loaded.grid.get_cell(6, 5).unwrap();
+        assert_eq!((restored.cell_type, restored.age, restored.metadata), (CellType::Blue, 42, 7));
+        assert_eq!(restored.rng_seed, cell.rng_seed);
+        assert_eq!(restored.genes.vitality, 0.77);
+        assert_eq!(restored.genes.generation, 14);
+        assert_eq!(restored.genes.parent_types, evolved.parent_types);
+        assert_eq!(loaded.get_grid_data(), sim.get_grid_data());
+        // The RNG resumes mid-stream rather than from the seed
+        assert_eq!(loaded.seed(), 9);
+        assert_eq!(loaded.rng.get_word_pos(), sim.rng.get_word_pos());
+
+        let mut bytes = encode(&sim);
+        bytes[4] = 99;
+        let err = decode(&bytes).err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert!(err.to_string().contains("version 99"));
+    }
+
+    #[test]
+    fn test_seeded_edits_and_ticks_checkpoint_identically() {
+        let run = || {
+            let mut sim = Simulator::new_seeded(24, 24, 4);
+            sim.paint_rect(2, 2, 10, 10, CellType::Green.to_u8());
+            sim.paint_circle(16, 16, 3, CellType::Blue.to_u8());
+            sim.set_cell(20, 4, CellType::Purple.to_u8());
+            sim.perturb(crate::Perturbation::AddPredators { count: 5 });
+            for _ in 0..4 {
+                sim.tick();
+            }
+            encode(&sim)
+        };
+        assert_eq!(run(), run());
+    }
+
+    #[test]
+    fn test_checkpoint_rejects_overflowing_dimensions() {
+        // u32::MAX x u32::MAX records overflow usize before any length check
+        let mut bytes = encode(&Simulator::new_seeded(2, 2, 1));
+        bytes[5..13].fill(0xFF);
+        let err = decode(&bytes).err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert!(err.to_string().contains("too large"));
+    }
+}
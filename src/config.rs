@@ -0,0 +1,782 @@
+//! Rule Configuration
+//!
+//! Tunable constants for the hand-written rules in `rules.rs`. Every
+//! `Default` impl reproduces the original hard-coded behaviour, so
+//! `RuleConfig::default()` runs the classic simulation.
+
+use serde::{Serialize, Deserialize};
+
+use crate::cell::CellType;
+pub use cellular_core::Neighborhood;
+
+/// Yellow (light) parameters
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct YellowConfig {
+    /// Added to a green cell's spread rate when light reaches it (0.0-1.0)
+    pub photosynthesis_boost: f64,
+
+    /// Upper bound on a boosted green spread rate (0.0-1.0)
+    pub max_boosted_rate: f64,
+
+    /// How far light reaches, in cells (1 = adjacent only)
+    ///
+    /// The boost falls off as `photosynthesis_boost / distance`, where
+    /// distance is the Chebyshev distance to the nearest yellow.
+    /// Should not exceed `grid::BOUNDARY_RADIUS`.
+    pub light_radius: u32,
+
+    /// Age (ticks) after which an isolated yellow can burn out
+    pub lifespan: u8,
+
+    /// Chance per tick that an isolated yellow past its lifespan burns out
+    pub burnout_chance: f64,
+
+    /// Chance to spread into each empty neighbor per tick
+    pub spread_rate: f64,
+}
+
+impl Default for YellowConfig {
+    fn default() -> Self {
+        YellowConfig {
+            photosynthesis_boost: 0.20,
+            max_boosted_rate: 0.90,
+            light_radius: 1,
+            lifespan: 15,
+            burnout_chance: 0.3,
+            spread_rate: 0.15,
+        }
+    }
+}
+
+/// Silver (sentinel) parameters
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SilverConfig {
+    /// Chance per tick that an under-supported silver turns white
+    pub white_transform_rate: f64,
+
+    /// Silver only turns white with fewer than this many white neighbors
+    pub white_neighbor_threshold: usize,
+
+    /// How far silver looks for purple/crimson threats
+    ///
+    /// Clamped to `grid::BOUNDARY_RADIUS` so the isolated read stays valid.
+    pub threat_radius: u32,
+
+    /// Chance to turn each adjacent orange/gray red while threatened
+    pub defense_rate: f64,
+}
+
+impl Default for SilverConfig {
+    fn default() -> Self {
+        SilverConfig {
+            white_transform_rate: 0.1,
+            white_neighbor_threshold: 2,
+            threat_radius: 10,
+            defense_rate: 0.5,
+        }
+    }
+}
+
+/// Blue (water) parameters
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct BlueConfig {
+    /// Age (ticks) after which blue can evaporate
+    pub lifespan: u8,
+
+    /// Chance per tick that blue past its lifespan evaporates
+    pub evaporation_chance: f64,
+
+    /// Chance to seed green into each empty neighbor per tick
+    pub green_spawn_rate: f64,
+
+    /// Multiplier on a green cell's spread rate when blue is adjacent
+    pub green_boost_multiplier: f64,
+
+    /// Upper bound on a water-boosted green spread rate (0.0-1.0)
+    pub green_boost_cap: f64,
+
+    /// Stop seeding green once this many green cells are within radius 5
+    ///
+    /// `None` (default) never stops. Set this when using very long
+    /// lifespans so an oasis can't turn the whole grid green.
+    pub green_saturation: Option<usize>,
+}
+
+impl Default for BlueConfig {
+    fn default() -> Self {
+        BlueConfig {
+            lifespan: 8,
+            evaporation_chance: 0.3,
+            green_spawn_rate: 0.20,
+            green_boost_multiplier: 1.75,
+            green_boost_cap: 0.70,
+            green_saturation: None,
+        }
+    }
+}
+
+/// Navy (deep water) parameters
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct NavyConfig {
+    /// Chance to deepen each adjacent blue into navy per tick
+    pub blue_conversion_rate: f64,
+
+    /// Chance to spread into each empty neighbor per tick
+    pub spread_rate: f64,
+
+    /// Chance per tick that navy shallows back into blue (0.0 = never)
+    pub reversion_rate: f64,
+}
+
+impl Default for NavyConfig {
+    fn default() -> Self {
+        NavyConfig {
+            blue_conversion_rate: 0.25,
+            spread_rate: 0.05,
+            reversion_rate: 0.0,
+        }
+    }
+}
+
+/// Behavioral specialty parameters: Tint (generalist) and Shade (strategist)
+///
+/// Grouped so the social dynamics can be tuned as a unit, from flocking
+/// (high tint thresholds, low tolerance) to loners (the reverse).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct BehavioralConfig {
+    /// A tint with fewer tint neighbors than this counts as isolated
+    pub tint_min_neighbors: usize,
+
+    /// Chance per tick that an isolated tint survives
+    pub tint_isolation_tolerance: f64,
+
+    /// Chance for tint to spread into each empty neighbor per tick
+    pub tint_spread_rate: f64,
+
+    /// Tint with at least this many tint neighbors spreads at the flock rate
+    pub tint_flock_size: usize,
+
+    /// Spread rate used instead of `tint_spread_rate` inside a flock
+    pub tint_flock_spread_rate: f64,
+
+    /// How far shade scans for green and threats
+    pub shade_scan_radius: u32,
+
+    /// Shade flees (dies) when more threats than this are in range and no green
+    pub shade_threat_threshold: usize,
+
+    /// Chance per tick to flee once the threat threshold is exceeded
+    pub shade_flee_rate: f64,
+
+    /// A threatened empty cell is still a spread target with more green neighbors than this
+    pub shade_green_cover: usize,
+
+    /// Chance per tick for shade to spread into one chosen target
+    pub shade_spread_rate: f64,
+}
+
+impl Default for BehavioralConfig {
+    fn default() -> Self {
+        BehavioralConfig {
+            tint_min_neighbors: 1,
+            tint_isolation_tolerance: 0.7,
+            tint_spread_rate: 0.30,
+            tint_flock_size: 2,
+            tint_flock_spread_rate: 0.40,
+            shade_scan_radius: 5,
+            shade_threat_threshold: 5,
+            shade_flee_rate: 0.5,
+            shade_green_cover: 2,
+            shade_spread_rate: 0.20,
+        }
+    }
+}
+
+/// Mint (healer) parameters
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct MintConfig {
+    /// Chance per tick to heal each eligible cell in range
+    pub heal_rate: f64,
+
+    /// Type mint heals
+    pub heals: CellType,
+
+    /// Type a healed cell becomes
+    pub heals_into: CellType,
+
+    /// How far mint reaches, in cells (1 = adjacent only)
+    ///
+    /// Clamped to `grid::BOUNDARY_RADIUS`.
+    pub radius: u32,
+}
+
+impl Default for MintConfig {
+    fn default() -> Self {
+        MintConfig {
+            heal_rate: 0.15,
+            heals: CellType::Gray,
+            heals_into: CellType::Orange,
+            radius: 1,
+        }
+    }
+}
+
+/// Grazing parameters for one herbivore
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrazerConfig {
+    /// Types this herbivore eats
+    pub diet: Vec<CellType>,
+
+    /// Chance per tick to clear each adjacent food cell to black
+    pub graze_rate: f64,
+
+    /// How far the herbivore looks for food
+    ///
+    /// Clamped to `grid::BOUNDARY_RADIUS`.
+    pub food_radius: u32,
+
+    /// Starves when fewer than this many food cells are within `food_radius`
+    pub min_food: usize,
+
+    /// Chance per tick that a starving herbivore dies
+    pub starvation_chance: f64,
+
+    /// What a starved herbivore becomes
+    pub starves_into: CellType,
+}
+
+/// Herbivore (brown, tan) parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HerbivoreConfig {
+    pub brown: GrazerConfig,
+    pub tan: GrazerConfig,
+}
+
+impl Default for HerbivoreConfig {
+    fn default() -> Self {
+        HerbivoreConfig {
+            brown: GrazerConfig {
+                diet: vec![CellType::Green],
+                graze_rate: 0.8,
+                food_radius: 5,
+                min_food: 1,
+                starvation_chance: 0.5,
+                starves_into: CellType::Gray,
+            },
+            tan: GrazerConfig {
+                diet: vec![CellType::Green, CellType::Orange],
+                graze_rate: 0.7,
+                food_radius: 5,
+                min_food: 2,
+                starvation_chance: 1.0,
+                starves_into: CellType::Gray,
+            },
+        }
+    }
+}
+
+/// Gold (scavenger) parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoldConfig {
+    /// Chance per tick to clear each adjacent food cell to black
+    pub scavenge_rate: f64,
+
+    /// Types gold feeds on
+    pub scavenges: Vec<CellType>,
+
+    /// How far gold looks for food before starving
+    ///
+    /// Clamped to `grid::BOUNDARY_RADIUS`.
+    pub food_radius: u32,
+
+    /// Chance per tick that gold with no food in range dies
+    pub starvation_chance: f64,
+}
+
+impl Default for GoldConfig {
+    fn default() -> Self {
+        GoldConfig {
+            scavenge_rate: 0.50,
+            scavenges: vec![CellType::Gray],
+            food_radius: 5,
+            starvation_chance: 1.0,
+        }
+    }
+}
+
+/// Peach (insulator) parameters
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PeachConfig {
+    /// Orange/gray within this distance of a peach can't be infected by purple
+    ///
+    /// 1 protects only cells touching the peach. Clamped to
+    /// `grid::BOUNDARY_RADIUS - 1`, since the check is made around the
+    /// target cell, one step away from the purple doing the infecting.
+    pub insulation_radius: u32,
+
+    /// How far peach looks for purple when deciding whether to die
+    pub exposure_radius: u32,
+
+    /// Peach can die once this many purple are within `exposure_radius`
+    pub death_threshold: usize,
+
+    /// Chance per tick to die once the threshold is reached
+    pub death_chance: f64,
+
+    /// Chance to spread into each empty neighbor per tick
+    pub spread_rate: f64,
+}
+
+impl Default for PeachConfig {
+    fn default() -> Self {
+        PeachConfig {
+            insulation_radius: 1,
+            exposure_radius: 5,
+            death_threshold: 5,
+            death_chance: 0.5,
+            spread_rate: 0.20,
+        }
+    }
+}
+
+/// Aqua (stabilizer) parameters
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct AquaConfig {
+    /// Aqua can die when more chaos cells (magenta, crimson, purple) than this are adjacent
+    pub chaos_threshold: usize,
+
+    /// Chance per tick to die once over the chaos threshold
+    pub death_chance: f64,
+
+    /// Chance to spread into each empty neighbor per tick
+    pub spread_rate: f64,
+
+    /// Chance per tick to calm each adjacent chaos cell back to black
+    ///
+    /// 0.0 (default) keeps aqua purely reactive.
+    pub stabilize_rate: f64,
+}
+
+impl Default for AquaConfig {
+    fn default() -> Self {
+        AquaConfig {
+            chaos_threshold: 6,
+            death_chance: 0.5,
+            spread_rate: 0.12,
+            stabilize_rate: 0.0,
+        }
+    }
+}
+
+/// Reproduction parameters (see `genetics::check_reproduction`)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ReproductionConfig {
+    /// Chance per cell per tick that a reproduction attempt is made
+    pub chance: f64,
+
+    /// How far a cell looks for a compatible partner
+    ///
+    /// Larger ranges find more partners but cost (2r+1)^2 lookups per
+    /// attempt. Clamped to `grid::BOUNDARY_RADIUS`.
+    pub partner_radius: u32,
+
+    /// How far from the parent offspring may be placed
+    ///
+    /// Clamped to `grid::BOUNDARY_RADIUS`.
+    pub offspring_radius: u32,
+}
+
+impl Default for ReproductionConfig {
+    fn default() -> Self {
+        ReproductionConfig {
+            chance: 1.0 / 100_000_000.0,
+            partner_radius: 2,
+            offspring_radius: 1,
+        }
+    }
+}
+
+/// Lime (symbiote) parameters
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct LimeConfig {
+    /// Type lime depends on
+    pub host: CellType,
+
+    /// Lime dies when no host is within this radius
+    ///
+    /// Clamped to `grid::BOUNDARY_RADIUS`.
+    pub dependency_radius: u32,
+
+    /// Chance per tick to seed the host into each empty cell next to both
+    /// the lime and an adjacent host (0.0 = lime gives nothing back)
+    pub host_spread_bonus: f64,
+}
+
+impl Default for LimeConfig {
+    fn default() -> Self {
+        LimeConfig {
+            host: CellType::Green,
+            dependency_radius: 5,
+            host_spread_bonus: 0.0,
+        }
+    }
+}
+
+/// Teal (moisture) parameters
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TealConfig {
+    /// Age (ticks) after which teal can dry up
+    pub lifespan: u8,
+
+    /// Chance per tick that teal past its lifespan dries up
+    pub evaporation_chance: f64,
+
+    /// Chance to spread into each empty neighbor per tick
+    pub spread_rate: f64,
+}
+
+impl Default for TealConfig {
+    fn default() -> Self {
+        TealConfig {
+            lifespan: 12,
+            evaporation_chance: 0.2,
+            spread_rate: 0.05,
+        }
+    }
+}
+
+/// Shared moisture effect of the water family (blue, teal, navy)
+///
+/// Green with any water-family cell within `moisture_radius` gets
+/// `moisture_boost` added to its spread rate, on top of blue's own
+/// adjacent boost. Off by default.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct WaterConfig {
+    /// How far moisture reaches (0 = disabled). Clamped to `grid::BOUNDARY_RADIUS`.
+    pub moisture_radius: u32,
+
+    /// Added to green's spread rate when moist (0.0-1.0)
+    pub moisture_boost: f64,
+
+    /// Upper bound on a moisture-boosted green spread rate
+    pub max_moist_rate: f64,
+}
+
+impl Default for WaterConfig {
+    fn default() -> Self {
+        WaterConfig {
+            moisture_radius: 0,
+            moisture_boost: 0.0,
+            max_moist_rate: 0.90,
+        }
+    }
+}
+
+/// Predator (crimson, maroon) parameters
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PredatorConfig {
+    /// Ticks crimson survives with no prey in range
+    pub crimson_starvation_ticks: u8,
+
+    /// Ticks maroon survives with no prey in range
+    pub maroon_starvation_ticks: u8,
+
+    /// Chance per tick crimson kills each adjacent prey cell
+    pub crimson_kill_rate: f64,
+
+    /// Chance per tick maroon kills each adjacent prey cell
+    pub maroon_kill_rate: f64,
+}
+
+impl Default for PredatorConfig {
+    fn default() -> Self {
+        PredatorConfig {
+            crimson_starvation_ticks: 3,
+            maroon_starvation_ticks: 2,
+            crimson_kill_rate: 0.9,
+            maroon_kill_rate: 0.9,
+        }
+    }
+}
+
+/// Pink (parasite) parameters
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PinkConfig {
+    /// Type pink parasitizes
+    pub host: CellType,
+
+    /// Pink dies when no host is within this radius
+    ///
+    /// Clamped to `grid::BOUNDARY_RADIUS`.
+    pub host_radius: u32,
+
+    /// Chance per tick to infect each adjacent host
+    pub infection_rate: f64,
+
+    /// true: an infected host becomes pink. false: it stays itself but
+    /// loses `harm` vitality, dying once vitality runs out.
+    pub converts_host: bool,
+
+    /// Vitality drained per infection when `converts_host` is false
+    pub harm: f64,
+}
+
+impl Default for PinkConfig {
+    fn default() -> Self {
+        PinkConfig {
+            host: CellType::Orange,
+            host_radius: 5,
+            infection_rate: 0.15,
+            converts_host: true,
+            harm: 0.25,
+        }
+    }
+}
+
+/// Smoke (toxic) parameters
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SmokeConfig {
+    /// Chance to spread into each neighbor per tick
+    pub spread_rate: f64,
+
+    /// Age (ticks) after which smoke can dissipate
+    pub lifespan: u8,
+
+    /// Chance per tick that smoke past its lifespan clears (0.0 = never)
+    pub dissipation_rate: f64,
+
+    /// Type that emits smoke into adjacent empty cells (None = no source)
+    pub source: Option<CellType>,
+
+    /// Chance per tick for a source to emit into each empty neighbor
+    pub emission_rate: f64,
+}
+
+impl Default for SmokeConfig {
+    fn default() -> Self {
+        SmokeConfig {
+            spread_rate: 0.25,
+            lifespan: 0,
+            dissipation_rate: 0.0,
+            source: None,
+            emission_rate: 0.05,
+        }
+    }
+}
+
+/// Violet (void) parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VioletConfig {
+    /// Chance per tick to erase each adjacent non-immune cell
+    pub destruction_rate: f64,
+
+    /// Types the void cannot erase (and that contain it)
+    pub immune: Vec<CellType>,
+
+    /// Violet can collapse once more than this many neighbors are immune
+    pub containment_threshold: usize,
+
+    /// Chance per tick that a contained violet collapses
+    pub containment_death_chance: f64,
+}
+
+impl Default for VioletConfig {
+    fn default() -> Self {
+        VioletConfig {
+            destruction_rate: 0.08,
+            immune: vec![CellType::Pearl, CellType::White, CellType::Indigo],
+            containment_threshold: 4,
+            containment_death_chance: 0.5,
+        }
+    }
+}
+
+/// Indigo (mineral) parameters
+///
+/// Indigo is inert by default; with a release rate it slowly breaks down
+/// into cyan nutrient.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct IndigoConfig {
+    /// Chance per tick that indigo releases its minerals and becomes cyan
+    pub nutrient_release_rate: f64,
+}
+
+/// Slate (bacteria) parameters
+///
+/// Slate is invisible and inert by default; with a decompose rate it
+/// breaks down adjacent dying cells.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SlateConfig {
+    /// Chance per tick to decompose each adjacent `decomposes` cell (0 = off)
+    pub decompose_rate: f64,
+
+    /// Cell type slate breaks down
+    pub decomposes: CellType,
+
+    /// What a decomposed cell turns into
+    pub decomposes_into: CellType,
+}
+
+impl Default for SlateConfig {
+    fn default() -> Self {
+        SlateConfig {
+            decompose_rate: 0.0,
+            decomposes: CellType::Gray,
+            decomposes_into: CellType::Cyan,
+        }
+    }
+}
+
+/// Green (plant) parameters
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct GreenConfig {
+    /// Base chance per tick to seed each empty neighbor, before crowding
+    /// and the water/light/smoke modifiers
+    pub spread_rate: f64,
+}
+
+impl Default for GreenConfig {
+    fn default() -> Self {
+        GreenConfig { spread_rate: 0.40 }
+    }
+}
+
+/// Purple (plague) parameters
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PurpleConfig {
+    /// Chance per tick to infect each adjacent orange or gray
+    pub spread_rate: f64,
+}
+
+impl Default for PurpleConfig {
+    fn default() -> Self {
+        PurpleConfig { spread_rate: 0.30 }
+    }
+}
+
+/// Orange (herbivore) parameters
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct OrangeConfig {
+    /// Radius scanned for green food (clamped to `BOUNDARY_RADIUS`)
+    pub survival_radius: u32,
+
+    /// Orange with fewer green in range than this turns gray
+    pub survival_threshold: usize,
+}
+
+impl Default for OrangeConfig {
+    fn default() -> Self {
+        OrangeConfig {
+            survival_radius: 5,
+            survival_threshold: 3,
+        }
+    }
+}
+
+/// Gray (weakened) parameters
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct GrayConfig {
+    /// Age from which gray may decay back to black
+    pub decay_age: u8,
+
+    /// Chance per tick that gray at `decay_age` or older decays
+    pub decay_chance: f64,
+}
+
+impl Default for GrayConfig {
+    fn default() -> Self {
+        GrayConfig {
+            decay_age: 4,
+            decay_chance: 0.5,
+        }
+    }
+}
+
+/// How simulated time maps onto ticks
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct TimeConfig {
+    /// Simulated time per tick; None (default) is the classic discrete mode
+    ///
+    /// When set, rule probabilities are treated as per-unit-time rates and
+    /// ages advance by `delta_time`, so running at `delta_time = 2` for N
+    /// ticks approximates `delta_time = 1` for 2N ticks. Use this to change
+    /// playback speed without changing the biology.
+    pub delta_time: Option<f64>,
+}
+
+/// Per-type on/off switches for rules
+///
+/// Serialized as the list of disabled types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "Vec<CellType>", into = "Vec<CellType>")]
+pub struct TypeMask([bool; CellType::COUNT]);
+
+impl TypeMask {
+    pub fn contains(&self, cell_type: CellType) -> bool {
+        self.0[cell_type.to_u8() as usize]
+    }
+
+    pub fn set(&mut self, cell_type: CellType, on: bool) {
+        self.0[cell_type.to_u8() as usize] = on;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.0.contains(&true)
+    }
+}
+
+impl Default for TypeMask {
+    fn default() -> Self {
+        TypeMask([false; CellType::COUNT])
+    }
+}
+
+impl From<Vec<CellType>> for TypeMask {
+    fn from(types: Vec<CellType>) -> Self {
+        let mut mask = TypeMask::default();
+        for cell_type in types {
+            mask.set(cell_type, true);
+        }
+        mask
+    }
+}
+
+impl From<TypeMask> for Vec<CellType> {
+    fn from(mask: TypeMask) -> Self {
+        CellType::all().filter(|&t| mask.contains(t)).collect()
+    }
+}
+
+/// All tunable rule parameters, grouped by cell family
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RuleConfig {
+    pub yellow: YellowConfig,
+    pub silver: SilverConfig,
+    pub blue: BlueConfig,
+    pub navy: NavyConfig,
+    pub behavioral: BehavioralConfig,
+    pub mint: MintConfig,
+    pub gold: GoldConfig,
+    pub peach: PeachConfig,
+    pub aqua: AquaConfig,
+    pub reproduction: ReproductionConfig,
+    pub lime: LimeConfig,
+    pub teal: TealConfig,
+    pub water: WaterConfig,
+    pub herbivore: HerbivoreConfig,
+    pub predator: PredatorConfig,
+    pub pink: PinkConfig,
+    pub smoke: SmokeConfig,
+    pub violet: VioletConfig,
+    pub indigo: IndigoConfig,
+    pub slate: SlateConfig,
+    pub green: GreenConfig,
+    pub purple: PurpleConfig,
+    pub orange: OrangeConfig,
+    pub gray: GrayConfig,
+    pub time: TimeConfig,
+    /// Neighbors that spread rules and neighbor counts look at
+    pub neighborhood: Neighborhood,
+    /// Types frozen in place: they keep their cell but run no rules
+    pub disabled: TypeMask,
+}
@@ -0,0 +1,182 @@
+//! Ecosystem Events
+//!
+//! Turns a series of `EcosystemStats` samples into a timeline of notable
+//! transitions: a type dying out, a type taking over a large share of the
+//! grid, and the whole ecosystem collapsing or coming back. Events fire on
+//! the sample where the transition is first seen, so the timeline is only as
+//! fine as the sampling.
+
+use crate::cell::CellType;
+use crate::stats::EcosystemStats;
+
+/// Thresholds for `EventLogger`
+#[derive(Debug, Clone, Copy)]
+pub struct EventConfig {
+    /// Share of the grid (0.0-1.0) a non-Black type must exceed to count as an outbreak
+    pub outbreak_fraction: f64,
+
+    /// Health score at or below which the ecosystem has collapsed
+    pub collapse_health: f64,
+
+    /// Health score a collapsed ecosystem must reach again to count as recovered
+    pub recovery_health: f64,
+}
+
+impl Default for EventConfig {
+    fn default() -> Self {
+        EventConfig {
+            outbreak_fraction: 0.30,
+            collapse_health: 0.1,
+            recovery_health: 0.3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EcosystemEvent {
+    /// The last cell of a type disappeared
+    Extinction { cell_type: CellType },
+    /// A type rose above `outbreak_fraction` of the grid
+    Outbreak { cell_type: CellType, fraction: f64 },
+    /// Health dropped to `collapse_health` or below
+    Collapse,
+    /// Health climbed back to `recovery_health` after a collapse
+    Recovery,
+}
+
+/// An event and the tick of the sample it was seen in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoggedEvent {
+    pub tick: u64,
+    pub event: EcosystemEvent,
+}
+
+/// Watches successive stats samples and records transitions
+///
+/// The first sample only sets the baseline. An outbreak is reported once
+/// per rise above the threshold and re-arms when the type falls back below
+/// it; collapse and recovery alternate.
+#[derive(Debug, Clone)]
+pub struct EventLogger {
+    pub config: EventConfig,
+    previous: Option<[u32; CellType::COUNT]>,
+    outbreaks: [bool; CellType::COUNT],
+    collapsed: bool,
+    events: Vec<LoggedEvent>,
+}
+
+impl Default for EventLogger {
+    fn default() -> Self {
+        Self::new(EventConfig::default())
+    }
+}
+
+impl EventLogger {
+    pub fn new(config: EventConfig) -> Self {
+        EventLogger {
+            config,
+            previous: None,
+            outbreaks: [false; CellType::COUNT],
+            collapsed: false,
+            events: Vec::new(),
+        }
+    }
+
+    /// Feed the stats sampled at `tick`; returns the events it triggered
+    pub fn observe(&mut self, tick: u64, stats: &EcosystemStats) -> &[LoggedEvent] {
+        let first_new = self.events.len();
+        let mut counts = [0; CellType::COUNT];
+        for cell_type in CellType::all() {
+            counts[cell_type.to_u8() as usize] = stats.populations.get(cell_type.as_name()).copied().unwrap_or(0);
+        }
+        let total = counts.iter().sum::<u32>().max(1) as f64;
+
+        for cell_type in CellType::all().filter(|&t| t != CellType::Black) {
+            let i = cell_type.to_u8() as usize;
+            if self.previous.is_some_and(|previous| previous[i] > 0) && counts[i] == 0 {
+                self.push(tick, EcosystemEvent::Extinction { cell_type });
+            }
+
+            let fraction = counts[i] as f64 / total;
+            let above = fraction > self.config.outbreak_fraction;
+            if above && !self.outbreaks[i] && self.previous.is_some() {
+                self.push(tick, EcosystemEvent::Outbreak { cell_type, fraction });
+            }
+            self.outbreaks[i] = above;
+        }
+
+        if !self.collapsed && stats.health_score <= self.config.collapse_health {
+            self.collapsed = true;
+            if self.previous.is_some() {
+                self.push(tick, EcosystemEvent::Collapse);
+            }
+        } else if self.collapsed && stats.health_score >= self.config.recovery_health {
+            self.collapsed = false;
+            self.push(tick, EcosystemEvent::Recovery);
+        }
+
+        self.previous = Some(counts);
+        &self.events[first_new..]
+    }
+
+    /// Every event so far, oldest first
+    pub fn events(&self) -> &[LoggedEvent] {
+        &self.events
+    }
+
+    fn push(&mut self, tick: u64, event: EcosystemEvent) {
+        self.events.push(LoggedEvent { tick, event });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn sample(green: u32, purple: u32, health_score: f64) -> EcosystemStats {
+        let populations: HashMap<String, u32> = [("Green", green), ("Purple", purple), ("Black", 100 - green - purple)]
+            .into_iter()
+            .map(|(name, count)| (name.to_string(), count))
+            .collect();
+        EcosystemStats {
+            populations,
+            health_score,
+            green_coverage: green as f64,
+            orange_population: 0,
+            predator_count: 0,
+            predator_category_count: 0,
+            disease_pressure: 0.0,
+            diversity_index: 0.0,
+            stability: 0.0,
+            max_generation: 0,
+            avg_generation: 0.0,
+            temporal_stability: None,
+        }
+    }
+
+    #[test]
+    fn test_green_dying_out_logs_extinction() {
+        let mut logger = EventLogger::new(EventConfig::default());
+        assert!(logger.observe(0, &sample(40, 0, 0.6)).is_empty());
+        assert!(logger.observe(10, &sample(12, 20, 0.4)).is_empty());
+
+        let new = logger.observe(20, &sample(0, 35, 0.05)).to_vec();
+        assert_eq!(
+            new,
+            vec![
+                LoggedEvent { tick: 20, event: EcosystemEvent::Extinction { cell_type: CellType::Green } },
+                LoggedEvent { tick: 20, event: EcosystemEvent::Outbreak { cell_type: CellType::Purple, fraction: 0.35 } },
+                LoggedEvent { tick: 20, event: EcosystemEvent::Collapse },
+            ]
+        );
+
+        // Still gone and still above the threshold: nothing new
+        assert!(logger.observe(30, &sample(0, 40, 0.05)).is_empty());
+        assert_eq!(
+            logger.observe(40, &sample(30, 5, 0.5)),
+            &[LoggedEvent { tick: 40, event: EcosystemEvent::Recovery }]
+        );
+        assert_eq!(logger.events().len(), 4);
+    }
+}
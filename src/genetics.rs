@@ -1,42 +1,56 @@
 use crate::cell::{Cell, CellType, Genes};
-use crate::grid::Grid;
+use crate::config::RuleConfig;
+use crate::grid::{Grid, BOUNDARY_RADIUS};
 use rand::Rng;
 
-const REPRODUCTION_CHANCE: f64 = 1.0 / 100_000_000.0; // 1 in 100 million
-
 /// Check for reproduction between nearby cells
-pub fn check_reproduction(grid: &mut Grid, x: u32, y: u32, rng: &mut impl Rng) {
-    // Very low chance of reproduction trigger
-    if rng.gen::<f64>() > REPRODUCTION_CHANCE {
-        return;
+///
+/// Returns where the offspring was written (in the next buffer), or None if
+/// no attempt was made or no partner and empty spot were found. The attempt
+/// rate is `config.reproduction.chance`, tiny by default; raise it to make
+/// births observable.
+pub fn check_reproduction(
+    grid: &mut Grid,
+    x: u32,
+    y: u32,
+    config: &RuleConfig,
+    rng: &mut impl Rng,
+) -> Option<(u32, u32)> {
+    let reproduction = &config.reproduction;
+
+    // Very low chance of reproduction trigger (1 in 100 million by default)
+    if rng.gen::<f64>() > reproduction.chance {
+        return None;
     }
 
-    // Check neighbors for compatible reproduction
-    for dy in -2..=2i32 {
-        for dx in -2..=2i32 {
-            if dx == 0 && dy == 0 {
-                continue;
-            }
-
-            let nx = (x as i32 + dx) as u32;
-            let ny = (y as i32 + dy) as u32;
-
-            if let Some(parent2) = grid.get_cell(nx, ny) {
-                if let Some(parent1) = grid.get_cell(x, y) {
-                    if can_reproduce(&parent1, &parent2) {
-                        // Attempt reproduction
-                        let offspring = create_offspring(&parent1, &parent2, rng);
-                        
-                        // Place offspring in random adjacent empty cell
-                        if let Some((ox, oy)) = find_empty_neighbor(grid, x, y, rng) {
-                            grid.set_next_cell(ox, oy, offspring);
-                            return;
-                        }
-                    }
-                }
-            }
-        }
+    let partner_radius = reproduction.partner_radius.min(BOUNDARY_RADIUS);
+    let offspring_radius = reproduction.offspring_radius.min(BOUNDARY_RADIUS);
+
+    let parent1 = grid.get_cell(x, y)?;
+    // Every compatible partner in range is equally likely, whatever its position
+    let partners: Vec<Cell> = window(grid, x, y, partner_radius)
+        .filter_map(|(nx, ny)| grid.get_cell(nx, ny))
+        .filter(|parent2| can_reproduce(&parent1, parent2))
+        .collect();
+    if partners.is_empty() {
+        return None;
     }
+    let parent2 = &partners[rng.gen_range(0..partners.len())];
+
+    // Place offspring in random nearby empty cell
+    let (ox, oy) = find_empty_neighbor(grid, x, y, offspring_radius, rng)?;
+    let offspring = create_offspring(&parent1, parent2, rng);
+    grid.set_next_cell(ox, oy, offspring);
+    Some((ox, oy))
+}
+
+/// Coordinates within `radius` of (x, y) on each axis, excluding (x, y) itself
+fn window(grid: &Grid, x: u32, y: u32, radius: u32) -> impl Iterator<Item = (u32, u32)> + '_ {
+    let r = radius as i32;
+    (-r..=r)
+        .flat_map(move |dy| (-r..=r).map(move |dx| (dx, dy)))
+        .filter(|&offset| offset != (0, 0))
+        .filter_map(move |(dx, dy)| grid.neighbor_coord(x, y, dx, dy))
 }
 
 fn can_reproduce(parent1: &Cell, parent2: &Cell) -> bool {
@@ -90,7 +104,7 @@ fn are_compatible_types(type1: CellType, type2: CellType) -> bool {
 
 fn create_offspring(parent1: &Cell, parent2: &Cell, rng: &mut impl Rng) -> Cell {
     // Blend genes from both parents
-    let mut genes = Genes::blend(&parent1.genes, &parent2.genes);
+    let mut genes = Genes::blend_with_rng(&parent1.genes, &parent2.genes, rng);
     genes.clamp();
 
     // Choose cell type: usually one of the parents, sometimes blended
@@ -106,10 +120,10 @@ fn create_offspring(parent1: &Cell, parent2: &Cell, rng: &mut impl Rng) -> Cell
         parent1.cell_type
     };
 
-    let mut offspring = Cell::with_genes(offspring_type, genes);
+    let mut offspring = Cell::with_genes_and_rng(offspring_type, genes, rng);
     
     // Offspring starts at age 0 with parents' traits
-    offspring.genes.generation = parent1.genes.generation.max(parent2.genes.generation) + 1;
+    offspring.genes.generation = parent1.genes.generation.max(parent2.genes.generation).saturating_add(1);
     offspring.genes.parent_types = (
         parent1.cell_type.to_u8(),
         parent2.cell_type.to_u8(),
@@ -118,25 +132,10 @@ fn create_offspring(parent1: &Cell, parent2: &Cell, rng: &mut impl Rng) -> Cell
     offspring
 }
 
-fn find_empty_neighbor(grid: &Grid, x: u32, y: u32, rng: &mut impl Rng) -> Option<(u32, u32)> {
-    let mut candidates = Vec::new();
-
-    for dy in -1..=1i32 {
-        for dx in -1..=1i32 {
-            if dx == 0 && dy == 0 {
-                continue;
-            }
-
-            let nx = (x as i32 + dx) as u32;
-            let ny = (y as i32 + dy) as u32;
-
-            if let Some(cell) = grid.get_cell(nx, ny) {
-                if cell.cell_type == CellType::Black {
-                    candidates.push((nx, ny));
-                }
-            }
-        }
-    }
+fn find_empty_neighbor(grid: &Grid, x: u32, y: u32, radius: u32, rng: &mut impl Rng) -> Option<(u32, u32)> {
+    let candidates: Vec<(u32, u32)> = window(grid, x, y, radius)
+        .filter(|&(nx, ny)| grid.get_cell(nx, ny).is_some_and(|cell| cell.cell_type == grid.empty_type()))
+        .collect();
 
     if candidates.is_empty() {
         None
@@ -189,4 +188,85 @@ mod tests {
         assert!(blended.aggression >= 0.0 && blended.aggression <= 1.0);
         assert_eq!(blended.generation, 1);
     }
+
+    #[test]
+    fn test_offspring_generation_saturates() {
+        use rand::SeedableRng;
+
+        let mut elder = Cell::new(CellType::Green);
+        elder.genes.generation = u8::MAX;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+        let offspring = create_offspring(&elder, &elder.clone(), &mut rng);
+        assert_eq!(offspring.genes.generation, u8::MAX);
+    }
+
+    #[test]
+    fn test_partner_radius_limits_mating_range() {
+        let offspring_placed = |partner_radius: u32| {
+            let mut config = RuleConfig::default();
+            config.reproduction.chance = 1.0;
+            config.reproduction.partner_radius = partner_radius;
+
+            // Two greens two cells apart, nothing else around
+            let mut grid = Grid::new(12, 12);
+            grid.set_cell(5, 5, CellType::Green);
+            grid.set_cell(7, 5, CellType::Green);
+            check_reproduction(&mut grid, 5, 5, &config, &mut rand::thread_rng());
+
+            (4..=6)
+                .flat_map(|y| (4..=6).map(move |x| (x, y)))
+                .any(|(x, y)| grid.get_next_cell(x, y).unwrap().cell_type == CellType::Green)
+        };
+
+        assert!(!offspring_placed(1));
+        assert!(offspring_placed(2));
+    }
+
+    #[test]
+    fn test_certain_reproduction_places_offspring_next_to_parent() {
+        let mut config = RuleConfig::default();
+        config.reproduction.chance = 1.0;
+
+        let mut grid = Grid::new(10, 10);
+        grid.set_cell(4, 4, CellType::Green);
+        grid.set_cell(5, 4, CellType::Green);
+        let (ox, oy) = check_reproduction(&mut grid, 4, 4, &config, &mut rand::thread_rng())
+            .expect("adjacent greens should breed");
+
+        assert!(ox.abs_diff(4) <= 1 && oy.abs_diff(4) <= 1);
+        assert_eq!(grid.get_cell(ox, oy).unwrap().cell_type, CellType::Black);
+        let offspring = grid.get_next_cell(ox, oy).unwrap();
+        assert_eq!(offspring.cell_type, CellType::Green);
+        assert_eq!(offspring.genes.generation, 1);
+
+        // At zero chance no attempt is made
+        config.reproduction.chance = 0.0;
+        assert_eq!(check_reproduction(&mut grid, 4, 4, &config, &mut rand::thread_rng()), None);
+    }
+
+    #[test]
+    fn test_single_partner_gets_single_offspring() {
+        let mut config = RuleConfig::default();
+        config.reproduction.chance = 1.0;
+
+        // One compatible Green beside the parent, one incompatible Orange
+        let mut grid = Grid::new(9, 9);
+        grid.set_cell(4, 4, CellType::Green);
+        grid.set_cell(5, 4, CellType::Green);
+        grid.set_cell(3, 4, CellType::Orange);
+        grid.carry_cells_forward();
+        let mut rng = crate::testutil::SequenceRng::new(&[0.0]);
+        let placed = check_reproduction(&mut grid, 4, 4, &config, &mut rng);
+
+        // The first empty spot in scan order, from an all-zero draw
+        assert_eq!(placed, Some((3, 3)));
+        let changed: Vec<(u32, u32)> = (0..9)
+            .flat_map(|y| (0..9).map(move |x| (x, y)))
+            .filter(|&(x, y)| grid.get_next_cell(x, y).unwrap().cell_type != grid.get_cell(x, y).unwrap().cell_type)
+            .collect();
+        assert_eq!(changed, vec![(3, 3)]);
+        let offspring = grid.get_next_cell(3, 3).unwrap();
+        assert_eq!(offspring.cell_type, CellType::Green);
+        assert_eq!(offspring.genes.parent_types, (CellType::Green.to_u8(), CellType::Green.to_u8()));
+    }
 }
@@ -1,93 +1,143 @@
-use crate::cell::{Cell, CellType};
+use crate::cell::{Cell, CellType, Genes};
+use crate::config::Neighborhood;
+use crate::storage::{CellStorage, SparseStorage, VecStorage};
+use crate::wire::{rle_decode, rle_encode};
+use rand::distributions::{Distribution, WeightedIndex};
 use rand::Rng;
 use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
 
 pub struct Grid {
     pub width: u32,
     pub height: u32,
-    cells: Vec<Cell>,
-    next_cells: Vec<Cell>,
+    /// Toroidal topology: neighbor lookups past one edge continue from the
+    /// opposite edge instead of being dropped
+    pub wrap: bool,
+    // The type that stands for "nothing here"; see `set_empty_type`
+    empty_type: CellType,
+    cells: Box<dyn CellStorage>,
+    next_cells: Box<dyn CellStorage>,
     // Triple buffer: stable read state for chunk boundaries
-    boundary_buffer: Vec<Cell>,
+    boundary_buffer: Box<dyn CellStorage>,
+    // Per-type counts for `cells` and `next_cells`, kept in step by every
+    // write to either buffer and exchanged on swap
+    population: [u32; CellType::COUNT],
+    next_population: [u32; CellType::COUNT],
+    // Chunks touched by spawn/kill since the last `take_dirty_chunks`
+    dirty_chunks: Vec<bool>,
+    // Chunks holding at least one non-empty cell in `cells`; the rest are skipped by ticks
+    active_chunks: Vec<bool>,
+    // Chunks where `next_cells` may differ from `cells`; see `carry_cells_forward`
+    stale_chunks: Vec<bool>,
+    // Chunks whose boundary region was copied since the last layer reset
+    #[cfg(debug_assertions)]
+    copied_chunks: Vec<bool>,
 }
 
 // Chunk configuration for batched processing
 pub const CHUNK_SIZE: u32 = 32;
-pub const BOUNDARY_RADIUS: u32 = 6;  // Radius for neighbor lookups (max interaction distance)
+pub const BOUNDARY_RADIUS: u32 = 10;  // Radius for neighbor lookups (max interaction distance, silver threat scan)
+// Boundary regions may only reach into adjacent chunks; the layer scheme and
+// the isolated-read checks both rely on it
+const _: () = assert!(BOUNDARY_RADIUS < CHUNK_SIZE);
 
 impl Grid {
     pub fn new(width: u32, height: u32) -> Self {
+        Self::with_storage(width, height, VecStorage::new)
+    }
+
+    /// A grid on `SparseStorage`, for mostly-Black simulations
+    ///
+    /// Memory scales with the number of non-Black cells instead of the grid
+    /// area; lookups cost a hash probe each, so dense grids are slower.
+    pub fn new_sparse(width: u32, height: u32) -> Self {
+        Self::with_storage(width, height, |len| SparseStorage::new(width, len))
+    }
+
+    /// A grid whose edges wrap around when `wrap` is true
+    pub fn with_topology(width: u32, height: u32, wrap: bool) -> Self {
+        let mut grid = Self::new(width, height);
+        grid.wrap = wrap;
+        grid
+    }
+
+    /// Build a grid on a custom storage backend
+    ///
+    /// `make(len)` is called once per buffer and must return storage holding
+    /// `len` Black cells.
+    pub fn with_storage<S, F>(width: u32, height: u32, make: F) -> Self
+    where
+        S: CellStorage + 'static,
+        F: Fn(usize) -> S,
+    {
         let size = (width * height) as usize;
+        let chunks = (width.div_ceil(CHUNK_SIZE) * height.div_ceil(CHUNK_SIZE)) as usize;
+        let mut population = [0; CellType::COUNT];
+        population[CellType::Black.to_u8() as usize] = size as u32;
         Grid {
             width,
             height,
-            cells: vec![Cell::new(CellType::Black); size],
-            next_cells: vec![Cell::new(CellType::Black); size],
-            boundary_buffer: vec![Cell::new(CellType::Black); size],
+            wrap: false,
+            empty_type: CellType::Black,
+            cells: Box::new(make(size)),
+            next_cells: Box::new(make(size)),
+            boundary_buffer: Box::new(make(size)),
+            population,
+            next_population: population,
+            dirty_chunks: vec![false; chunks],
+            active_chunks: vec![false; chunks],
+            stale_chunks: vec![false; chunks],
+            #[cfg(debug_assertions)]
+            copied_chunks: vec![false; chunks],
         }
     }
 
     pub fn initialize_random(&mut self, densities: &serde_json::Map<String, serde_json::Value>) {
-        let mut rng = rand::thread_rng();
-        
-        // Start all cells as Black
-        self.cells.fill(Cell::new(CellType::Black));
-
-        let cell_types = [
-            ("Black", CellType::Black),
-            ("Green", CellType::Green),
-            ("Orange", CellType::Orange),
-            ("Gray", CellType::Gray),
-            ("Purple", CellType::Purple),
-            ("Red", CellType::Red),
-            ("White", CellType::White),
-            ("Blue", CellType::Blue),
-            ("Brown", CellType::Brown),
-            ("Tan", CellType::Tan),
-            ("Gold", CellType::Gold),
-            ("Lime", CellType::Lime),
-            ("Crimson", CellType::Crimson),
-            ("Maroon", CellType::Maroon),
-            ("Coral", CellType::Coral),
-            ("Pink", CellType::Pink),
-            ("Magenta", CellType::Magenta),
-            ("Cyan", CellType::Cyan),
-            ("Yellow", CellType::Yellow),
-            ("Teal", CellType::Teal),
-            ("Navy", CellType::Navy),
-            ("Olive", CellType::Olive),
-            ("Indigo", CellType::Indigo),
-            ("Khaki", CellType::Khaki),
-            ("Slate", CellType::Slate),
-            ("Rust", CellType::Rust),
-            ("Mint", CellType::Mint),
-            ("Peach", CellType::Peach),
-            ("Aqua", CellType::Aqua),
-            ("Silver", CellType::Silver),
-            ("Violet", CellType::Violet),
-            ("Amber", CellType::Amber),
-            ("Pearl", CellType::Pearl),
-            ("Smoke", CellType::Smoke),
-            ("Glint", CellType::Glint),
-            ("Tint", CellType::Tint),
-            ("Shade", CellType::Shade),
-        ];
+        self.initialize_random_with_rng(densities, &mut rand::thread_rng());
+    }
 
-        // Process each cell type and only update if it should be that type
-        for (name, cell_type) in cell_types.iter() {
-            if let Some(density_val) = densities.get(*name) {
-                if let Some(density) = density_val.as_f64() {
-                    let density = (density / 100.0).min(1.0).max(0.0);
-                    if density > 0.0 {
-                        for cell in self.cells.iter_mut() {
-                            if cell.cell_type == CellType::Black && rng.gen::<f64>() < density {
-                                *cell = Cell::new(*cell_type);
-                            }
-                        }
-                    }
+    /// Same as `initialize_random`, but draws placement and each placed
+    /// cell's `rng_seed` from the given RNG
+    ///
+    /// Densities are percentages treated as weights of one categorical
+    /// draw per cell, so realized shares match their ratios whatever order
+    /// the types come in. If `empty_type` has no entry it takes up whatever
+    /// the others leave of 100; all-zero weights fill the grid with it.
+    pub fn initialize_random_with_rng(
+        &mut self,
+        densities: &serde_json::Map<String, serde_json::Value>,
+        rng: &mut impl Rng,
+    ) {
+        let mut weights = [0.0; CellType::COUNT];
+        for cell_type in CellType::all() {
+            if let Some(density) = densities.get(cell_type.as_name()).and_then(|v| v.as_f64()) {
+                weights[cell_type.to_u8() as usize] = density.max(0.0);
+            }
+        }
+        let empty = self.empty_type;
+        if !densities.contains_key(empty.as_name()) {
+            weights[empty.to_u8() as usize] = (100.0 - weights.iter().sum::<f64>()).max(0.0);
+        }
+
+        let empty_cell = Cell::new_with_rng(empty, rng);
+        match WeightedIndex::new(weights) {
+            Ok(picker) => {
+                for idx in 0..self.cells.len() {
+                    let cell_type = CellType::from_u8(picker.sample(rng) as u8).expect("weight index is a type id");
+                    let cell = if cell_type == empty {
+                        empty_cell.clone()
+                    } else {
+                        Cell::new_with_rng(cell_type, rng)
+                    };
+                    self.cells.set(idx, cell);
                 }
             }
+            Err(_) => self.cells.fill(empty_cell),
         }
+        self.recount_population();
+        self.dirty_chunks.fill(true);
+        self.stale_chunks.fill(true);
     }
 
     pub fn get_cell(&self, x: u32, y: u32) -> Option<Cell> {
@@ -95,15 +145,256 @@ impl Grid {
             return None;
         }
         let idx = (y * self.width + x) as usize;
-        Some(self.cells[idx].clone())
+        Some(self.cells.get(idx).clone())
     }
 
     pub fn set_cell(&mut self, x: u32, y: u32, cell_type: CellType) {
+        if cell_type == self.empty_type {
+            self.kill(x, y);
+        } else {
+            self.spawn(x, y, cell_type);
+        }
+    }
+
+    /// Place a fresh cell, updating population counts and marking its chunk dirty
+    ///
+    /// Returns the type that was replaced, or None if (x, y) is out of bounds.
+    /// The new cell's `rng_seed` comes from thread_rng; seeded simulations
+    /// go through `spawn_with_rng`.
+    pub fn spawn(&mut self, x: u32, y: u32, cell_type: CellType) -> Option<CellType> {
+        self.spawn_with_rng(x, y, cell_type, &mut rand::thread_rng())
+    }
+
+    /// Same as `spawn`, but the new cell's `rng_seed` is drawn from `rng`
+    pub fn spawn_with_rng(&mut self, x: u32, y: u32, cell_type: CellType, rng: &mut impl Rng) -> Option<CellType> {
+        self.put_cell(x, y, Cell::new_with_rng(cell_type, rng))
+    }
+
+    /// Place an existing cell (genes, age and all) with the same bookkeeping as `spawn`
+    pub fn put_cell(&mut self, x: u32, y: u32, cell: Cell) -> Option<CellType> {
         if x >= self.width || y >= self.height {
-            return;
+            return None;
         }
         let idx = (y * self.width + x) as usize;
-        self.cells[idx] = Cell::new(cell_type);
+        let previous = self.cells.get(idx).cell_type;
+
+        self.population[previous.to_u8() as usize] -= 1;
+        self.population[cell.cell_type.to_u8() as usize] += 1;
+        let live = cell.cell_type != self.empty_type;
+        self.cells.set(idx, cell);
+
+        let chunk = self.chunk_index(x, y);
+        self.dirty_chunks[chunk] = true;
+        self.stale_chunks[chunk] = true;
+        // Kills leave the flag set; the next tick's carry clears it
+        self.active_chunks[chunk] |= live;
+        Some(previous)
+    }
+
+    /// Clear a cell to the empty type, with the same bookkeeping as `spawn`
+    pub fn kill(&mut self, x: u32, y: u32) -> Option<CellType> {
+        self.spawn(x, y, self.empty_type)
+    }
+
+    /// The type that stands for "nothing here" (Black by default)
+    ///
+    /// `kill` writes it, the rules treat it as inert and spread or die into
+    /// it, reproduction only places offspring on it, chunks holding nothing
+    /// else are skipped, and it is left out of `live_cells` and stats.
+    pub fn empty_type(&self) -> CellType {
+        self.empty_type
+    }
+
+    /// Make `empty_type` the grid's empty type
+    ///
+    /// Every cell of the previous empty type, in both buffers, is replaced
+    /// by a fresh `empty_type` cell, so the grid stays as empty as it was.
+    pub fn set_empty_type(&mut self, empty_type: CellType) {
+        let previous = std::mem::replace(&mut self.empty_type, empty_type);
+        if previous == empty_type {
+            return;
+        }
+        let empty = Cell::blank(empty_type);
+        for storage in [&mut self.cells, &mut self.next_cells, &mut self.boundary_buffer] {
+            for idx in 0..storage.len() {
+                if storage.get(idx).cell_type == previous {
+                    storage.set(idx, empty.clone());
+                }
+            }
+            storage.set_empty(empty.clone());
+        }
+        for population in [&mut self.population, &mut self.next_population] {
+            population[empty_type.to_u8() as usize] += std::mem::take(&mut population[previous.to_u8() as usize]);
+        }
+        self.recount_active_chunks();
+        self.dirty_chunks.fill(true);
+    }
+
+    /// Fill the half-open rect [x0, x1) x [y0, y1), clipped to the grid
+    ///
+    /// An empty or inverted rect changes nothing.
+    pub fn fill_rect(&mut self, x0: u32, y0: u32, x1: u32, y1: u32, cell_type: CellType) {
+        self.fill_rect_with_rng(x0, y0, x1, y1, cell_type, &mut rand::thread_rng());
+    }
+
+    /// Same as `fill_rect`, drawing each new cell's `rng_seed` from `rng`
+    pub fn fill_rect_with_rng(&mut self, x0: u32, y0: u32, x1: u32, y1: u32, cell_type: CellType, rng: &mut impl Rng) {
+        for y in y0..y1.min(self.height) {
+            for x in x0..x1.min(self.width) {
+                self.spawn_with_rng(x, y, cell_type, rng);
+            }
+        }
+    }
+
+    /// Fill every cell within Euclidean `radius` of (cx, cy), clipped to the grid
+    pub fn fill_circle(&mut self, cx: u32, cy: u32, radius: u32, cell_type: CellType) {
+        self.fill_circle_with_rng(cx, cy, radius, cell_type, &mut rand::thread_rng());
+    }
+
+    /// Same as `fill_circle`, drawing each new cell's `rng_seed` from `rng`
+    pub fn fill_circle_with_rng(&mut self, cx: u32, cy: u32, radius: u32, cell_type: CellType, rng: &mut impl Rng) {
+        for (x, y) in self.disc(cx, cy, radius) {
+            self.spawn_with_rng(x, y, cell_type, rng);
+        }
+    }
+
+    /// Paint `pattern` (rows of cells) with its top-left corner at (x, y)
+    ///
+    /// `None` entries leave the cell underneath unchanged; anything falling
+    /// off the grid is dropped.
+    pub fn stamp(&mut self, x: u32, y: u32, pattern: &[Vec<Option<CellType>>]) {
+        for (dy, row) in pattern.iter().enumerate() {
+            for (dx, cell_type) in row.iter().enumerate() {
+                if let Some(cell_type) = *cell_type {
+                    self.set_cell(x.saturating_add(dx as u32), y.saturating_add(dy as u32), cell_type);
+                }
+            }
+        }
+    }
+
+    /// In-grid coordinates within Euclidean `radius` of (x, y)
+    pub(crate) fn disc(&self, x: u32, y: u32, radius: u32) -> Vec<(u32, u32)> {
+        let r2 = (radius as u64).pow(2);
+        let x_range = x.saturating_sub(radius)..x.saturating_add(radius).saturating_add(1).min(self.width);
+        let y_range = y.saturating_sub(radius)..y.saturating_add(radius).saturating_add(1).min(self.height);
+        y_range
+            .flat_map(|cy| x_range.clone().map(move |cx| (cx, cy)))
+            .filter(|&(cx, cy)| (x.abs_diff(cx) as u64).pow(2) + (y.abs_diff(cy) as u64).pow(2) <= r2)
+            .collect()
+    }
+
+    /// The grid split into `tile_size` squares, row-major
+    ///
+    /// Edge tiles are clipped to the grid. Tiles don't overlap and together
+    /// cover every cell once, so per-tile results can be combined in any
+    /// order (e.g. from a rayon `par_iter`).
+    pub fn tiles(&self, tile_size: u32) -> impl Iterator<Item = TileView<'_>> {
+        let tile_size = tile_size.max(1);
+        let tiles_x = self.width.div_ceil(tile_size);
+        (0..self.height.div_ceil(tile_size))
+            .flat_map(move |ty| (0..tiles_x).map(move |tx| (tx, ty)))
+            .map(move |(tx, ty)| {
+                let (x0, y0) = (tx * tile_size, ty * tile_size);
+                self.tile(x0..x0 + tile_size, y0..y0 + tile_size)
+            })
+    }
+
+    /// A view of the cells in columns `x` and rows `y`, clipped to the grid
+    pub fn tile(&self, x: Range<u32>, y: Range<u32>) -> TileView<'_> {
+        let clip = |r: Range<u32>, len: u32| r.start.min(len)..r.end.min(len);
+        TileView { grid: self, x: clip(x, self.width), y: clip(y, self.height) }
+    }
+
+    /// Cells the current buffer holds in memory (see `CellStorage::stored_len`)
+    pub fn stored_cells(&self) -> usize {
+        self.cells.stored_len()
+    }
+
+    /// Current population of one type, without scanning the grid
+    pub fn population(&self, cell_type: CellType) -> u32 {
+        self.population[cell_type.to_u8() as usize]
+    }
+
+    /// Chunks (chunk_x, chunk_y) changed by spawn/kill since the last call, clearing the set
+    ///
+    /// Rule ticks don't mark chunks; this tracks external edits only.
+    pub fn take_dirty_chunks(&mut self) -> Vec<(u32, u32)> {
+        let chunks_x = self.width.div_ceil(CHUNK_SIZE).max(1);
+        let dirty = self
+            .dirty_chunks
+            .iter()
+            .enumerate()
+            .filter(|(_, &d)| d)
+            .map(|(i, _)| (i as u32 % chunks_x, i as u32 / chunks_x))
+            .collect();
+        self.dirty_chunks.fill(false);
+        dirty
+    }
+
+    /// Every cell in row-major order; feed back through `restore_cells`
+    pub(crate) fn snapshot_cells(&self) -> Vec<Cell> {
+        self.cells.iter().cloned().collect()
+    }
+
+    /// Replace every cell in row-major order, keeping the population cache in step
+    ///
+    /// `cells` must hold exactly width * height entries.
+    pub(crate) fn restore_cells(&mut self, cells: impl IntoIterator<Item = Cell>) {
+        let mut len = 0;
+        for (idx, cell) in cells.into_iter().enumerate() {
+            self.cells.set(idx, cell);
+            len += 1;
+        }
+        debug_assert_eq!(len, self.cells.len(), "restore_cells needs one cell per slot");
+        self.recount_population();
+        self.dirty_chunks.fill(true);
+        self.stale_chunks.fill(true);
+    }
+
+    fn recount_population(&mut self) {
+        let mut counts = [0; CellType::COUNT];
+        for cell in self.cells.iter() {
+            counts[cell.to_u8() as usize] += 1;
+        }
+        self.population = counts;
+        self.recount_active_chunks();
+    }
+
+    fn recount_active_chunks(&mut self) {
+        self.active_chunks.fill(false);
+        let width = self.width.max(1) as usize;
+        for (idx, _) in self.cells.iter_live(self.empty_type) {
+            let (x, y) = ((idx % width) as u32, (idx / width) as u32);
+            let chunk = self.chunk_index(x, y);
+            self.active_chunks[chunk] = true;
+        }
+    }
+
+    fn chunk_has_live_cells(&self, chunk: usize) -> bool {
+        let chunks_x = self.width.div_ceil(CHUNK_SIZE);
+        let (cx, cy) = (chunk as u32 % chunks_x, chunk as u32 / chunks_x);
+        (cy * CHUNK_SIZE..((cy + 1) * CHUNK_SIZE).min(self.height)).any(|y| {
+            (cx * CHUNK_SIZE..((cx + 1) * CHUNK_SIZE).min(self.width))
+                .any(|x| self.cells.get((y * self.width + x) as usize).cell_type != self.empty_type)
+        })
+    }
+
+    #[inline]
+    fn chunk_index(&self, x: u32, y: u32) -> usize {
+        ((y / CHUNK_SIZE) * self.width.div_ceil(CHUNK_SIZE) + x / CHUNK_SIZE) as usize
+    }
+
+    /// Whether chunk (chunk_x, chunk_y) may hold non-empty cells
+    ///
+    /// All-empty chunks do nothing in a tick (their cells only roll for
+    /// reproduction, which is effectively never), so ticks skip them. The
+    /// flag is exact after every tick and may over-report after kills.
+    pub fn is_chunk_active(&self, chunk_x: u32, chunk_y: u32) -> bool {
+        let chunks_x = self.width.div_ceil(CHUNK_SIZE);
+        self.active_chunks
+            .get((chunk_y * chunks_x + chunk_x) as usize)
+            .copied()
+            .unwrap_or(false)
     }
 
     pub fn get_next_cell(&self, x: u32, y: u32) -> Option<Cell> {
@@ -111,7 +402,7 @@ impl Grid {
             return None;
         }
         let idx = (y * self.width + x) as usize;
-        Some(self.next_cells[idx].clone())
+        Some(self.next_cells.get(idx).clone())
     }
 
     pub fn set_next_cell(&mut self, x: u32, y: u32, cell: Cell) {
@@ -119,31 +410,210 @@ impl Grid {
             return;
         }
         let idx = (y * self.width + x) as usize;
-        self.next_cells[idx] = cell;
+        self.write_next(idx, cell);
+        let chunk = self.chunk_index(x, y);
+        self.stale_chunks[chunk] = true;
+    }
+
+    /// Overwrite `next_cells[idx]`, keeping `next_population` in step
+    #[inline]
+    fn write_next(&mut self, idx: usize, cell: Cell) {
+        let previous = self.next_cells.get(idx).cell_type;
+        self.next_population[previous.to_u8() as usize] -= 1;
+        self.next_population[cell.cell_type.to_u8() as usize] += 1;
+        self.next_cells.set(idx, cell);
+    }
+
+    /// Start a tick with `next_cells` equal to `cells`
+    ///
+    /// Rules only write the cells they change, so anything left alone must
+    /// already hold its current value. Only chunks written since they were
+    /// last in step (by the previous tick's rules or by edits) are copied;
+    /// a swap leaves every other chunk equal in both buffers, so a tick
+    /// costs time in proportion to the area that changed, not the grid.
+    pub(crate) fn carry_cells_forward(&mut self) {
+        let chunks_x = self.width.div_ceil(CHUNK_SIZE);
+        for chunk in 0..self.stale_chunks.len() {
+            if !std::mem::take(&mut self.stale_chunks[chunk]) {
+                continue;
+            }
+            let (cx, cy) = (chunk as u32 % chunks_x, chunk as u32 / chunks_x);
+            // Edits since the last tick may have emptied the chunk
+            let mut live = false;
+            for y in cy * CHUNK_SIZE..((cy + 1) * CHUNK_SIZE).min(self.height) {
+                for x in cx * CHUNK_SIZE..((cx + 1) * CHUNK_SIZE).min(self.width) {
+                    let idx = (y * self.width + x) as usize;
+                    let cell = self.cells.get(idx).clone();
+                    live |= cell.cell_type != self.empty_type;
+                    self.write_next(idx, cell);
+                }
+            }
+            self.active_chunks[chunk] = live;
+        }
     }
 
     pub fn swap_buffers(&mut self) {
         std::mem::swap(&mut self.cells, &mut self.next_cells);
+        std::mem::swap(&mut self.population, &mut self.next_population);
+        // Only chunks written this tick can have changed liveness
+        for chunk in 0..self.stale_chunks.len() {
+            if self.stale_chunks[chunk] {
+                self.active_chunks[chunk] = self.chunk_has_live_cells(chunk);
+            }
+        }
+        self.reset_boundary_coverage();
+    }
+
+    /// Forget which chunk boundaries have been copied
+    ///
+    /// Called at the start of each chunk layer so debug builds can catch
+    /// isolated reads that rely on a previous layer's (now stale) copy.
+    /// A no-op in release builds.
+    pub fn reset_boundary_coverage(&mut self) {
+        #[cfg(debug_assertions)]
+        self.copied_chunks.fill(false);
+    }
+
+    /// Coordinates within `radius` of `c` along an axis of `len`, each visited once
+    ///
+    /// Clipped to `0..len`, or continued around the opposite edge when wrapping.
+    #[inline]
+    fn axis_window(&self, c: u32, radius: u32, len: u32) -> impl Iterator<Item = u32> + Clone {
+        let len = len.max(1);
+        let (start, span) = if self.wrap {
+            let start = (c as i64 - radius as i64).rem_euclid(len as i64) as u32;
+            (start, radius.saturating_mul(2).saturating_add(1).min(len))
+        } else {
+            let start = c.saturating_sub(radius);
+            (start, c.saturating_add(radius).saturating_add(1).min(len).saturating_sub(start))
+        };
+        (0..span).map(move |i| (start + i) % len)
+    }
+
+    /// Distance between two coordinates on an axis of `len`, the short way round when wrapping
+    #[inline]
+    fn axis_distance(&self, a: u32, b: u32, len: u32) -> u32 {
+        let d = a.abs_diff(b);
+        if self.wrap { d.min(len - d) } else { d }
+    }
+
+    /// The cell at offset (dx, dy) from (x, y), or None if it's off the grid
+    ///
+    /// Offsets past an edge wrap around on a toroidal grid.
+    #[inline]
+    pub fn neighbor_coord(&self, x: u32, y: u32, dx: i32, dy: i32) -> Option<(u32, u32)> {
+        self.resolve(x as i64 + dx as i64, y as i64 + dy as i64)
+    }
+
+    /// Resolve a possibly out-of-range neighbor position to grid coordinates
+    #[inline]
+    fn resolve(&self, x: i64, y: i64) -> Option<(u32, u32)> {
+        let (w, h) = (self.width as i64, self.height as i64);
+        if self.wrap && w > 0 && h > 0 {
+            Some((x.rem_euclid(w) as u32, y.rem_euclid(h) as u32))
+        } else if (0..w).contains(&x) && (0..h).contains(&y) {
+            Some((x as u32, y as u32))
+        } else {
+            None
+        }
+    }
+
+    /// Debug check that an isolated read of `radius` around (x, y) stays
+    /// inside a boundary region copied since the last coverage reset
+    ///
+    /// Only chunks next to (x, y) need checking: regions extend
+    /// `BOUNDARY_RADIUS` < `CHUNK_SIZE` past their chunk.
+    #[inline]
+    fn debug_assert_isolated_read(&self, x: u32, y: u32, radius: u32) {
+        #[cfg(debug_assertions)]
+        {
+            if x >= self.width || y >= self.height {
+                return;
+            }
+            let xs = self.axis_window(x, radius, self.width);
+            let ys = self.axis_window(y, radius, self.height);
+            let chunks_x = self.width.div_ceil(CHUNK_SIZE);
+            let chunks_y = self.height.div_ceil(CHUNK_SIZE);
+            // Chunks next to (x, y), across the seam on a wrapping grid
+            let adjacent = |c: u32, chunks: u32| {
+                let wrap = self.wrap;
+                (-1..=1).filter_map(move |d| {
+                    let n = c as i64 + d;
+                    if wrap {
+                        Some(n.rem_euclid(chunks as i64) as u32)
+                    } else {
+                        (0..chunks as i64).contains(&n).then_some(n as u32)
+                    }
+                })
+            };
+
+            let covered = adjacent(y / CHUNK_SIZE, chunks_y).any(|ny| {
+                adjacent(x / CHUNK_SIZE, chunks_x).any(|nx| {
+                    self.copied_chunks[(ny * chunks_x + nx) as usize]
+                        && xs.clone().all(|c| self.chunk_boundary_contains(nx, c, self.width))
+                        && ys.clone().all(|c| self.chunk_boundary_contains(ny, c, self.height))
+                })
+            });
+            debug_assert!(
+                covered,
+                "isolated read of radius {} around ({}, {}) reaches outside the boundary buffer copied this layer",
+                radius, x, y
+            );
+        }
+        #[cfg(not(debug_assertions))]
+        let _ = (x, y, radius);
     }
 
     /// Copy boundary region for a chunk to boundary_buffer for isolated reads
     /// 
     /// This must be called sequentially before parallel chunk processing.
     /// Since chunks at (cx%2, cy%2) don't overlap, this is called in layers.
+    ///
+    /// On a wrapping grid the region continues past the edges, so edge
+    /// chunks also see the cells on the opposite side.
     pub fn copy_chunk_boundary(&mut self, chunk_x: u32, chunk_y: u32) {
-        let start_x = (chunk_x * CHUNK_SIZE).saturating_sub(BOUNDARY_RADIUS);
-        let start_y = (chunk_y * CHUNK_SIZE).saturating_sub(BOUNDARY_RADIUS);
-        let end_x = ((chunk_x + 1) * CHUNK_SIZE + BOUNDARY_RADIUS).min(self.width);
-        let end_y = ((chunk_y + 1) * CHUNK_SIZE + BOUNDARY_RADIUS).min(self.height);
-
-        for y in start_y..end_y {
-            for x in start_x..end_x {
-                if x < self.width && y < self.height {
-                    let idx = (y * self.width + x) as usize;
-                    self.boundary_buffer[idx] = self.cells[idx].clone();
-                }
+        let xs = self.chunk_boundary_axis(chunk_x, self.width);
+        let ys = self.chunk_boundary_axis(chunk_y, self.height);
+
+        for y in ys {
+            for x in xs.clone() {
+                let idx = (y * self.width + x) as usize;
+                self.boundary_buffer.set(idx, self.cells.get(idx).clone());
             }
         }
+
+        #[cfg(debug_assertions)]
+        {
+            let chunks_x = self.width.div_ceil(CHUNK_SIZE);
+            self.copied_chunks[(chunk_y * chunks_x + chunk_x) as usize] = true;
+        }
+    }
+
+    /// One axis of the region copied for a chunk, wrapped on toroidal grids
+    fn chunk_boundary_axis(&self, chunk: u32, len: u32) -> impl Iterator<Item = u32> + Clone {
+        let len = len.max(1);
+        let (start, span) = self.chunk_boundary_span(chunk, len);
+        (0..span).map(move |i| (start + i) % len)
+    }
+
+    /// Whether `c` lies in `chunk_boundary_axis(chunk, len)`
+    fn chunk_boundary_contains(&self, chunk: u32, c: u32, len: u32) -> bool {
+        let len = len.max(1);
+        let (start, span) = self.chunk_boundary_span(chunk, len);
+        (c as i64 - start as i64).rem_euclid(len as i64) < span as i64
+    }
+
+    /// First coordinate and length of a chunk's boundary axis
+    fn chunk_boundary_span(&self, chunk: u32, len: u32) -> (u32, u32) {
+        let lo = chunk * CHUNK_SIZE;
+        let hi = ((chunk + 1) * CHUNK_SIZE).min(len);
+        if self.wrap {
+            let start = (lo as i64 - BOUNDARY_RADIUS as i64).rem_euclid(len as i64) as u32;
+            (start, (hi - lo + 2 * BOUNDARY_RADIUS).min(len))
+        } else {
+            let start = lo.saturating_sub(BOUNDARY_RADIUS);
+            (start, (hi + BOUNDARY_RADIUS).min(len) - start)
+        }
     }
 
     /// Get cell from boundary buffer (stable read state)
@@ -151,34 +621,28 @@ impl Grid {
         if x >= self.width || y >= self.height {
             return None;
         }
+        self.debug_assert_isolated_read(x, y, 0);
         let idx = (y * self.width + x) as usize;
-        Some(self.boundary_buffer[idx].clone())
+        Some(self.boundary_buffer.get(idx).clone())
     }
 
     /// Count neighbors using boundary buffer for isolation (optimized)
     #[inline]
-    pub fn count_neighbors_isolated(&self, x: u32, y: u32, cell_type: CellType) -> usize {
+    pub fn count_neighbors_isolated(&self, x: u32, y: u32, cell_type: CellType, neighborhood: Neighborhood) -> usize {
+        self.debug_assert_isolated_read(x, y, 1);
         let mut count = 0;
         let width = self.width as usize;
-        let x_usize = x as usize;
-        let y_usize = y as usize;
         
         // Direct array access without bounds checking for interior cells
         // Much faster than calling get_cell_from_boundary 8 times
-        let x_i = x as i32;
-        let y_i = y as i32;
+        let x_i = x as i64;
+        let y_i = y as i64;
         
-        // Check all 8 neighbors
-        for dy in -1..=1i32 {
-            for dx in -1..=1i32 {
-                if dx == 0 && dy == 0 { continue; }
-                let nx = x_i + dx;
-                let ny = y_i + dy;
-                if nx >= 0 && ny >= 0 && (nx as u32) < self.width && (ny as u32) < self.height {
-                    let idx = ((ny as usize) * width + (nx as usize)) as usize;
-                    if self.boundary_buffer[idx].cell_type == cell_type {
-                        count += 1;
-                    }
+        for &(dx, dy) in neighborhood.offsets() {
+            if let Some((nx, ny)) = self.resolve(x_i + dx as i64, y_i + dy as i64) {
+                let idx = (ny as usize) * width + (nx as usize);
+                if self.boundary_buffer.get(idx).cell_type == cell_type {
+                    count += 1;
                 }
             }
         }
@@ -188,27 +652,48 @@ impl Grid {
     /// Count in radius using boundary buffer for isolation (optimized)
     #[inline]
     pub fn count_in_radius_isolated(&self, x: u32, y: u32, cell_type: CellType, radius: u32) -> usize {
+        self.debug_assert_isolated_read(x, y, radius);
         let mut count = 0;
-        let x_start = if x < radius { 0 } else { x - radius };
-        let x_end = (x + radius + 1).min(self.width);
-        let y_start = if y < radius { 0 } else { y - radius };
-        let y_end = (y + radius + 1).min(self.height);
+        let xs = self.axis_window(x, radius, self.width);
         
         // Row-major iteration for cache efficiency
-        for cy in y_start..y_end {
+        for cy in self.axis_window(y, radius, self.height) {
             let row_base = (cy * self.width) as usize;
-            for cx in x_start..x_end {
-                let idx = (row_base + cx as usize) as usize;
-                if idx < self.boundary_buffer.len() {
-                    if self.boundary_buffer[idx].cell_type == cell_type {
-                        count += 1;
-                    }
+            for cx in xs.clone() {
+                let idx = row_base + cx as usize;
+                if idx < self.boundary_buffer.len() && self.boundary_buffer.get(idx).cell_type == cell_type {
+                    count += 1;
                 }
             }
         }
         count
     }
 
+    /// Chebyshev distance to the nearest cell of a type within `radius`, using the boundary buffer
+    ///
+    /// The cell at (x, y) itself is never counted. Returns None if no match is in range.
+    pub fn nearest_distance_isolated(&self, x: u32, y: u32, cell_type: CellType, radius: u32) -> Option<u32> {
+        self.debug_assert_isolated_read(x, y, radius);
+        let xs = self.axis_window(x, radius, self.width);
+        let mut nearest: Option<u32> = None;
+
+        for cy in self.axis_window(y, radius, self.height) {
+            let row_base = (cy * self.width) as usize;
+            for cx in xs.clone() {
+                if cx == x && cy == y {
+                    continue;
+                }
+                if self.boundary_buffer.get(row_base + cx as usize).cell_type == cell_type {
+                    let distance = self
+                        .axis_distance(x, cx, self.width)
+                        .max(self.axis_distance(y, cy, self.height));
+                    nearest = Some(nearest.map_or(distance, |n| n.min(distance)));
+                }
+            }
+        }
+        nearest
+    }
+
     pub fn count_neighbors(&self, x: u32, y: u32, cell_type: CellType) -> usize {
         let mut count = 0;
         for dy in -1..=1i32 {
@@ -216,8 +701,9 @@ impl Grid {
                 if dx == 0 && dy == 0 {
                     continue;
                 }
-                let nx = (x as i32 + dx) as u32;
-                let ny = (y as i32 + dy) as u32;
+                let Some((nx, ny)) = self.neighbor_coord(x, y, dx, dy) else {
+                    continue;
+                };
                 if let Some(cell) = self.get_cell(nx, ny) {
                     if cell.cell_type == cell_type {
                         count += 1;
@@ -228,15 +714,36 @@ impl Grid {
         count
     }
 
+    /// The 8 Moore neighbors of (x, y), clockwise from the top-left
+    ///
+    /// Order: NW, N, NE, E, SE, S, SW, W. Positions off a non-wrapping grid
+    /// read as Black.
+    pub fn neighborhood(&self, x: u32, y: u32) -> [Cell; 8] {
+        self.moore_ring(x, y, |nx, ny| self.get_cell(nx, ny))
+    }
+
+    /// `neighborhood` read from the boundary buffer (see `copy_chunk_boundary`)
+    pub fn neighborhood_isolated(&self, x: u32, y: u32) -> [Cell; 8] {
+        self.debug_assert_isolated_read(x, y, 1);
+        self.moore_ring(x, y, |nx, ny| Some(self.boundary_buffer.get((ny * self.width + nx) as usize).clone()))
+    }
+
+    fn moore_ring(&self, x: u32, y: u32, read: impl Fn(u32, u32) -> Option<Cell>) -> [Cell; 8] {
+        const CLOCKWISE: [(i64, i64); 8] = [(-1, -1), (0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0)];
+        std::array::from_fn(|i| {
+            let (dx, dy) = CLOCKWISE[i];
+            self.resolve(x as i64 + dx, y as i64 + dy)
+                .and_then(|(nx, ny)| read(nx, ny))
+                .unwrap_or_else(|| Cell::blank(self.empty_type))
+        })
+    }
+
     pub fn count_in_radius(&self, x: u32, y: u32, cell_type: CellType, radius: u32) -> usize {
         let mut count = 0;
-        let x_start = if x < radius { 0 } else { x - radius };
-        let x_end = (x + radius + 1).min(self.width);
-        let y_start = if y < radius { 0 } else { y - radius };
-        let y_end = (y + radius + 1).min(self.height);
+        let xs = self.axis_window(x, radius, self.width);
 
-        for cy in y_start..y_end {
-            for cx in x_start..x_end {
+        for cy in self.axis_window(y, radius, self.height) {
+            for cx in xs.clone() {
                 if let Some(cell) = self.get_cell(cx, cy) {
                     if cell.cell_type == cell_type {
                         count += 1;
@@ -247,10 +754,173 @@ impl Grid {
         count
     }
 
+    /// Population of every type, indexed by `CellType::to_u8`
+    pub fn type_counts(&self) -> [u32; CellType::COUNT] {
+        self.population
+    }
+
+    /// True when every cell is the empty type, so nothing can ever happen
+    /// again short of a (very rare) spontaneous reproduction
+    pub fn is_extinct(&self) -> bool {
+        self.population(self.empty_type) as usize == (self.width * self.height) as usize
+    }
+
+    /// Every cell not of the empty type, with its coordinates, row-major
+    pub fn live_cells(&self) -> impl Iterator<Item = (u32, u32, &Cell)> + '_ {
+        let width = self.width;
+        self.cells
+            .iter_live(self.empty_type)
+            .map(move |(idx, cell)| (idx as u32 % width, idx as u32 / width, cell))
+    }
+
+    /// Coordinates of live cells whose genes satisfy `selector`, row-major
+    pub fn cells_where_gene(&self, selector: impl Fn(&Genes) -> bool) -> Vec<(u32, u32)> {
+        self.live_cells()
+            .filter(|(_, _, cell)| selector(&cell.genes))
+            .map(|(x, y, _)| (x, y))
+            .collect()
+    }
+
+    /// Stable 64-bit FNV-1a hash of the dimensions and every cell type
+    ///
+    /// Only cell types are hashed (not ages or genes), matching what
+    /// `to_bytes` and the wire format capture. Stable across runs and
+    /// platforms, so it can be stored in fixtures.
+    pub fn content_hash(&self) -> u64 {
+        const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+        const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+        let header = self.width.to_le_bytes().into_iter().chain(self.height.to_le_bytes());
+        header
+            .chain(self.cells.iter().map(|c| c.to_u8()))
+            .fold(FNV_OFFSET, |hash, byte| (hash ^ byte as u64).wrapping_mul(FNV_PRIME))
+    }
+
+    /// Every position whose cell type differs, as (x, y, self's type, other's type)
+    ///
+    /// Row-major order. Grids of different sizes aren't comparable and give
+    /// an empty diff.
+    pub fn diff(&self, other: &Grid) -> Vec<(u32, u32, CellType, CellType)> {
+        if (self.width, self.height) != (other.width, other.height) {
+            return Vec::new();
+        }
+        self.cells
+            .iter()
+            .zip(other.cells.iter())
+            .enumerate()
+            .filter(|(_, (old, new))| old.cell_type != new.cell_type)
+            .map(|(idx, (old, new))| {
+                let idx = idx as u32;
+                (idx % self.width, idx / self.width, old.cell_type, new.cell_type)
+            })
+            .collect()
+    }
+
+    /// Number of positions `diff` would report
+    pub fn diff_count(&self, other: &Grid) -> usize {
+        if (self.width, self.height) != (other.width, other.height) {
+            return 0;
+        }
+        self.cells
+            .iter()
+            .zip(other.cells.iter())
+            .filter(|(old, new)| old.cell_type != new.cell_type)
+            .count()
+    }
+
     pub fn to_bytes(&self) -> Vec<u8> {
         self.cells.iter().map(|c| c.to_u8()).collect()
     }
 
+    /// Rebuild a grid from a `to_bytes` stream of cell type ids
+    ///
+    /// Each byte becomes a fresh cell with default genes. None if the length
+    /// isn't `width * height` or a byte isn't a valid cell type.
+    pub fn from_bytes(bytes: &[u8], width: u32, height: u32) -> Option<Grid> {
+        if bytes.len() != width as usize * height as usize {
+            return None;
+        }
+        let cells = bytes
+            .iter()
+            .map(|&byte| CellType::from_u8(byte).map(Cell::new))
+            .collect::<Option<Vec<_>>>()?;
+
+        let mut grid = Grid::new(width, height);
+        grid.restore_cells(cells);
+        grid.take_dirty_chunks();
+        Some(grid)
+    }
+
+    /// Build a grid from a text template, one row per line
+    ///
+    /// Each character is looked up in `legend`; `CHAR_MAP_BLANK` ('.') is
+    /// Black unless the legend maps it to something else. The grid is as
+    /// wide as the longest row, and shorter rows are padded with Black.
+    pub fn from_char_map(map: &str, legend: &HashMap<char, CellType>) -> Result<Grid, ParseError> {
+        let rows: Vec<Vec<CellType>> = map
+            .lines()
+            .enumerate()
+            .map(|(line, row)| {
+                row.chars()
+                    .enumerate()
+                    .map(|(column, ch)| match legend.get(&ch) {
+                        Some(&cell_type) => Ok(cell_type),
+                        None if ch == CHAR_MAP_BLANK => Ok(CellType::Black),
+                        None => Err(ParseError::UnknownChar { line: line + 1, column: column + 1, found: ch }),
+                    })
+                    .collect()
+            })
+            .collect::<Result<_, _>>()?;
+
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        if width == 0 {
+            return Err(ParseError::Empty);
+        }
+        let cells = rows.iter().flat_map(|row| {
+            (0..width).map(|x| Cell::blank(row.get(x).copied().unwrap_or(CellType::Black)))
+        });
+
+        let mut grid = Grid::new(width as u32, rows.len() as u32);
+        grid.restore_cells(cells.collect::<Vec<_>>());
+        grid.take_dirty_chunks();
+        Ok(grid)
+    }
+
+    /// Cell types as a run-length encoded snapshot
+    ///
+    /// An 8-byte header (width, height as little-endian u32) followed by
+    /// `(count, cell_type)` pairs, with runs over 255 split. Long Black
+    /// stretches collapse to a couple of bytes per 255 cells.
+    pub fn to_rle(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(8);
+        out.extend_from_slice(&self.width.to_le_bytes());
+        out.extend_from_slice(&self.height.to_le_bytes());
+        out.extend(rle_encode(&self.to_bytes()));
+        out
+    }
+
+    /// Rebuild a grid from `to_rle` output
+    ///
+    /// Returns None if the header doesn't match `width` x `height`, the runs
+    /// don't cover exactly that many cells, or a type byte is unknown.
+    pub fn from_rle(bytes: &[u8], width: u32, height: u32) -> Option<Grid> {
+        let header = bytes.get(..8)?;
+        if header[..4] != width.to_le_bytes() || header[4..] != height.to_le_bytes() {
+            return None;
+        }
+        let types = rle_decode(&bytes[8..], width as usize * height as usize)?;
+
+        let mut grid = Grid::new(width, height);
+        for (i, &byte) in types.iter().enumerate() {
+            let cell_type = CellType::from_u8(byte)?;
+            if cell_type != CellType::Black {
+                grid.set_cell(i as u32 % width, i as u32 / width, cell_type);
+            }
+        }
+        grid.take_dirty_chunks();
+        Some(grid)
+    }
+
     pub fn to_json(&self) -> String {
         let mut map = serde_json::Map::new();
         map.insert("width".to_string(), serde_json::json!(self.width));
@@ -260,55 +930,488 @@ impl Grid {
     }
 
     pub fn get_population_counts(&self) -> String {
-        let mut counts: HashMap<String, u32> = HashMap::new();
-        
-        for cell in self.cells.iter() {
-            let name = match cell.cell_type {
-                CellType::Black => "Black",
-                CellType::Green => "Green",
-                CellType::Orange => "Orange",
-                CellType::Gray => "Gray",
-                CellType::Purple => "Purple",
-                CellType::Red => "Red",
-                CellType::White => "White",
-                CellType::Blue => "Blue",
-                CellType::Brown => "Brown",
-                CellType::Tan => "Tan",
-                CellType::Gold => "Gold",
-                CellType::Lime => "Lime",
-                CellType::Crimson => "Crimson",
-                CellType::Maroon => "Maroon",
-                CellType::Coral => "Coral",
-                CellType::Pink => "Pink",
-                CellType::Magenta => "Magenta",
-                CellType::Cyan => "Cyan",
-                CellType::Yellow => "Yellow",
-                CellType::Teal => "Teal",
-                CellType::Navy => "Navy",
-                CellType::Olive => "Olive",
-                CellType::Indigo => "Indigo",
-                CellType::Khaki => "Khaki",
-                CellType::Slate => "Slate",
-                CellType::Rust => "Rust",
-                CellType::Mint => "Mint",
-                CellType::Peach => "Peach",
-                CellType::Aqua => "Aqua",
-                CellType::Silver => "Silver",
-                CellType::Violet => "Violet",
-                CellType::Amber => "Amber",
-                CellType::Pearl => "Pearl",
-                CellType::Smoke => "Smoke",
-                CellType::Glint => "Glint",
-                CellType::Tint => "Tint",
-                CellType::Shade => "Shade",
-            };
-            *counts.entry(name.to_string()).or_insert(0) += 1;
-        }
-
         let mut map = serde_json::Map::new();
-        for (name, count) in counts {
-            map.insert(name, serde_json::json!(count));
+        for (cell_type, &count) in CellType::all().zip(self.population.iter()) {
+            map.insert(cell_type.as_name().to_string(), serde_json::json!(count));
         }
         serde_json::to_string(&map).unwrap_or_default()
     }
 }
+
+/// Character `Grid::from_char_map` reads as Black when the legend doesn't map it
+pub const CHAR_MAP_BLANK: char = '.';
+
+/// Why `Grid::from_char_map` rejected a template
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// A character with no legend entry, at 1-based line and column
+    UnknownChar { line: usize, column: usize, found: char },
+    /// The template has no cells
+    Empty,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::UnknownChar { line, column, found } => {
+                write!(f, "unknown cell char {:?} at line {}, column {}", found, line, column)
+            }
+            ParseError::Empty => write!(f, "char map has no cells"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A read-only rectangle of a grid's current cells (see `Grid::tiles`)
+#[derive(Clone)]
+pub struct TileView<'a> {
+    grid: &'a Grid,
+    /// Half-open column range
+    pub x: Range<u32>,
+    /// Half-open row range
+    pub y: Range<u32>,
+}
+
+impl<'a> TileView<'a> {
+    pub fn len(&self) -> usize {
+        self.x.len() * self.y.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Every cell in the tile, row-major
+    pub fn cells(&self) -> impl Iterator<Item = &'a Cell> + '_ {
+        let grid = self.grid;
+        self.y.clone().flat_map(move |y| {
+            self.x.clone().map(move |x| grid.cells.get((y * grid.width + x) as usize))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const TYPES: [CellType; 4] = [CellType::Green, CellType::Purple, CellType::Orange, CellType::Black];
+
+    fn random_grid(width: u32, height: u32, seed: u64) -> Grid {
+        let preset = crate::presets::load_preset("balanced").unwrap();
+        let mut grid = Grid::new(width, height);
+        grid.initialize_random_with_rng(&preset, &mut StdRng::seed_from_u64(seed));
+        grid
+    }
+
+    fn copy_all_boundaries(grid: &mut Grid) {
+        for cy in 0..grid.height.div_ceil(CHUNK_SIZE) {
+            for cx in 0..grid.width.div_ceil(CHUNK_SIZE) {
+                grid.copy_chunk_boundary(cx, cy);
+            }
+        }
+    }
+
+    #[test]
+    fn test_isolated_counts_match_live_after_full_copy() {
+        let mut grid = random_grid(70, 45, 3);
+        copy_all_boundaries(&mut grid);
+
+        for y in 0..grid.height {
+            for x in 0..grid.width {
+                for &t in &TYPES {
+                    assert_eq!(
+                        grid.count_neighbors_isolated(x, y, t, Neighborhood::Moore),
+                        grid.count_neighbors(x, y, t),
+                        "neighbors of ({}, {}) for {:?}",
+                        x, y, t
+                    );
+                    for radius in [2, 5, BOUNDARY_RADIUS] {
+                        assert_eq!(
+                            grid.count_in_radius_isolated(x, y, t, radius),
+                            grid.count_in_radius(x, y, t, radius),
+                            "radius {} around ({}, {}) for {:?}",
+                            radius, x, y, t
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_spawn_then_kill_is_population_neutral() {
+        let mut grid = random_grid(80, 40, 5);
+        grid.take_dirty_chunks();
+        let before = grid.type_counts();
+
+        // First empty cell in the second chunk column
+        let idx = grid.to_bytes().iter().enumerate()
+            .position(|(i, &b)| b == 0 && i as u32 % 80 >= CHUNK_SIZE)
+            .unwrap() as u32;
+        let (x, y) = (idx % 80, idx / 80);
+
+        assert_eq!(grid.spawn(x, y, CellType::Pearl), Some(CellType::Black));
+        assert_eq!(grid.population(CellType::Pearl), before[CellType::Pearl.to_u8() as usize] + 1);
+        assert_eq!(grid.take_dirty_chunks(), vec![(x / CHUNK_SIZE, y / CHUNK_SIZE)]);
+        assert!(grid.take_dirty_chunks().is_empty());
+
+        assert_eq!(grid.kill(x, y), Some(CellType::Pearl));
+        assert_eq!(grid.type_counts(), before);
+        assert_eq!(grid.spawn(80, 0, CellType::Green), None);
+
+        // Cached counts agree with a fresh scan
+        let mut scanned = [0; CellType::COUNT];
+        for &b in &grid.to_bytes() {
+            scanned[b as usize] += 1;
+        }
+        assert_eq!(grid.type_counts(), scanned);
+    }
+
+    #[test]
+    fn test_population_tracks_ticks_without_rescan() {
+        let mut grid = random_grid(70, 45, 9);
+        let config = crate::config::RuleConfig::default();
+        let mut rng = StdRng::seed_from_u64(9);
+        for _ in 0..10 {
+            crate::rules::apply_rules_with_rng(&mut grid, &config, &mut rng);
+            let mut scanned = [0; CellType::COUNT];
+            for &b in &grid.to_bytes() {
+                scanned[b as usize] += 1;
+            }
+            assert_eq!(grid.type_counts(), scanned);
+        }
+    }
+
+    #[test]
+    fn test_single_chunk_copy_covers_boundary_radius() {
+        let mut grid = random_grid(96, 96, 9);
+        grid.copy_chunk_boundary(1, 1);
+
+        // Every cell inside chunk (1, 1) sees the live state out to BOUNDARY_RADIUS
+        for y in CHUNK_SIZE..2 * CHUNK_SIZE {
+            for x in CHUNK_SIZE..2 * CHUNK_SIZE {
+                for &t in &TYPES {
+                    assert_eq!(
+                        grid.count_in_radius_isolated(x, y, t, BOUNDARY_RADIUS),
+                        grid.count_in_radius(x, y, t, BOUNDARY_RADIUS),
+                        "({}, {}) for {:?}",
+                        x, y, t
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "reaches outside the boundary buffer")]
+    fn test_isolated_read_past_boundary_radius_asserts() {
+        let mut grid = random_grid(96, 96, 9);
+        grid.copy_chunk_boundary(1, 1);
+
+        // One step further than the copied region
+        grid.count_in_radius_isolated(CHUNK_SIZE, CHUNK_SIZE, CellType::Black, BOUNDARY_RADIUS + 1);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn test_isolated_read_across_seam_uses_wrapped_region() {
+        let mut grid = Grid::with_topology(2 * CHUNK_SIZE, 2 * CHUNK_SIZE, true);
+        // Only the far corner chunk, whose region wraps round to (0, 0)
+        grid.copy_chunk_boundary(1, 1);
+        assert_eq!(grid.count_in_radius_isolated(0, 0, CellType::Black, 2), 25);
+    }
+
+    #[test]
+    fn test_wrapped_edges_see_opposite_side() {
+        let width = 70;
+        let mut wrapped = Grid::with_topology(width, 40, true);
+        let mut clipped = Grid::new(width, 40);
+        for grid in [&mut wrapped, &mut clipped] {
+            grid.set_cell(width - 1, 5, CellType::Green);
+            grid.set_cell(3, 39, CellType::Red);
+        }
+
+        assert_eq!(wrapped.count_neighbors(0, 5, CellType::Green), 1);
+        assert_eq!(clipped.count_neighbors(0, 5, CellType::Green), 0);
+        assert_eq!(wrapped.count_in_radius(2, 0, CellType::Red, 1), 1);
+
+        // Chunk (0, 0)'s halo wraps around to the last column and row
+        wrapped.copy_chunk_boundary(0, 0);
+        assert_eq!(wrapped.count_neighbors_isolated(0, 5, CellType::Green, Neighborhood::Moore), 1);
+        assert_eq!(wrapped.count_in_radius_isolated(0, 0, CellType::Red, 3), 1);
+        assert_eq!(wrapped.nearest_distance_isolated(0, 5, CellType::Green, 4), Some(1));
+
+        clipped.copy_chunk_boundary(0, 0);
+        assert_eq!(clipped.count_neighbors_isolated(0, 5, CellType::Green, Neighborhood::Moore), 0);
+        assert_eq!(clipped.nearest_distance_isolated(0, 5, CellType::Green, 4), None);
+    }
+
+    #[test]
+    fn test_cells_where_gene_finds_high_vitality() {
+        let mut grid = Grid::new(8, 8);
+        grid.set_cell(1, 1, CellType::Green);
+        grid.set_cell(6, 2, CellType::Orange);
+        grid.set_cell(3, 5, CellType::Green);
+
+        let hardy = Genes { vitality: 0.95, ..Genes::default() };
+        grid.set_next_cell(6, 2, Cell::with_genes(CellType::Orange, hardy));
+        grid.set_next_cell(3, 5, Cell::with_genes(CellType::Green, hardy));
+        grid.set_next_cell(1, 1, Cell::new(CellType::Green));
+        grid.swap_buffers();
+
+        assert_eq!(grid.cells_where_gene(|g| g.vitality > 0.9), vec![(6, 2), (3, 5)]);
+        assert_eq!(grid.cells_where_gene(|_| true).len(), 3);
+    }
+
+    #[test]
+    fn test_rle_snapshot_compresses_sparse_grid() {
+        let mut grid = Grid::new(500, 500);
+        for (x, y, t) in [(0, 0, CellType::Green), (250, 250, CellType::Red), (499, 499, CellType::Shade)] {
+            grid.set_cell(x, y, t);
+        }
+
+        let rle = grid.to_rle();
+        assert!(rle.len() * 100 < grid.to_bytes().len(), "{} bytes", rle.len());
+
+        let decoded = Grid::from_rle(&rle, 500, 500).unwrap();
+        assert_eq!(decoded.to_bytes(), grid.to_bytes());
+        assert_eq!(decoded.population(CellType::Red), 1);
+        assert!(Grid::from_rle(&rle, 250, 1000).is_none());
+        assert!(Grid::from_rle(&rle[..rle.len() - 2], 500, 500).is_none());
+    }
+
+    #[test]
+    fn test_initialize_random_matches_requested_shares() {
+        // Balanced weights sum to 103, with Aqua last in id order
+        let preset = crate::presets::load_preset("balanced").unwrap();
+        let total: f64 = preset.values().filter_map(|v| v.as_f64()).sum();
+        let mut grid = Grid::new(256, 256);
+        grid.initialize_random_with_rng(&preset, &mut StdRng::seed_from_u64(4));
+
+        let cells = (256 * 256) as f64;
+        for (cell_type, weight) in [(CellType::Green, 20.0), (CellType::Black, 30.0), (CellType::Aqua, 1.0)] {
+            let realized = grid.population(cell_type) as f64 / cells;
+            let requested = weight / total;
+            assert!((realized - requested).abs() < 0.01, "{:?}: {} vs {}", cell_type, realized, requested);
+        }
+
+        // Without a Black entry the rest of 100 stays empty
+        let sparse = serde_json::json!({ "Green": 10 });
+        grid.initialize_random_with_rng(sparse.as_object().unwrap(), &mut StdRng::seed_from_u64(4));
+        let green = grid.population(CellType::Green) as f64 / cells;
+        assert!((green - 0.10).abs() < 0.01, "green {}", green);
+    }
+
+    #[test]
+    fn test_neighborhood_is_clockwise_with_black_edges() {
+        let mut grid = Grid::new(5, 5);
+        grid.set_cell(1, 0, CellType::Green);
+        grid.set_cell(2, 1, CellType::Red);
+        grid.set_cell(0, 1, CellType::Blue);
+
+        let types: Vec<CellType> = grid.neighborhood(1, 1).iter().map(|c| c.cell_type).collect();
+        assert_eq!(types[1], CellType::Green);
+        assert_eq!(types[3], CellType::Red);
+        assert_eq!(types[7], CellType::Blue);
+
+        // Corner: five of the eight neighbors are off the grid
+        let corner = grid.neighborhood(0, 0);
+        assert_eq!(corner.iter().filter(|c| c.cell_type == CellType::Black).count(), 6);
+        assert_eq!(corner[4].cell_type, CellType::Black);
+        assert_eq!(corner[3].cell_type, CellType::Green);
+    }
+
+    #[test]
+    fn test_from_bytes_round_trips_cell_types() {
+        let grid = random_grid(40, 24, 3);
+        let bytes = grid.to_bytes();
+        let decoded = Grid::from_bytes(&bytes, 40, 24).unwrap();
+
+        for (x, y) in [(0, 0), (39, 0), (17, 11), (0, 23), (39, 23)] {
+            assert_eq!(decoded.get_cell(x, y).unwrap().cell_type, grid.get_cell(x, y).unwrap().cell_type);
+        }
+        assert_eq!(decoded.to_bytes(), bytes);
+        assert_eq!(decoded.population(CellType::Green), grid.population(CellType::Green));
+
+        assert!(Grid::from_bytes(&bytes, 24, 24).is_none());
+        let mut bad = bytes.clone();
+        bad[5] = CellType::COUNT as u8;
+        assert!(Grid::from_bytes(&bad, 40, 24).is_none());
+    }
+
+    #[test]
+    fn test_content_hash_tracks_cell_types() {
+        let grid = random_grid(40, 24, 1);
+        let (decoded, _) = crate::wire::decode_frame(&crate::wire::encode_frame(&grid, 0)).unwrap();
+        assert_eq!(decoded.content_hash(), grid.content_hash());
+
+        let mut changed = random_grid(40, 24, 1);
+        let old = changed.get_cell(5, 5).unwrap().cell_type;
+        changed.set_cell(5, 5, if old == CellType::Pearl { CellType::Green } else { CellType::Pearl });
+        assert_ne!(changed.content_hash(), grid.content_hash());
+        assert_ne!(Grid::new(4, 6).content_hash(), Grid::new(6, 4).content_hash());
+    }
+
+    #[test]
+    fn test_population_counts_list_every_type() {
+        let grid = Grid::new(30, 20);
+        let counts: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_str(&grid.get_population_counts()).unwrap();
+        assert_eq!(counts.len(), CellType::COUNT);
+        assert_eq!(counts["Black"], 600);
+        assert!(CellType::all().all(|t| counts.contains_key(t.as_name())));
+        assert_eq!(counts["Shade"], 0);
+    }
+
+    #[test]
+    fn test_brush_fills_clip_to_grid() {
+        let mut grid = Grid::new(10, 10);
+        grid.fill_rect(2, 2, 5, 5, CellType::Teal);
+        assert_eq!(grid.population(CellType::Teal), 9);
+        assert_eq!(grid.get_cell(4, 4).unwrap().cell_type, CellType::Teal);
+        assert_eq!(grid.get_cell(5, 4).unwrap().cell_type, CellType::Black);
+
+        grid.fill_rect(6, 6, 3, 9, CellType::Teal);
+        grid.fill_rect(8, 8, 50, 50, CellType::Gold);
+        assert_eq!(grid.population(CellType::Teal), 9);
+        assert_eq!(grid.population(CellType::Gold), 4);
+
+        grid.fill_circle(7, 2, 0, CellType::Pearl);
+        assert_eq!(grid.population(CellType::Pearl), 1);
+        assert_eq!(grid.get_cell(7, 2).unwrap().cell_type, CellType::Pearl);
+        grid.fill_circle(0, 0, 1, CellType::Pearl);
+        assert_eq!(grid.population(CellType::Pearl), 4);
+
+        // A pattern hanging off the right edge; None keeps what is already there
+        let pattern = vec![
+            vec![None, Some(CellType::Red), None],
+            vec![Some(CellType::Red), Some(CellType::Red), Some(CellType::Red)],
+        ];
+        grid.stamp(8, 2, &pattern);
+        assert_eq!(grid.population(CellType::Red), 3);
+        assert_eq!(grid.get_cell(8, 2).unwrap().cell_type, CellType::Black);
+        assert_eq!(grid.get_cell(9, 3).unwrap().cell_type, CellType::Red);
+    }
+
+    #[test]
+    fn test_diff_reports_changed_cell() {
+        let before = random_grid(30, 20, 4);
+        let mut after = random_grid(30, 20, 4);
+        assert!(before.diff(&after).is_empty());
+
+        let old = after.get_cell(17, 6).unwrap().cell_type;
+        let new = if old == CellType::Amber { CellType::Glint } else { CellType::Amber };
+        after.set_cell(17, 6, new);
+
+        assert_eq!(before.diff(&after), vec![(17, 6, old, new)]);
+        assert_eq!(before.diff_count(&after), 1);
+        assert_eq!(before.diff_count(&Grid::new(20, 30)), 0);
+    }
+
+    #[test]
+    fn test_from_char_map_places_cells() {
+        let legend = HashMap::from([('G', CellType::Green), ('#', CellType::Pearl)]);
+        let grid = Grid::from_char_map("...\n.G.\n...\n", &legend).unwrap();
+        assert_eq!((grid.width, grid.height), (3, 3));
+        assert_eq!(grid.get_cell(1, 1).unwrap().cell_type, CellType::Green);
+        assert_eq!(grid.type_counts()[CellType::Green.to_u8() as usize], 1);
+        assert_eq!(grid.type_counts()[CellType::Black.to_u8() as usize], 8);
+
+        // Ragged rows pad with Black; unknown chars report where they are
+        let ragged = Grid::from_char_map("####\n#", &legend).unwrap();
+        assert_eq!((ragged.width, ragged.height), (4, 2));
+        assert_eq!(ragged.get_cell(3, 1).unwrap().cell_type, CellType::Black);
+        let err = Grid::from_char_map("..\n.x", &legend).err().unwrap();
+        assert_eq!(err, ParseError::UnknownChar { line: 2, column: 2, found: 'x' });
+        assert_eq!(err.to_string(), "unknown cell char 'x' at line 2, column 2");
+    }
+
+    #[test]
+    fn test_slate_as_empty_type_fills_and_hosts_offspring() {
+        let mut grid = Grid::new(20, 20);
+        grid.set_empty_type(CellType::Slate);
+        let densities = serde_json::json!({ "Green": 10.0 }).as_object().unwrap().clone();
+        grid.initialize_random(&densities);
+
+        let counts = grid.type_counts();
+        assert_eq!(counts[CellType::Black.to_u8() as usize], 0);
+        assert_eq!(counts[CellType::Slate.to_u8() as usize] + counts[CellType::Green.to_u8() as usize], 400);
+        assert!(counts[CellType::Slate.to_u8() as usize] > 300);
+
+        // Two greens in a field of Slate: the offspring lands on Slate
+        let mut config = crate::config::RuleConfig::default();
+        config.reproduction.chance = 1.0;
+        grid.fill_rect(0, 0, 20, 20, CellType::Slate);
+        grid.set_cell(10, 10, CellType::Green);
+        grid.set_cell(11, 10, CellType::Green);
+        grid.carry_cells_forward();
+        let (ox, oy) = crate::genetics::check_reproduction(&mut grid, 10, 10, &config, &mut rand::thread_rng())
+            .expect("greens on Slate should breed");
+        assert_eq!(grid.get_cell(ox, oy).unwrap().cell_type, CellType::Slate);
+        assert_eq!(grid.get_next_cell(ox, oy).unwrap().cell_type, CellType::Green);
+    }
+
+    #[test]
+    fn test_empty_type_drives_kills_extinction_and_storage() {
+        for (mut grid, stored) in [(Grid::new(40, 40), 40 * 40), (Grid::new_sparse(40, 40), 1)] {
+            grid.set_cell(3, 3, CellType::Green);
+            grid.set_empty_type(CellType::Slate);
+            assert_eq!(grid.population(CellType::Slate), 40 * 40 - 1);
+            assert_eq!(grid.population(CellType::Black), 0);
+            assert_eq!(grid.live_cells().map(|(x, y, _)| (x, y)).collect::<Vec<_>>(), vec![(3, 3)]);
+            assert_eq!(grid.stored_cells(), stored);
+
+            assert_eq!(grid.kill(3, 3), Some(CellType::Green));
+            assert_eq!(grid.get_cell(3, 3).unwrap().cell_type, CellType::Slate);
+            assert!(grid.is_extinct());
+            assert_eq!(grid.live_cells().count(), 0);
+
+            // Black is now an ordinary type and keeps its chunk active
+            grid.set_cell(35, 35, CellType::Black);
+            crate::rules::apply_rules_with_rng(&mut grid, &Default::default(), &mut StdRng::seed_from_u64(4));
+            assert_eq!(grid.active_chunks, vec![false, false, false, true]);
+            assert!(!grid.is_extinct());
+        }
+    }
+
+    #[test]
+    fn test_tick_only_carries_changed_chunks() {
+        // Lime with no green dies on its first tick; the chunk it was in
+        // must not get it back from the stale buffer two ticks later
+        let mut grid = Grid::new(3 * CHUNK_SIZE, CHUNK_SIZE);
+        grid.set_cell(5, 5, CellType::Lime);
+        let config = crate::config::RuleConfig::default();
+        let mut rng = StdRng::seed_from_u64(1);
+        crate::rules::apply_rules_with_rng(&mut grid, &config, &mut rng);
+        assert_eq!(grid.get_cell(5, 5).unwrap().cell_type, CellType::Black);
+        assert_eq!(grid.stale_chunks, vec![true, false, false]);
+
+        for _ in 0..3 {
+            crate::rules::apply_rules_with_rng(&mut grid, &config, &mut rng);
+            assert!(grid.is_extinct());
+        }
+        // Nothing was written, so nothing is left to carry
+        assert_eq!(grid.stale_chunks, vec![false; 3]);
+    }
+
+    #[test]
+    fn test_active_flags_follow_written_chunks() {
+        let mut grid = Grid::new(3 * CHUNK_SIZE, CHUNK_SIZE);
+        grid.set_cell(5, 5, CellType::Indigo);
+        grid.set_cell(70, 5, CellType::Indigo);
+        let config = crate::config::RuleConfig::default();
+        let mut rng = StdRng::seed_from_u64(2);
+        crate::rules::apply_rules_with_rng(&mut grid, &config, &mut rng);
+        assert_eq!(grid.active_chunks, vec![true, false, true]);
+
+        // The kill over-reports until the next tick carries the chunk
+        grid.kill(70, 5);
+        assert!(grid.is_chunk_active(2, 0));
+        crate::rules::apply_rules_with_rng(&mut grid, &config, &mut rng);
+        assert_eq!(grid.active_chunks, vec![true, false, false]);
+    }
+}
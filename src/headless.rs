@@ -0,0 +1,168 @@
+//! Headless Batch Runs
+//!
+//! Ticks a grid with no GUI and no system metrics, for collecting statistics
+//! on servers. `Simulator` owns a `MetricsCollector` (which probes the OS via
+//! sysinfo on creation); `BatchRunner` skips it and keeps only the grid, the
+//! rules and a seeded RNG. Nothing here needs the `gui` feature, so servers
+//! can build with `--no-default-features` and skip iced, sysinfo and the
+//! image crates entirely.
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::config::RuleConfig;
+use crate::events::EventLogger;
+use crate::grid::Grid;
+use crate::presets::{self, Preset};
+use crate::rules::apply_rules_with_rng;
+use crate::stats::{calculate_stats, EcosystemStats, EnsembleSummary, MetricSummary};
+
+pub struct BatchRunner {
+    pub grid: Grid,
+    pub rule_config: RuleConfig,
+    pub tick_count: u64,
+    /// Fed every stats sample taken by `run`, if set
+    pub events: Option<EventLogger>,
+    rng: ChaCha8Rng,
+}
+
+impl BatchRunner {
+    /// An empty (all Black) grid whose runs are reproducible from `seed`
+    pub fn new(width: u32, height: u32, seed: u64) -> Self {
+        BatchRunner {
+            grid: Grid::new(width, height),
+            rule_config: RuleConfig::default(),
+            tick_count: 0,
+            events: None,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    /// Fill the grid from a named preset; false if the name is unknown
+    pub fn load_preset(&mut self, preset_name: &str) -> bool {
+        match presets::load_preset(preset_name) {
+            Some(densities) => {
+                self.load_densities(&densities);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Fill the grid from an already-loaded preset, drawing from the run's RNG
+    pub fn load_densities(&mut self, densities: &Preset) {
+        self.grid.initialize_random_with_rng(densities, &mut self.rng);
+    }
+
+    pub fn tick(&mut self) {
+        apply_rules_with_rng(&mut self.grid, &self.rule_config, &mut self.rng);
+        self.tick_count += 1;
+    }
+
+    /// Run `ticks` ticks, sampling `calculate_stats` after every `sample_every`-th
+    ///
+    /// Sample `i` is taken after `(i + 1) * sample_every` ticks of this run, so
+    /// there are `ticks / sample_every` samples. `sample_every` of 0 samples
+    /// every tick. Samples also go to the event logger, if one is set.
+    pub fn run(&mut self, ticks: u64, sample_every: u64) -> Vec<EcosystemStats> {
+        let sample_every = sample_every.max(1);
+        let mut samples = Vec::with_capacity((ticks / sample_every) as usize);
+        for i in 1..=ticks {
+            self.tick();
+            if i % sample_every == 0 {
+                let stats = calculate_stats(&self.grid);
+                if let Some(events) = &mut self.events {
+                    events.observe(self.tick_count, &stats);
+                }
+                samples.push(stats);
+            }
+        }
+        samples
+    }
+}
+
+/// Run `preset` once per seed for `ticks` ticks and summarize the final stats
+///
+/// Each run is a `BatchRunner` seeded with its own seed, so both the initial
+/// layout and every tick are reproducible and the runs are independent.
+pub fn ensemble_run(width: u32, height: u32, preset: &Preset, ticks: u64, seeds: &[u64]) -> EnsembleSummary {
+    let finals: Vec<EcosystemStats> = seeds
+        .iter()
+        .map(|&seed| {
+            let mut runner = BatchRunner::new(width, height, seed);
+            runner.load_densities(preset);
+            for _ in 0..ticks {
+                runner.tick();
+            }
+            calculate_stats(&runner.grid)
+        })
+        .collect();
+
+    let summarize = |metric: fn(&EcosystemStats) -> f64| {
+        MetricSummary::from_values(&finals.iter().map(metric).collect::<Vec<_>>())
+    };
+    EnsembleSummary {
+        samples: finals.len(),
+        health_score: summarize(|s| s.health_score),
+        green_coverage: summarize(|s| s.green_coverage),
+        diversity_index: summarize(|s| s.diversity_index),
+        stability: summarize(|s| s.stability),
+        disease_pressure: summarize(|s| s.disease_pressure),
+        orange_population: summarize(|s| s.orange_population as f64),
+        predator_count: summarize(|s| s.predator_count as f64),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::CellType;
+    use crate::events::EcosystemEvent;
+
+    #[test]
+    fn test_headless_run_samples_on_interval() {
+        let mut runner = BatchRunner::new(48, 48, 5);
+        assert!(runner.load_preset("balanced"));
+
+        let samples = runner.run(100, 10);
+        assert_eq!(samples.len(), 10);
+        assert_eq!(runner.tick_count, 100);
+        let last_total: u32 = samples[9].populations.values().sum();
+        assert_eq!(last_total, 48 * 48);
+
+        assert_eq!(runner.run(7, 3).len(), 2);
+    }
+
+    #[test]
+    fn test_headless_run_feeds_event_logger() {
+        // Lime with no green starves on the first tick
+        let mut runner = BatchRunner::new(16, 16, 2);
+        runner.grid.set_cell(5, 5, CellType::Lime);
+        let mut logger = EventLogger::default();
+        logger.observe(0, &calculate_stats(&runner.grid));
+        runner.events = Some(logger);
+
+        runner.run(3, 1);
+        let events = runner.events.as_ref().unwrap().events();
+        assert_eq!(events[0].tick, 1);
+        assert_eq!(events[0].event, EcosystemEvent::Extinction { cell_type: CellType::Lime });
+    }
+
+    #[test]
+    fn test_ensemble_run_summarizes_each_seed() {
+        let preset = presets::load_preset("balanced").unwrap();
+        let summary = ensemble_run(40, 40, &preset, 10, &[1, 2, 3]);
+        assert_eq!(summary.samples, 3);
+
+        for metric in [summary.health_score, summary.diversity_index, summary.stability] {
+            assert!((0.0..=1.0).contains(&metric.mean), "{:?}", metric);
+            assert!(metric.std_dev >= 0.0 && metric.std_dev.is_finite());
+        }
+        assert!((0.0..=100.0).contains(&summary.green_coverage.mean));
+        assert!(summary.orange_population.mean <= 1600.0);
+
+        // Same seeds, same summary
+        let again = ensemble_run(40, 40, &preset, 10, &[1, 2, 3]);
+        assert_eq!(again.health_score, summary.health_score);
+    }
+}
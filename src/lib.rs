@@ -1,6 +1,7 @@
 #![allow(dead_code)]
 
 pub mod cell;
+pub mod config;
 pub mod grid;
 pub mod rules;
 pub mod stats;
@@ -8,44 +9,511 @@ pub mod presets;
 pub mod genetics;
 pub mod ml_layer;
 pub mod nca;
+#[cfg(feature = "gui")]
 pub mod metrics;
 pub mod logging;
+pub mod wire;
+pub mod storage;
+pub mod replay;
+pub mod perturb;
+pub mod species;
+pub mod checkpoint;
+pub mod headless;
+#[cfg(feature = "gui")]
+pub mod render;
+pub mod events;
+#[cfg(feature = "gui")]
+pub mod worker;
+#[cfg(test)]
+mod testutil;
 
-pub use cell::{Cell, CellType, Genes};
+pub use cell::{Cell, CellCategory, CellType, Genes};
+pub use config::RuleConfig;
 pub use grid::Grid;
-pub use rules::apply_rules;
+pub use rules::{apply_rules, apply_rules_ml, apply_rules_with_rng};
 pub use stats::{calculate_stats, get_ecosystem_status};
 pub use presets::{load_preset, Preset};
 pub use genetics::check_reproduction;
+#[cfg(feature = "gui")]
+pub use metrics::{DashboardSnapshot, MetricsCollector};
+pub use perturb::{Perturbation, Rect};
+
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/// Outcome of `Simulator::step_many`
+#[derive(Debug, Clone)]
+pub struct SweepResult {
+    /// Ticks actually run by this call
+    pub ticks_run: u64,
+    /// Simulator tick count when the sweep stopped
+    pub stop_tick: u64,
+    /// True if the grid went inert before the tick budget ran out
+    pub stopped_early: bool,
+    pub stats: stats::EcosystemStats,
+    pub status: String,
+}
+
+/// Type, age and genes of one cell, as returned by `Simulator::get_cell_full`
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct CellInfo {
+    pub cell_type: u8,
+    pub age: u8,
+    pub generation: u8,
+    pub spread_tendency: f64,
+    pub aggression: f64,
+    pub vitality: f64,
+    pub mutatability: f64,
+}
+
+impl From<&Cell> for CellInfo {
+    fn from(cell: &Cell) -> Self {
+        CellInfo {
+            cell_type: cell.to_u8(),
+            age: cell.age,
+            generation: cell.genes.generation,
+            spread_tendency: cell.genes.spread_tendency,
+            aggression: cell.genes.aggression,
+            vitality: cell.genes.vitality,
+            mutatability: cell.genes.mutatability,
+        }
+    }
+}
+
+/// What happened on one tick, as yielded by `Simulator::states`
+#[derive(Debug, Clone)]
+pub struct TickReport {
+    /// Tick count after this tick
+    pub tick: u64,
+    /// Cells of each type, indexed by `CellType::to_u8`
+    pub type_counts: [u32; CellType::COUNT],
+    pub health_score: f64,
+    pub status: String,
+    /// True when the ecosystem status is "Collapsed"
+    pub collapsed: bool,
+}
+
+/// One tick's output, as sent by `Simulator::run_streaming`
+#[derive(Debug, Clone)]
+pub struct FramePayload {
+    /// Tick count after this tick
+    pub tick: u64,
+    /// Cell type ids in row-major order (`Simulator::get_grid_data`)
+    pub bytes: Vec<u8>,
+    /// Cells of every type by name, extinct ones included
+    pub populations: HashMap<String, u32>,
+}
 
 pub struct Simulator {
     pub grid: Grid,
     pub tick_count: u64,
+    pub rule_config: RuleConfig,
+    #[cfg(feature = "gui")]
+    pub metrics: MetricsCollector,
+    dominance: Vec<(u64, CellType)>,
+    // Source of every random draw: ticks, preset placement, perturbations
+    rng: ChaCha8Rng,
+    seed: u64,
+    // Pre-tick cell snapshots for `step_back`, oldest first
+    history: VecDeque<Vec<Cell>>,
+    history_capacity: usize,
+    population_history: Option<stats::PopulationHistory>,
 }
 
 impl Simulator {
+    /// A simulator with a random seed (see `seed` to reproduce it later)
     pub fn new(width: u32, height: u32) -> Self {
+        Self::new_seeded(width, height, rand::random())
+    }
+
+    /// A simulator whose runs are reproducible
+    ///
+    /// Same seed, size and preset give byte-identical grids after any number
+    /// of ticks.
+    pub fn new_seeded(width: u32, height: u32, seed: u64) -> Self {
         let w = if width == 0 { 1200 } else { width };
         let h = if height == 0 { 1200 } else { height };
+        Self::with_grid(Grid::new(w, h), seed)
+    }
+
+    /// A fresh simulator around an existing grid, its RNG seeded with `seed`
+    pub(crate) fn with_grid(grid: Grid, seed: u64) -> Self {
         Simulator {
-            grid: Grid::new(w, h),
+            grid,
             tick_count: 0,
+            rule_config: RuleConfig::default(),
+            #[cfg(feature = "gui")]
+            metrics: MetricsCollector::new(),
+            dominance: Vec::new(),
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            seed,
+            history: VecDeque::new(),
+            history_capacity: 0,
+            population_history: None,
         }
     }
 
+    /// Seed this simulator's RNG was created from
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
     pub fn initialize_random(&mut self, densities: &serde_json::Map<String, serde_json::Value>) {
-        self.grid.initialize_random(densities);
+        self.grid.initialize_random_with_rng(densities, &mut self.rng);
     }
 
     pub fn tick(&mut self) {
-        apply_rules(&mut self.grid);
+        if self.history_capacity > 0 {
+            if self.history.len() == self.history_capacity {
+                self.history.pop_front();
+            }
+            self.history.push_back(self.grid.snapshot_cells());
+        }
+        #[cfg(feature = "gui")]
+        let start = Instant::now();
+        apply_rules_with_rng(&mut self.grid, &self.rule_config, &mut self.rng);
+        #[cfg(feature = "gui")]
+        self.metrics.record_tick(start.elapsed());
         self.tick_count += 1;
+        self.track_dominance();
+        if let Some(history) = &mut self.population_history {
+            history.record(&self.grid);
+        }
+    }
+
+    /// Keep the pre-tick grid of the last `capacity` ticks so `step_back` can rewind
+    ///
+    /// Each snapshot is a full copy of the cells, genes included. A capacity
+    /// of 0 turns history off and drops what was kept.
+    pub fn enable_history(&mut self, capacity: usize) {
+        self.history_capacity = capacity;
+        while self.history.len() > capacity {
+            self.history.pop_front();
+        }
+    }
+
+    /// Undo the most recent tick; false once history is exhausted
+    ///
+    /// Only the grid and tick count rewind. The RNG keeps its position, so
+    /// ticking again from a rewound state takes a different path.
+    pub fn step_back(&mut self) -> bool {
+        let Some(tick_count) = self.tick_count.checked_sub(1) else {
+            return false;
+        };
+        let Some(cells) = self.history.pop_back() else {
+            return false;
+        };
+        self.grid.restore_cells(cells);
+        self.tick_count = tick_count;
+        true
+    }
+
+    /// Record every type's population after each tick from now on
+    ///
+    /// `capacity` bounds how many ticks are kept (oldest dropped first);
+    /// None keeps the whole run. Replaces any history already recorded.
+    pub fn enable_population_history(&mut self, capacity: Option<usize>) {
+        self.population_history = Some(stats::PopulationHistory::new(capacity));
+    }
+
+    pub fn disable_population_history(&mut self) {
+        self.population_history = None;
+    }
+
+    pub fn population_history(&self) -> Option<&stats::PopulationHistory> {
+        self.population_history.as_ref()
+    }
+
+    /// Mutable access, needed to read a series as one slice
+    pub fn population_history_mut(&mut self) -> Option<&mut stats::PopulationHistory> {
+        self.population_history.as_mut()
+    }
+
+    /// Tick until `wall` has elapsed; returns ticks completed and final stats
+    pub fn run_for_duration(&mut self, wall: Duration) -> (u64, stats::EcosystemStats) {
+        self.run_for_duration_with_progress(wall, None)
+    }
+
+    /// `run_for_duration`, logging progress every `progress_every` if set
+    ///
+    /// The clock is checked between batches of ticks rather than after
+    /// every tick. Batches start at one tick and double (up to 64) while a
+    /// batch stays well under the budget, so slow grids don't overshoot.
+    pub fn run_for_duration_with_progress(
+        &mut self,
+        wall: Duration,
+        progress_every: Option<Duration>,
+    ) -> (u64, stats::EcosystemStats) {
+        const MAX_CLOCK_BATCH: u64 = 64;
+
+        let start = Instant::now();
+        let mut last_progress = start;
+        let mut batch = 1;
+        let mut ticks = 0;
+
+        while start.elapsed() < wall {
+            let batch_start = Instant::now();
+            for _ in 0..batch {
+                self.tick();
+            }
+            ticks += batch;
+
+            if batch < MAX_CLOCK_BATCH && batch_start.elapsed() * 16 < wall {
+                batch *= 2;
+            }
+            if let Some(every) = progress_every {
+                if last_progress.elapsed() >= every {
+                    last_progress = Instant::now();
+                    log::info!(
+                        "Headless run: {} ticks in {:.1?} ({:.0} ticks/s)",
+                        ticks,
+                        start.elapsed(),
+                        ticks as f64 / start.elapsed().as_secs_f64()
+                    );
+                }
+            }
+        }
+
+        (ticks, stats::calculate_stats(&self.grid))
+    }
+
+    /// Inject an external disturbance; returns how many cells changed type
+    pub fn perturb(&mut self, perturbation: Perturbation) -> usize {
+        perturbation.apply(&mut self.grid, &mut self.rng)
+    }
+
+    /// Tick once and summarize the result
+    pub fn tick_report(&mut self) -> TickReport {
+        self.tick();
+        let stats = stats::calculate_stats(&self.grid);
+        let status = stats::get_ecosystem_status(&stats);
+        TickReport {
+            tick: self.tick_count,
+            type_counts: self.grid.type_counts(),
+            health_score: stats.health_score,
+            collapsed: status == "Collapsed",
+            status,
+        }
+    }
+
+    /// Endless stream of tick reports; each `next()` runs one tick
+    ///
+    /// Combine with `take`, `take_while(|r| !r.collapsed)` and friends.
+    pub fn states(&mut self) -> impl Iterator<Item = TickReport> + '_ {
+        std::iter::from_fn(move || Some(self.tick_report()))
+    }
+
+    /// Reports for the next `n` ticks
+    pub fn states_for(&mut self, n: usize) -> impl Iterator<Item = TickReport> + '_ {
+        self.states().take(n)
+    }
+
+    /// Run up to `ticks` ticks, sending a `FramePayload` after each
+    ///
+    /// Stops early once the receiver is dropped. Returns how many frames
+    /// were delivered.
+    pub fn run_streaming(&mut self, ticks: u64, tx: std::sync::mpsc::Sender<FramePayload>) -> u64 {
+        let mut sent = 0;
+        for _ in 0..ticks {
+            self.tick();
+            let populations = CellType::all()
+                .zip(self.grid.type_counts())
+                .map(|(cell_type, count)| (cell_type.as_name().to_string(), count))
+                .collect();
+            let frame = FramePayload { tick: self.tick_count, bytes: self.get_grid_data(), populations };
+            if tx.send(frame).is_err() {
+                break;
+            }
+            sent += 1;
+        }
+        sent
+    }
+
+    /// Run up to `n` ticks, stopping early once the grid is extinct
+    ///
+    /// Returns the number of ticks actually run.
+    pub fn tick_n(&mut self, n: u64) -> u64 {
+        let mut ticks_run = 0;
+        while ticks_run < n && !self.grid.is_extinct() {
+            self.tick();
+            ticks_run += 1;
+        }
+        ticks_run
+    }
+
+    /// Run up to `max_ticks` ticks, stopping once `predicate` holds or the grid is extinct
+    ///
+    /// Full stats are costly, so the predicate only sees them every
+    /// `check_every` ticks (at least 1, and after the last tick); a run can
+    /// overshoot the tick where it would first have fired by up to one
+    /// interval. Returns the ticks run.
+    pub fn tick_until(
+        &mut self,
+        predicate: impl Fn(&stats::EcosystemStats) -> bool,
+        max_ticks: u64,
+        check_every: u64,
+    ) -> u64 {
+        let mut ticks_run = 0;
+        while ticks_run < max_ticks {
+            let batch = check_every.max(1).min(max_ticks - ticks_run);
+            let ran = self.tick_n(batch);
+            ticks_run += ran;
+            if ran < batch || predicate(&stats::calculate_stats(&self.grid)) {
+                break;
+            }
+        }
+        ticks_run
+    }
+
+    /// Run up to `ticks` ticks, stopping as soon as the grid is inert
+    ///
+    /// Meant for batch parameter sweeps, where a grid that can no longer
+    /// change is not worth ticking further. See `rules::is_inert`.
+    pub fn step_many(&mut self, ticks: u64) -> SweepResult {
+        let mut ticks_run = 0;
+        while ticks_run < ticks && !rules::is_inert(&self.grid, &self.rule_config) {
+            self.tick();
+            ticks_run += 1;
+        }
+
+        let stats = stats::calculate_stats(&self.grid);
+        SweepResult {
+            ticks_run,
+            stop_tick: self.tick_count,
+            stopped_early: ticks_run < ticks,
+            status: stats::get_ecosystem_status(&stats),
+            stats,
+        }
+    }
+
+    /// Tick until the grid returns to a state seen in the last `max_period` ticks
+    ///
+    /// Returns the period (1 for a fixed point), or None if no state repeats
+    /// within `max_period` ticks. States are compared by `Grid::content_hash`,
+    /// so cell ages and genes are ignored.
+    pub fn detect_cycle(&mut self, max_period: usize) -> Option<usize> {
+        let mut recent = VecDeque::with_capacity(max_period);
+        recent.push_back(self.grid.content_hash());
+        for _ in 0..max_period {
+            self.tick();
+            let hash = self.grid.content_hash();
+            if let Some(back) = recent.iter().rev().position(|&h| h == hash) {
+                return Some(back + 1);
+            }
+            if recent.len() == max_period {
+                recent.pop_front();
+            }
+            recent.push_back(hash);
+        }
+        None
+    }
+
+    /// Ticks at which the most populous cell type changed, with the new leader
+    ///
+    /// The first entry is the leader at the first recorded tick. Ties go to
+    /// the lower type id.
+    pub fn dominance_timeline(&self) -> Vec<(u64, CellType)> {
+        self.dominance.clone()
+    }
+
+    fn track_dominance(&mut self) {
+        let counts = self.grid.type_counts();
+        let leader = counts
+            .iter()
+            .enumerate()
+            .fold(0, |best, (i, &count)| if count > counts[best] { i } else { best });
+        let leader = CellType::from_u8(leader as u8).unwrap_or(CellType::Black);
+
+        if self.dominance.last().map(|&(_, t)| t) != Some(leader) {
+            self.dominance.push((self.tick_count, leader));
+        }
     }
 
     pub fn get_grid_data(&self) -> Vec<u8> {
         self.grid.to_bytes()
     }
 
+    /// Replace the grid with one rebuilt from `Grid::to_bytes` output
+    ///
+    /// Keeps the current edge topology and tick count; undo history is
+    /// dropped. False (and the grid untouched) if the bytes don't describe
+    /// a `width` x `height` grid.
+    pub fn set_grid_from_bytes(&mut self, bytes: &[u8], width: u32, height: u32) -> bool {
+        let Some(mut grid) = Grid::from_bytes(bytes, width, height) else {
+            return false;
+        };
+        grid.wrap = self.grid.wrap;
+        self.grid = grid;
+        self.history.clear();
+        true
+    }
+
+    /// Encode the grid and tick count as a compact binary frame (see `wire`)
+    pub fn to_frame(&self) -> Vec<u8> {
+        wire::encode_frame(&self.grid, self.tick_count)
+    }
+
+    /// Rebuild a simulator from a frame produced by `to_frame`
+    pub fn from_frame(bytes: &[u8]) -> Result<Simulator, wire::FrameError> {
+        let (grid, tick_count) = wire::decode_frame(bytes)?;
+        let mut sim = Simulator::with_grid(grid, rand::random());
+        sim.tick_count = tick_count;
+        Ok(sim)
+    }
+
+    /// Checkpoint the full state (every cell field, tick count, RNG position) to `path`
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        checkpoint::save(self, path)
+    }
+
+    /// Resume from a checkpoint written by `save`
+    ///
+    /// Fails with `InvalidData` on a malformed file or a format version
+    /// this build doesn't know.
+    pub fn load(path: &Path) -> io::Result<Simulator> {
+        checkpoint::load(path)
+    }
+
+    #[cfg(feature = "gui")]
+    /// Write the grid as a PNG with `scale` pixels per cell (0 means 1)
+    pub fn save_frame_png(&self, path: &Path, scale: u32) -> io::Result<()> {
+        std::fs::write(path, render::render_png(&self.grid, scale, None))
+    }
+
+    #[cfg(feature = "gui")]
+    /// Run `ticks` ticks, writing every `frame_stride`-th one to an animated GIF
+    ///
+    /// Frames are taken after ticks `frame_stride`, `2 * frame_stride`, ...,
+    /// so there are `ticks / frame_stride` of them (a stride of 0 captures
+    /// every tick). Each cell is `scale` pixels across and frames advance
+    /// every `frame_delay`. Returns the number of frames written.
+    pub fn record_gif(
+        &mut self,
+        path: &Path,
+        ticks: u64,
+        scale: u32,
+        frame_stride: u64,
+        frame_delay: Duration,
+    ) -> io::Result<usize> {
+        let out = io::BufWriter::new(std::fs::File::create(path)?);
+        let mut gif = render::GifWriter::new(out, self.grid.width, self.grid.height, scale, frame_delay)?;
+        let frame_stride = frame_stride.max(1);
+        let mut frames = 0;
+        for i in 1..=ticks {
+            self.tick();
+            if i % frame_stride == 0 {
+                gif.write_frame(&self.grid)?;
+                frames += 1;
+            }
+        }
+        Ok(frames)
+    }
+
     pub fn get_grid_json(&self) -> String {
         self.grid.to_json()
     }
@@ -59,8 +527,13 @@ impl Simulator {
     }
 
     pub fn reset(&mut self) {
-        self.grid = Grid::new(self.grid.width, self.grid.height);
+        self.grid = Grid::with_topology(self.grid.width, self.grid.height, self.grid.wrap);
         self.tick_count = 0;
+        self.dominance.clear();
+        self.history.clear();
+        if let Some(history) = &mut self.population_history {
+            history.clear();
+        }
     }
 
     pub fn width(&self) -> u32 {
@@ -75,9 +548,44 @@ impl Simulator {
         self.grid.get_cell(x, y).map(|c| c.to_u8())
     }
 
+    /// Type, age and genes of the cell at (x, y)
+    pub fn get_cell_full(&self, x: u32, y: u32) -> Option<CellInfo> {
+        self.grid.get_cell(x, y).map(|cell| CellInfo::from(&cell))
+    }
+
+    /// Genes of the cell at (x, y) as a JSON object
+    pub fn get_cell_genes_json(&self, x: u32, y: u32) -> Option<String> {
+        let cell = self.grid.get_cell(x, y)?;
+        serde_json::to_string(&cell.genes).ok()
+    }
+
     pub fn set_cell(&mut self, x: u32, y: u32, cell_type: u8) {
         if let Some(ct) = cell::CellType::from_u8(cell_type) {
-            self.grid.set_cell(x, y, ct);
+            self.grid.spawn_with_rng(x, y, ct, &mut self.rng);
+        }
+    }
+
+    /// Switch a type's rules on or off by raw type id; invalid ids are ignored
+    ///
+    /// Disabled cells stay on the grid, block space and are counted, but
+    /// don't spread, move, age or reproduce until re-enabled.
+    pub fn set_type_enabled(&mut self, cell_type: u8, enabled: bool) {
+        if let Some(ct) = CellType::from_u8(cell_type) {
+            self.rule_config.disabled.set(ct, !enabled);
+        }
+    }
+
+    /// `Grid::fill_rect` with a raw type id; invalid ids are ignored
+    pub fn paint_rect(&mut self, x0: u32, y0: u32, x1: u32, y1: u32, cell_type: u8) {
+        if let Some(ct) = CellType::from_u8(cell_type) {
+            self.grid.fill_rect_with_rng(x0, y0, x1, y1, ct, &mut self.rng);
+        }
+    }
+
+    /// `Grid::fill_circle` with a raw type id; invalid ids are ignored
+    pub fn paint_circle(&mut self, cx: u32, cy: u32, radius: u32, cell_type: u8) {
+        if let Some(ct) = CellType::from_u8(cell_type) {
+            self.grid.fill_circle_with_rng(cx, cy, radius, ct, &mut self.rng);
         }
     }
 
@@ -92,18 +600,60 @@ impl Simulator {
             "disease_pressure": stats.disease_pressure,
             "diversity_index": stats.diversity_index,
             "stability": stats.stability,
+            "max_generation": stats.max_generation,
+            "avg_generation": stats.avg_generation,
         })).unwrap_or_default()
     }
 
+    /// Set the lineage depth of the cell at (x, y); false if out of bounds
+    ///
+    /// Reproduction is rare at default rates, so this is the practical way
+    /// to exercise generation stats.
+    pub fn seed_generation(&mut self, x: u32, y: u32, generation: u8) -> bool {
+        let Some(mut cell) = self.grid.get_cell(x, y) else {
+            return false;
+        };
+        cell.genes.generation = generation;
+        self.grid.put_cell(x, y, cell).is_some()
+    }
+
+    #[cfg(feature = "gui")]
+    /// Tick, performance metrics and ecosystem stats in one flat struct
+    pub fn dashboard_snapshot(&self) -> DashboardSnapshot {
+        let stats = stats::calculate_stats(&self.grid);
+        DashboardSnapshot::new(self.tick_count, &self.metrics, &stats)
+    }
+
     pub fn load_preset(&mut self, preset_name: &str) -> bool {
         if let Some(densities) = presets::load_preset(preset_name) {
-            self.grid.initialize_random(&densities);
+            self.grid.initialize_random_with_rng(&densities, &mut self.rng);
+            // Earlier ticks belong to the old grid; don't rewind into them
+            self.history.clear();
             true
         } else {
             false
         }
     }
 
+    /// Same as `load_preset`, but cell placement is drawn from `rng`
+    pub fn load_preset_with_rng(&mut self, preset_name: &str, rng: &mut impl rand::Rng) -> bool {
+        if let Some(densities) = presets::load_preset(preset_name) {
+            self.grid.initialize_random_with_rng(&densities, rng);
+            self.history.clear();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Initialize the grid from a JSON preset file (see `presets::load_preset_file`)
+    pub fn load_preset_from_file(&mut self, path: &Path) -> Result<(), presets::PresetError> {
+        let densities = presets::load_preset_file(path)?;
+        self.grid.initialize_random_with_rng(&densities, &mut self.rng);
+        self.history.clear();
+        Ok(())
+    }
+
     pub fn list_presets() -> Vec<String> {
         vec![
             "balanced".to_string(),
@@ -115,3 +665,295 @@ impl Simulator {
         ]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paint(sim: &mut Simulator, cell_type: CellType) {
+        for y in 0..sim.height() {
+            for x in 0..sim.width() {
+                sim.grid.set_cell(x, y, cell_type);
+            }
+        }
+    }
+
+    #[test]
+    fn test_dominance_timeline_records_transitions() {
+        let mut sim = Simulator::new(8, 8);
+
+        // Scripted run: green takes over, holds, then purple replaces it
+        paint(&mut sim, CellType::Green);
+        sim.track_dominance();
+        sim.tick_count = 1;
+        sim.track_dominance();
+        paint(&mut sim, CellType::Purple);
+        sim.tick_count = 2;
+        sim.track_dominance();
+
+        assert_eq!(
+            sim.dominance_timeline(),
+            vec![(0, CellType::Green), (2, CellType::Purple)]
+        );
+    }
+
+    #[test]
+    fn test_same_seed_gives_identical_runs() {
+        let run = |seed: u64| {
+            let mut sim = Simulator::new_seeded(64, 64, seed);
+            assert!(sim.load_preset("balanced"));
+            for _ in 0..50 {
+                sim.tick();
+            }
+            sim.get_grid_data()
+        };
+
+        let first = run(42);
+        assert_eq!(first, run(42));
+        assert_ne!(first, run(43));
+    }
+
+    #[test]
+    fn test_step_back_restores_earlier_ticks() {
+        let mut sim = Simulator::new_seeded(40, 40, 3);
+        assert!(sim.load_preset("balanced"));
+        sim.enable_history(4);
+        assert!(!sim.step_back());
+
+        let mut recorded = Vec::new();
+        for _ in 0..5 {
+            sim.tick();
+            if sim.tick_count == 2 {
+                recorded = sim.grid.snapshot_cells();
+            }
+        }
+        for _ in 0..3 {
+            assert!(sim.step_back());
+        }
+        assert_eq!(sim.tick_count, 2);
+        assert_eq!(sim.grid.snapshot_cells(), recorded);
+
+        // Only four pre-tick states were kept; one of them is still left
+        assert!(sim.step_back());
+        assert!(!sim.step_back());
+        assert_eq!(sim.tick_count, 1);
+
+        // A preset load starts a new run with nothing to rewind
+        sim.tick();
+        assert!(sim.load_preset("balanced"));
+        assert!(!sim.step_back());
+
+        // A replayed load also zeroes the tick count; history can't take it below 0
+        sim.tick();
+        sim.tick_count = 0;
+        assert!(!sim.step_back());
+        assert_eq!(sim.tick_count, 0);
+        sim.tick();
+        replay::Action::LoadPreset { name: "balanced".to_string(), seed: 1 }.apply(&mut sim);
+        assert_eq!(sim.tick_count, 0);
+        assert!(!sim.step_back());
+    }
+
+    #[test]
+    fn test_generation_stats_track_deepest_lineage() {
+        let mut sim = Simulator::new(10, 10);
+        sim.set_cell(1, 1, CellType::Green.to_u8());
+        sim.set_cell(2, 1, CellType::Green.to_u8());
+        assert!(sim.seed_generation(1, 1, 5));
+        // Black cells don't count towards lineage stats
+        assert!(sim.seed_generation(0, 0, 9));
+        assert!(!sim.seed_generation(10, 0, 1));
+
+        let stats = stats::calculate_stats(&sim.grid);
+        assert_eq!(stats.max_generation, 5);
+        assert_eq!(stats.avg_generation, 2.5);
+
+        let json: serde_json::Value = serde_json::from_str(&sim.get_ecosystem_stats()).unwrap();
+        assert_eq!(json["max_generation"], 5);
+    }
+
+    #[test]
+    fn test_get_cell_full_reads_genes_and_age() {
+        let mut sim = Simulator::new(8, 8);
+        sim.set_cell(2, 3, CellType::Teal.to_u8());
+
+        let mut cell = sim.grid.get_cell(2, 3).unwrap();
+        cell.age = 9;
+        cell.genes.aggression = 0.875;
+        cell.genes.generation = 4;
+        sim.grid.set_next_cell(2, 3, cell);
+        sim.grid.swap_buffers();
+
+        let info = sim.get_cell_full(2, 3).unwrap();
+        assert_eq!(info.cell_type, CellType::Teal.to_u8());
+        assert_eq!((info.age, info.generation), (9, 4));
+        assert_eq!(info.aggression, 0.875);
+        assert_eq!(info.vitality, Genes::default().vitality);
+
+        let json = sim.get_cell_genes_json(2, 3).unwrap();
+        let genes: Genes = serde_json::from_str(&json).unwrap();
+        assert_eq!(genes.aggression, 0.875);
+        assert!(sim.get_cell_full(8, 0).is_none());
+    }
+
+    #[test]
+    fn test_step_many_stops_when_grid_dies() {
+        // Lime with no green anywhere dies on its first tick
+        let mut sim = Simulator::new(16, 16);
+        sim.tick_count = 5;
+        for i in 0..4 {
+            sim.grid.set_cell(3 * i, 2 * i, CellType::Lime);
+        }
+
+        let result = sim.step_many(50);
+        assert!(result.stopped_early);
+        assert_eq!(result.ticks_run, 1);
+        assert_eq!(result.stop_tick, 6);
+        assert_eq!(result.status, "Collapsed");
+        assert!(sim.grid.is_extinct());
+
+        // Already dead: nothing to run
+        let again = sim.step_many(50);
+        assert_eq!((again.ticks_run, again.stop_tick), (0, 6));
+    }
+
+    #[test]
+    fn test_step_many_stops_when_nothing_can_act() {
+        // Pearl and idle Indigo never change, once breeding is off
+        let mut sim = Simulator::new(16, 16);
+        sim.grid.set_cell(2, 2, CellType::Pearl);
+        sim.grid.set_cell(3, 2, CellType::Pearl);
+        sim.grid.set_cell(9, 9, CellType::Indigo);
+        assert_eq!(sim.step_many(5).ticks_run, 5);
+
+        sim.rule_config.reproduction.chance = 0.0;
+        let result = sim.step_many(50);
+        assert!(result.stopped_early);
+        assert_eq!(result.ticks_run, 0);
+
+        // Releasing nutrients wakes the Indigo up
+        sim.rule_config.indigo.nutrient_release_rate = 0.5;
+        assert_eq!(sim.step_many(3).ticks_run, 3);
+    }
+
+    #[test]
+    fn test_tick_n_and_tick_until() {
+        let mut sim = Simulator::new_seeded(16, 16, 4);
+        sim.load_preset("balanced");
+        assert_eq!(sim.tick_n(10), 10);
+        assert_eq!(sim.tick_count, 10);
+
+        // A predicate that never fires runs the whole budget
+        assert_eq!(sim.tick_until(|_| false, 23, 5), 23);
+        assert_eq!(sim.tick_count, 33);
+
+        // The predicate fires at the first check where green is rarer than 90%
+        let mut sim = Simulator::new_seeded(16, 16, 4);
+        sim.grid.fill_rect(0, 0, 16, 16, CellType::Green);
+        sim.grid.fill_rect(0, 0, 16, 2, CellType::Purple);
+        let ran = sim.tick_until(|stats| stats.green_coverage < 90.0, 1000, 4);
+        assert!(ran > 0 && ran < 1000 && ran.is_multiple_of(4), "ran {}", ran);
+
+        // Lime with no green dies on its first tick; tick_n stops there
+        let mut sim = Simulator::new_seeded(16, 16, 4);
+        for i in 0..4 {
+            sim.grid.set_cell(3 * i, 2 * i, CellType::Lime);
+        }
+        assert_eq!(sim.tick_n(10), 1);
+        assert_eq!(sim.tick_count, 1);
+        assert_eq!(sim.tick_n(10), 0);
+        assert_eq!(sim.tick_until(|_| false, 10, 3), 0);
+    }
+
+    #[test]
+    fn test_detect_cycle_finds_gray_orange_oscillator() {
+        // Red turns its gray neighbor orange; with no green the orange
+        // starves back to gray the next tick. Pearl fills every other cell
+        // so gray has no black cell to spawn purple into.
+        let mut sim = Simulator::new_seeded(8, 8, 3);
+        sim.rule_config.reproduction.chance = 0.0;
+        sim.paint_rect(0, 0, 8, 8, CellType::Pearl.to_u8());
+        sim.set_cell(3, 3, CellType::Gray.to_u8());
+        sim.set_cell(4, 3, CellType::Red.to_u8());
+
+        assert_eq!(sim.detect_cycle(8), Some(2));
+        assert_eq!(sim.tick_count, 2);
+        assert_eq!(sim.grid.get_cell(3, 3).unwrap().cell_type, CellType::Gray);
+
+        // A single tick of budget can't see a period-2 repeat
+        assert_eq!(sim.detect_cycle(1), None);
+    }
+
+    #[test]
+    fn test_disabled_green_neither_spreads_nor_dies() {
+        let mut sim = Simulator::new_seeded(32, 32, 11);
+        sim.rule_config.green.spread_rate = 1.0;
+        sim.set_type_enabled(CellType::Green.to_u8(), false);
+        for (x, y) in [(4, 4), (16, 20), (28, 9)] {
+            sim.set_cell(x, y, CellType::Green.to_u8());
+        }
+
+        for _ in 0..10 {
+            sim.tick();
+        }
+        assert_eq!(sim.grid.population(CellType::Green), 3);
+        assert_eq!(sim.get_cell(16, 20), Some(CellType::Green.to_u8()));
+
+        sim.set_type_enabled(CellType::Green.to_u8(), true);
+        sim.tick();
+        assert!(sim.grid.population(CellType::Green) > 3);
+    }
+
+    #[test]
+    fn test_states_yield_increasing_ticks() {
+        let mut sim = Simulator::new(24, 24);
+        sim.grid.set_cell(4, 4, CellType::Green);
+
+        let ticks: Vec<u64> = sim.states().take(10).map(|r| r.tick).collect();
+        assert_eq!(ticks, (1..=10).collect::<Vec<_>>());
+        assert_eq!(sim.tick_count, 10);
+
+        let reports: Vec<TickReport> = sim.states_for(3).collect();
+        assert_eq!(reports.len(), 3);
+        assert_eq!(reports[2].tick, 13);
+        assert_eq!(reports[2].type_counts.iter().sum::<u32>(), 24 * 24);
+    }
+
+    #[test]
+    fn test_run_for_duration_returns_promptly() {
+        let mut sim = Simulator::new(32, 32);
+        assert!(sim.load_preset("balanced"));
+
+        let start = Instant::now();
+        let (ticks, stats) = sim.run_for_duration(Duration::from_millis(30));
+        let elapsed = start.elapsed();
+
+        assert!(elapsed >= Duration::from_millis(30));
+        assert!(elapsed < Duration::from_secs(2), "took {:?}", elapsed);
+        assert!(ticks > 0);
+        assert_eq!(ticks, sim.tick_count);
+        assert_eq!(stats.populations.values().sum::<u32>(), 32 * 32);
+    }
+
+    #[test]
+    fn test_run_streaming_sends_sequential_frames() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let runner = std::thread::spawn(move || {
+            let mut sim = Simulator::new_seeded(24, 16, 3);
+            sim.load_preset("balanced");
+            sim.run_streaming(1_000_000, tx)
+        });
+
+        let frames: Vec<FramePayload> = rx.iter().take(5).collect();
+        assert_eq!(frames.iter().map(|f| f.tick).collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+        for frame in &frames {
+            assert_eq!(frame.bytes.len(), 24 * 16);
+            assert_eq!(frame.populations.values().sum::<u32>(), 24 * 16);
+        }
+
+        // Dropping the receiver ends the run long before its tick budget
+        drop(rx);
+        let sent = runner.join().unwrap();
+        assert!((5..1_000_000).contains(&sent));
+    }
+}
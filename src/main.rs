@@ -1,27 +1,31 @@
 use iced::widget::{container, column, row, button, text, slider, text_input};
-use iced::{Element, Subscription, Settings, window, time, Application, Command};
+use iced::{event, mouse, Element, Event, Point, Subscription, Settings, window, time, Application, Command};
+use std::fs::File;
 use std::sync::{Arc, Mutex};
-use std::time::{Duration, Instant};
-
-pub mod cell;
-pub mod grid;
-pub mod rules;
-pub mod stats;
-pub mod presets;
-pub mod genetics;
-pub mod ml_layer;
-pub mod nca;
-pub mod ui;
-pub mod metrics;
-pub mod logging;
-
-use grid::Grid;
-use ui::GridDisplay;
-use metrics::MetricsCollector;
-use logging::init_logging;
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+mod ui;
+
+use cellular_sim::config::RuleConfig;
+use cellular_sim::grid::Grid;
+use cellular_sim::metrics::MetricsCollector;
+use cellular_sim::logging::{self, init_logging};
+use cellular_sim::replay::{Action, Recorder};
+use cellular_sim::presets;
+use cellular_sim::worker::SimWorker;
+use cellular_sim::{CellInfo, CellType};
+use ui::{GridDisplay, Viewport};
 
 const GRID_WIDTH: u32 = 500;
 const GRID_HEIGHT: u32 = 500;
+/// Set to a file path to record the session there (see `replay`); unset, nothing is recorded
+const RECORD_ENV: &str = "CELLULAR_SIM_RECORD";
+/// Redraw interval while running; independent of the tick rate
+const FRAME_INTERVAL: Duration = Duration::from_millis(16);
 
 // ============================================================================
 // Messages
@@ -32,10 +36,19 @@ enum Message {
     Play,
     Pause,
     Reset,
-    SpeedChanged(f32),
     PresetInputChanged(String),
     LoadPreset,
-    Tick,
+    Redraw,
+    // Speed slider position; see tps_from_slider
+    SpeedChanged(f32),
+    // Wheel steps; positive zooms in
+    Zoom(f32),
+    GridPressed,
+    GridReleased,
+    // Cursor position over the grid image
+    GridCursorMoved(Point),
+    // Grid coordinates of a click that wasn't a drag
+    CellClicked(u32, u32),
 }
 
 // ============================================================================
@@ -43,14 +56,66 @@ enum Message {
 // ============================================================================
 
 struct CellularApp {
-    grid: Arc<Mutex<Grid>>,
-    is_running: bool,
-    tick_count: u64,
-    speed: f32,
+    // Owns the grid and ticks it off the UI thread
+    worker: SimWorker,
+    // Ticks per second the worker aims for
+    target_tps: f64,
     selected_preset: String,
-    tick_accumulator: f32,
     metrics: Arc<Mutex<MetricsCollector>>,
-    last_tick_time: Instant,
+    recorder: Option<Recorder<File>>,
+    viewport: Viewport,
+    // Last cursor position over the grid, and where the current drag is anchored
+    cursor: Option<Point>,
+    drag_from: Option<Point>,
+    pressed_at: Option<Point>,
+    // Cell shown in the side panel: position, tick it was read at, and its data
+    inspected: Option<(u32, u32, u64, CellInfo)>,
+}
+
+impl CellularApp {
+    /// Log a user action, applied at worker tick `tick`, to the session recording
+    fn record(&mut self, tick: u64, action: Action) {
+        if let Some(recorder) = self.recorder.as_mut() {
+            if let Err(e) = recorder.record(tick, action) {
+                log::warn!("Session recording stopped: {}", e);
+                self.recorder = None;
+            }
+        }
+    }
+}
+
+/// Slowest and fastest rates the speed slider offers
+const MIN_TPS: f64 = 0.1;
+const MAX_TPS: f64 = 1000.0;
+
+/// The speed slider is logarithmic: its position is log10 of the tick rate
+fn tps_from_slider(position: f32) -> f64 {
+    10f64.powf(position as f64).clamp(MIN_TPS, MAX_TPS)
+}
+
+/// How far the cursor may move between press and release for it to count as a click
+const CLICK_SLOP: f32 = 3.0;
+
+/// Zoom factor per wheel step
+const ZOOM_STEP: f32 = 1.25;
+
+/// Wheel input anywhere a widget hasn't already used it (e.g. the slider)
+fn wheel_zoom(event: Event, status: event::Status) -> Option<Message> {
+    match (event, status) {
+        (Event::Mouse(mouse::Event::WheelScrolled { delta }), event::Status::Ignored) => match delta {
+            mouse::ScrollDelta::Lines { y, .. } => Some(Message::Zoom(y)),
+            mouse::ScrollDelta::Pixels { y, .. } => Some(Message::Zoom(y / 50.0)),
+        },
+        _ => None,
+    }
+}
+
+/// Seeded placement for a preset, so the load can be replayed exactly
+fn load_seeded(grid: &mut Grid, preset: &str) -> Option<u64> {
+    let densities = presets::load_preset(preset)?;
+    let seed = rand::random();
+    grid.initialize_random_with_rng(&densities, &mut StdRng::seed_from_u64(seed));
+    Some(seed)
 }
 
 impl Application for CellularApp {
@@ -64,10 +129,28 @@ impl Application for CellularApp {
         let _ = init_logging();
         
         let mut grid = Grid::new(GRID_WIDTH, GRID_HEIGHT);
+        let seed = rand::random();
+        let mut recorder = match std::env::var_os(RECORD_ENV).filter(|path| !path.is_empty()) {
+            Some(path) => match Recorder::create(&path, GRID_WIDTH, GRID_HEIGHT, seed) {
+                Ok(recorder) => {
+                    log::info!("Recording session to {}", path.to_string_lossy());
+                    Some(recorder)
+                }
+                Err(e) => {
+                    log::warn!("Session recording disabled: {}", e);
+                    None
+                }
+            },
+            None => None,
+        };
         
         // Initialize with sparse genesis preset by default
-        if let Some(densities) = presets::load_preset("sparse_genesis") {
-            grid.initialize_random(&densities);
+        if let Some(seed) = load_seeded(&mut grid, "sparse_genesis") {
+            let action = Action::LoadPreset { name: "sparse_genesis".to_string(), seed };
+            if let Some(Err(e)) = recorder.as_mut().map(|r| r.record(0, action)) {
+                log::warn!("Session recording stopped: {}", e);
+                recorder = None;
+            }
         } else {
             // Fallback: random initialization if preset fails
             grid.initialize_random(&serde_json::json!({
@@ -80,16 +163,29 @@ impl Application for CellularApp {
         
         logging::log_startup_info(GRID_WIDTH, GRID_HEIGHT, "sparse_genesis");
         
+        let target_tps = 60.0;
+        let metrics = Arc::new(Mutex::new(MetricsCollector::new()));
+        // Tick RNG; its seed goes in the recording header
+        let worker = SimWorker::spawn(
+            Arc::new(Mutex::new(grid)),
+            RuleConfig::default(),
+            ChaCha8Rng::seed_from_u64(seed),
+            target_tps,
+            Arc::clone(&metrics),
+        );
+
         (
             CellularApp {
-                grid: Arc::new(Mutex::new(grid)),
-                is_running: false,
-                tick_count: 0,
-                speed: 1.0,
+                worker,
+                target_tps,
                 selected_preset: "sparse_genesis".to_string(),
-                tick_accumulator: 0.0,
-                metrics: Arc::new(Mutex::new(MetricsCollector::new())),
-                last_tick_time: Instant::now(),
+                metrics,
+                recorder,
+                viewport: Viewport::default(),
+                cursor: None,
+                drag_from: None,
+                pressed_at: None,
+                inspected: None,
             },
             Command::none(),
         )
@@ -102,83 +198,101 @@ impl Application for CellularApp {
     fn update(&mut self, message: Message) -> Command<Message> {
         match message {
             Message::Play => {
-                self.is_running = true;
+                let tick = self.worker.set_running(true);
+                self.record(tick, Action::Play);
             }
             Message::Pause => {
-                self.is_running = false;
+                let tick = self.worker.set_running(false);
+                self.record(tick, Action::Pause);
             }
             Message::Reset => {
-                if let Ok(mut grid) = self.grid.lock() {
-                    let width = grid.width;
-                    let height = grid.height;
-                    *grid = Grid::new(width, height);
-                    // Reinitialize with current preset
-                    if let Some(densities) = presets::load_preset(&self.selected_preset) {
-                        grid.initialize_random(&densities);
-                    } else {
-                        // Fallback to balanced
-                        if let Some(densities) = presets::load_preset("balanced") {
-                            grid.initialize_random(&densities);
-                        }
+                self.worker.set_running(false);
+                let preset = self.selected_preset.clone();
+                let edited = self.worker.edit(true, |grid| {
+                    *grid = Grid::with_topology(grid.width, grid.height, grid.wrap);
+                    // Reinitialize with current preset, falling back to balanced
+                    load_seeded(grid, &preset)
+                        .map(|seed| (preset, seed))
+                        .or_else(|| load_seeded(grid, "balanced").map(|seed| ("balanced".to_string(), seed)))
+                });
+                if let Some((tick, loaded)) = edited {
+                    self.record(tick, Action::Reset);
+                    if let Some((name, seed)) = loaded {
+                        self.record(tick, Action::LoadPreset { name, seed });
                     }
-                    self.tick_count = 0;
                 }
-                self.is_running = false;
             }
-            Message::SpeedChanged(speed) => {
-                self.speed = speed.max(0.1);
+            Message::SpeedChanged(position) => {
+                self.target_tps = tps_from_slider(position);
+                self.worker.set_ticks_per_second(self.target_tps);
+                // Informational only, so the tick it's logged at needn't be exact
+                self.record(self.worker.tick_count(), Action::SpeedChanged(self.target_tps as f32));
             }
             Message::PresetInputChanged(preset) => {
                 self.selected_preset = preset;
             }
             Message::LoadPreset => {
-                if let Ok(mut grid) = self.grid.lock() {
-                    if let Some(densities) = presets::load_preset(&self.selected_preset) {
-                        grid.initialize_random(&densities);
-                        self.tick_count = 0;
+                // Unknown names leave the grid and tick count alone
+                let name = self.selected_preset.clone();
+                if presets::load_preset(&name).is_some() {
+                    if let Some((tick, Some(seed))) = self.worker.edit(true, |grid| load_seeded(grid, &name)) {
+                        self.record(tick, Action::LoadPreset { name, seed });
                     }
                 }
             }
-            Message::Tick => {
-                if self.is_running {
-                    // Accumulate tick time based on speed
-                    self.tick_accumulator += self.speed;
-                    
-                    // Execute ticks when accumulated time >= 1.0
-                    while self.tick_accumulator >= 1.0 {
-                        let tick_start = Instant::now();
-                        if let Ok(mut grid) = self.grid.lock() {
-                            rules::apply_rules(&mut grid);
-                            self.tick_count += 1;
-                            
-                            // Record tick performance
-                            let tick_duration = tick_start.elapsed();
-                            if let Ok(mut metrics) = self.metrics.lock() {
-                                metrics.record_tick(tick_duration);
-                                let cells = (grid.width as u64) * (grid.height as u64);
-                                logging::log_tick_performance(self.tick_count, tick_duration.as_secs_f64() * 1000.0, cells);
-                            }
-                        }
-                        self.tick_accumulator -= 1.0;
-                    }
-                }
-                
-                // Record frame and update metrics
+            Message::Redraw => {
+                // The worker ticks on its own; this only paces the view
                 if let Ok(mut metrics) = self.metrics.lock() {
                     metrics.record_frame();
                     metrics.record_metrics(GRID_WIDTH);
                 }
             }
+            Message::Zoom(steps) => {
+                self.viewport = self.viewport.zoom_by(ZOOM_STEP.powf(steps), GRID_WIDTH, GRID_HEIGHT);
+            }
+            Message::GridPressed => {
+                self.drag_from = self.cursor;
+                self.pressed_at = self.cursor;
+            }
+            Message::GridReleased => {
+                self.drag_from = None;
+                let click = self.pressed_at.take().zip(self.cursor)
+                    .filter(|(from, to)| from.distance(*to) <= CLICK_SLOP)
+                    .and_then(|(_, at)| self.viewport.cell_at(at.x, at.y, GRID_WIDTH, GRID_HEIGHT));
+                if let Some((x, y)) = click {
+                    return self.update(Message::CellClicked(x, y));
+                }
+            }
+            Message::CellClicked(x, y) => {
+                let info = match self.worker.grid().lock() {
+                    Ok(grid) => grid.get_cell(x, y).map(|cell| CellInfo::from(&cell)),
+                    Err(_) => None,
+                };
+                self.inspected = info.map(|info| (x, y, self.worker.tick_count(), info));
+            }
+            Message::GridCursorMoved(position) => {
+                if let Some(from) = self.drag_from {
+                    self.viewport = self.viewport.pan_by_pixels(
+                        position.x - from.x,
+                        position.y - from.y,
+                        GRID_WIDTH,
+                        GRID_HEIGHT,
+                    );
+                    self.drag_from = Some(position);
+                }
+                self.cursor = Some(position);
+            }
         }
         Command::none()
     }
 
     fn subscription(&self) -> Subscription<Message> {
-        if self.is_running {
-            time::every(Duration::from_millis(16)).map(|_| Message::Tick)
+        let redraw = if self.worker.is_running() {
+            time::every(FRAME_INTERVAL).map(|_| Message::Redraw)
         } else {
             Subscription::none()
-        }
+        };
+        Subscription::batch([redraw, event::listen_with(wheel_zoom)])
     }
 
     fn view(&self) -> Element<'_, Message> {
@@ -202,14 +316,18 @@ impl Application for CellularApp {
         let reset_btn = button("↻ Reset")
             .on_press(Message::Reset);
 
-        let speed_label = text(format!("Speed: {:.1}x", self.speed));
-        let speed_slider = slider(0.1..=10.0, self.speed, Message::SpeedChanged)
+        let speed_label = text(format!("Speed: {:.1} ticks/s", self.target_tps));
+        let slider_range = MIN_TPS.log10() as f32..=MAX_TPS.log10() as f32;
+        let speed_slider = slider(slider_range, self.target_tps.log10() as f32, Message::SpeedChanged)
+            .step(0.01)
             .width(iced::Length::Fixed(200.0));
 
-        let status = if self.is_running {
-            text(format!("▶ Running | Ticks: {}", self.tick_count)).size(14)
+        let is_running = self.worker.is_running();
+        let tick_count = self.worker.tick_count();
+        let status = if is_running {
+            text(format!("▶ Running | Ticks: {}", tick_count)).size(14)
         } else {
-            text(format!("⏸ Paused | Ticks: {}", self.tick_count)).size(14)
+            text(format!("⏸ Paused | Ticks: {}", tick_count)).size(14)
         };
 
         let controls = row![
@@ -224,13 +342,15 @@ impl Application for CellularApp {
 
         // Get metrics for display
         let metrics_text = if let Ok(metrics) = self.metrics.lock() {
-            let status_str = metrics.get_status_string(self.tick_count, self.is_running);
+            let status_str = metrics.get_status_string(tick_count, is_running);
             text(status_str).size(11)
         } else {
             text("Metrics unavailable").size(11)
         };
 
-        let grid_display = GridDisplay::new(Arc::clone(&self.grid));
+        let grid_display = GridDisplay::new(self.worker.latest_frame())
+            .viewport(self.viewport)
+            .on_mouse(Message::GridPressed, Message::GridReleased, Message::GridCursorMoved);
 
         let main_column = column![
             presets,
@@ -241,7 +361,28 @@ impl Application for CellularApp {
         ]
         .spacing(10);
 
-        container(main_column)
+        let inspector = match &self.inspected {
+            Some((x, y, tick, info)) => {
+                let type_name = CellType::from_u8(info.cell_type).map_or("Unknown", |t| t.as_name());
+                column![
+                    text(format!("Cell ({}, {})", x, y)).size(16),
+                    text(format!("As of tick {}", tick)).size(11),
+                    text(format!("Type: {}", type_name)).size(14),
+                    text(format!("Age: {}", info.age)).size(14),
+                    text(format!("Generation: {}", info.generation)).size(14),
+                    text(format!("Spread tendency: {:.3}", info.spread_tendency)).size(14),
+                    text(format!("Aggression: {:.3}", info.aggression)).size(14),
+                    text(format!("Vitality: {:.3}", info.vitality)).size(14),
+                    text(format!("Mutatability: {:.3}", info.mutatability)).size(14),
+                ]
+            }
+            None => column![text("Click a cell to inspect it").size(14)],
+        }
+        .spacing(6)
+        .padding(10)
+        .width(iced::Length::Fixed(240.0));
+
+        container(row![main_column, inspector].spacing(10))
             .padding(10)
             .width(iced::Length::Fill)
             .height(iced::Length::Fill)
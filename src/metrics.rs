@@ -1,8 +1,15 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 use std::time::{Instant, Duration};
-use std::collections::VecDeque;
-use sysinfo::System;
+use std::collections::{HashMap, VecDeque};
+use sysinfo::{Pid, System, MINIMUM_CPU_UPDATE_INTERVAL};
 use log::info;
+use serde::Serialize;
+
+use crate::stats::{get_ecosystem_status, EcosystemStats};
 
 #[derive(Clone, Debug)]
 pub struct FrameMetrics {
@@ -14,18 +21,92 @@ pub struct FrameMetrics {
     pub grid_size: u32,
 }
 
+/// Performance and ecosystem figures for one tick, flattened for dashboards
+///
+/// Serializes to a single flat JSON object, so monitoring UIs and exporters
+/// don't need to query `MetricsCollector` and `stats` separately.
+#[derive(Clone, Debug, Serialize)]
+pub struct DashboardSnapshot {
+    pub tick: u64,
+    pub fps: f64,
+    pub tps: f64,
+    pub cpu_percent: f32,
+    pub ram_mb: f64,
+    pub avg_tick_time_ms: f64,
+    pub status: String,
+    pub health_score: f64,
+    pub green_coverage: f64,
+    pub orange_population: u32,
+    pub predator_count: u32,
+    pub disease_pressure: f64,
+    pub diversity_index: f64,
+    pub stability: f64,
+    pub populations: HashMap<String, u32>,
+}
+
+impl DashboardSnapshot {
+    pub fn new(tick: u64, metrics: &MetricsCollector, stats: &EcosystemStats) -> Self {
+        DashboardSnapshot {
+            tick,
+            fps: metrics.get_fps(),
+            tps: metrics.get_tps(),
+            cpu_percent: metrics.get_cpu_usage(),
+            ram_mb: metrics.get_ram_usage_mb(),
+            avg_tick_time_ms: metrics.get_avg_tick_time_ms(),
+            status: get_ecosystem_status(stats),
+            health_score: stats.health_score,
+            green_coverage: stats.green_coverage,
+            orange_population: stats.orange_population,
+            predator_count: stats.predator_count,
+            disease_pressure: stats.disease_pressure,
+            diversity_index: stats.diversity_index,
+            stability: stats.stability,
+            populations: stats.populations.clone(),
+        }
+    }
+}
+
+/// Default minimum time between system (CPU/RAM) samples in `record_metrics`
+pub const SYSTEM_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
 pub struct MetricsCollector {
     start_time: Instant,
     last_frame_time: Instant,
     frame_times: VecDeque<Duration>,
     tick_times: VecDeque<Duration>,
     system: Arc<Mutex<System>>,
+    /// This process, if sysinfo can identify it
+    pid: Option<Pid>,
+    /// Last valid process CPU reading, as a percentage of one core
+    cpu_percent: f32,
+    last_cpu_refresh: Instant,
+    /// Used system memory as of the last system sample
+    ram_mb: f64,
+    sample_interval: Duration,
+    last_system_sample: Option<Instant>,
+    /// Append p99 and max frame times to `get_status_string`
+    show_jitter: bool,
     history: Vec<FrameMetrics>,
 }
 
+impl Default for MetricsCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl MetricsCollector {
     pub fn new() -> Self {
-        let system = System::new_all();
+        // sysinfo derives CPU usage from the difference between two process
+        // refreshes, so take the first one now and let later ones measure
+        // from here
+        let mut system = System::new();
+        let pid = sysinfo::get_current_pid().ok();
+        if let Some(pid) = pid {
+            system.refresh_process(pid);
+        }
+        system.refresh_memory();
+        let ram_mb = used_memory_mb(&system);
         
         MetricsCollector {
             start_time: Instant::now(),
@@ -33,6 +114,13 @@ impl MetricsCollector {
             frame_times: VecDeque::with_capacity(120),
             tick_times: VecDeque::with_capacity(120),
             system: Arc::new(Mutex::new(system)),
+            pid,
+            cpu_percent: 0.0,
+            last_cpu_refresh: Instant::now(),
+            ram_mb,
+            sample_interval: SYSTEM_SAMPLE_INTERVAL,
+            last_system_sample: None,
+            show_jitter: false,
             history: Vec::new(),
         }
     }
@@ -86,25 +174,59 @@ impl MetricsCollector {
         }
     }
 
+    /// This process's CPU usage as of the last `refresh_cpu_usage`
+    ///
+    /// A percentage of one core, so it can reach `100 * cores`. Reads 0.0
+    /// until the first refresh after construction.
     pub fn get_cpu_usage(&self) -> f32 {
-        if let Ok(mut system) = self.system.lock() {
-            system.refresh_all();
-            if let Some(pid) = sysinfo::get_current_pid().ok() {
-                if let Some(process) = system.process(pid) {
-                    return process.cpu_usage();
-                }
+        self.cpu_percent
+    }
+
+    /// Re-measure this process's CPU usage; false if it was too soon to
+    ///
+    /// Readings closer together than `sysinfo::MINIMUM_CPU_UPDATE_INTERVAL`
+    /// aren't meaningful, so those calls keep the cached value.
+    pub fn refresh_cpu_usage(&mut self) -> bool {
+        if self.last_cpu_refresh.elapsed() < MINIMUM_CPU_UPDATE_INTERVAL {
+            return false;
+        }
+        let (Some(pid), Ok(mut system)) = (self.pid, self.system.lock()) else {
+            return false;
+        };
+        system.refresh_process(pid);
+        if let Some(usage) = system.process(pid).map(|process| process.cpu_usage()) {
+            if usage.is_finite() && usage >= 0.0 {
+                self.cpu_percent = usage;
             }
         }
-        0.0
+        self.last_cpu_refresh = Instant::now();
+        true
     }
 
+    /// Used system memory as of the last system sample
     pub fn get_ram_usage_mb(&self) -> f64 {
+        self.ram_mb
+    }
+
+    /// Minimum time between system samples taken by `record_metrics`
+    pub fn set_sample_interval(&mut self, interval: Duration) {
+        self.sample_interval = interval;
+    }
+
+    /// Refresh CPU and RAM unless the last sample is under `sample_interval` old
+    ///
+    /// Returns whether the system was queried.
+    fn sample_system(&mut self) -> bool {
+        if self.last_system_sample.is_some_and(|last| last.elapsed() < self.sample_interval) {
+            return false;
+        }
+        self.refresh_cpu_usage();
         if let Ok(mut system) = self.system.lock() {
             system.refresh_memory();
-            (system.used_memory() as f64) / 1024.0 / 1024.0
-        } else {
-            0.0
+            self.ram_mb = used_memory_mb(&system);
         }
+        self.last_system_sample = Some(Instant::now());
+        true
     }
 
     pub fn get_avg_frame_time_ms(&self) -> f64 {
@@ -125,7 +247,14 @@ impl MetricsCollector {
         sum.as_secs_f64() * 1000.0 / self.tick_times.len() as f64
     }
 
+    /// Append a sample to the history
+    ///
+    /// FPS and TPS come from the in-memory timers on every call; CPU and RAM
+    /// are re-read at most once per sample interval (see `set_sample_interval`)
+    /// and repeat the cached values in between, so calling this every frame
+    /// stays cheap.
     pub fn record_metrics(&mut self, grid_size: u32) {
+        self.sample_system();
         let metrics = FrameMetrics {
             timestamp: Instant::now(),
             fps: self.get_fps(),
@@ -143,6 +272,52 @@ impl MetricsCollector {
         }
     }
 
+    /// Frame time (ms) at quantile `p` (0.0-1.0, clamped) of the recent window
+    ///
+    /// Nearest-rank over a sorted copy of the window, so the result is
+    /// always one of the recorded frames. 0.0 with no frames yet.
+    pub fn frame_time_percentile(&self, p: f64) -> f64 {
+        percentile_ms(&self.frame_times, p)
+    }
+
+    /// Tick time (ms) at quantile `p`; see `frame_time_percentile`
+    pub fn tick_time_percentile(&self, p: f64) -> f64 {
+        percentile_ms(&self.tick_times, p)
+    }
+
+    /// Longest frame (ms) in the recent window, 0.0 with no frames yet
+    pub fn frame_time_max(&self) -> f64 {
+        self.frame_times.iter().max().map_or(0.0, |d| d.as_secs_f64() * 1000.0)
+    }
+
+    /// Include p99 and max frame times in `get_status_string`
+    pub fn set_show_jitter(&mut self, show: bool) {
+        self.show_jitter = show;
+    }
+
+    /// Recorded samples as CSV, one row per `record_metrics` call
+    ///
+    /// Columns: `elapsed_s,fps,tps,cpu_percent,ram_mb,grid_size`, where
+    /// `elapsed_s` is seconds since this collector was created. With no
+    /// samples yet, only the header row is written.
+    pub fn to_csv_string(&self) -> String {
+        let mut csv = String::from("elapsed_s,fps,tps,cpu_percent,ram_mb,grid_size\n");
+        for m in &self.history {
+            let elapsed = m.timestamp.saturating_duration_since(self.start_time).as_secs_f64();
+            let _ = writeln!(
+                csv,
+                "{:.3},{:.2},{:.2},{:.1},{:.1},{}",
+                elapsed, m.fps, m.tps, m.cpu_percent, m.ram_mb, m.grid_size
+            );
+        }
+        csv
+    }
+
+    /// Write `to_csv_string` to `path`
+    pub fn export_csv(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_csv_string())
+    }
+
     pub fn log_summary(&self, tick_count: u64) {
         let uptime = self.start_time.elapsed();
         
@@ -158,15 +333,139 @@ impl MetricsCollector {
     }
 
     pub fn get_status_string(&self, tick_count: u64, is_running: bool) -> String {
+        let jitter = if self.show_jitter {
+            format!(" | p99 Frame: {:.2}ms | Max Frame: {:.2}ms", self.frame_time_percentile(0.99), self.frame_time_max())
+        } else {
+            String::new()
+        };
         format!(
-            "Tick: {} | FPS: {:.1} | TPS: {:.1} | CPU: {:.1}% | RAM: {:.0}MB | Avg Frame: {:.2}ms | Status: {}",
+            "Tick: {} | FPS: {:.1} | TPS: {:.1} | CPU: {:.1}% | RAM: {:.0}MB | Avg Frame: {:.2}ms{} | Status: {}",
             tick_count,
             self.get_fps(),
             self.get_tps(),
             self.get_cpu_usage(),
             self.get_ram_usage_mb(),
             self.get_avg_frame_time_ms(),
+            jitter,
             if is_running { "Running" } else { "Paused" }
         )
     }
 }
+
+/// Nearest-rank percentile of a duration window, in milliseconds
+fn percentile_ms(window: &VecDeque<Duration>, p: f64) -> f64 {
+    if window.is_empty() {
+        return 0.0;
+    }
+    let mut sorted: Vec<Duration> = window.iter().copied().collect();
+    sorted.sort_unstable();
+    let rank = (p.clamp(0.0, 1.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1].as_secs_f64() * 1000.0
+}
+
+fn used_memory_mb(system: &System) -> f64 {
+    (system.used_memory() as f64) / 1024.0 / 1024.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Simulator;
+
+    #[test]
+    fn test_dashboard_snapshot_has_metrics_and_stats() {
+        let mut sim = Simulator::new(32, 32);
+        sim.load_preset("balanced");
+        sim.tick();
+
+        let snapshot = sim.dashboard_snapshot();
+        assert_eq!(snapshot.tick, 1);
+        assert!(snapshot.tps > 0.0);
+
+        let json = serde_json::to_value(&snapshot).unwrap();
+        assert!(json.get("tps").is_some_and(|v| v.is_number()));
+        assert!(json.get("green_coverage").is_some_and(|v| v.is_number()));
+    }
+
+    #[test]
+    fn test_metrics_csv_has_row_per_sample() {
+        let mut metrics = MetricsCollector::new();
+        assert_eq!(metrics.to_csv_string(), "elapsed_s,fps,tps,cpu_percent,ram_mb,grid_size\n");
+
+        for _ in 0..3 {
+            metrics.record_tick(Duration::from_millis(4));
+            metrics.record_metrics(64 * 64);
+        }
+        let path = std::env::temp_dir().join(format!("cellular_sim_metrics_{}.csv", std::process::id()));
+        metrics.export_csv(&path).unwrap();
+        let csv = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let rows: Vec<Vec<&str>> = csv.lines().skip(1).map(|line| line.split(',').collect()).collect();
+        assert_eq!(rows.len(), 3);
+        for row in rows {
+            assert_eq!(row.len(), 6);
+            assert!(row[0].parse::<f64>().unwrap() >= 0.0);
+            assert_eq!(row[2].parse::<f64>().unwrap(), 250.0);
+            assert_eq!(row[5], "4096");
+        }
+    }
+
+    #[test]
+    fn test_cpu_usage_stays_in_range_after_timed_refreshes() {
+        let mut metrics = MetricsCollector::new();
+        assert!(!metrics.refresh_cpu_usage(), "refresh right after construction is too soon");
+
+        let cores = std::thread::available_parallelism().map_or(1, |n| n.get());
+        for _ in 0..3 {
+            // Burn some CPU so there is something to measure
+            let deadline = Instant::now() + MINIMUM_CPU_UPDATE_INTERVAL;
+            let mut x = 0u64;
+            while Instant::now() < deadline {
+                x = std::hint::black_box(x.wrapping_mul(31).wrapping_add(7));
+            }
+            metrics.refresh_cpu_usage();
+            let cpu = metrics.get_cpu_usage();
+            assert!((0.0..=100.0 * cores as f32).contains(&cpu), "cpu {} with {} cores", cpu, cores);
+        }
+    }
+
+    #[test]
+    fn test_back_to_back_records_share_one_system_sample() {
+        let mut metrics = MetricsCollector::new();
+        metrics.record_metrics(64);
+        let sampled_at = metrics.last_system_sample.expect("first record samples the system");
+
+        metrics.record_tick(Duration::from_millis(5));
+        metrics.record_metrics(64);
+        assert_eq!(metrics.last_system_sample, Some(sampled_at));
+        assert_eq!(metrics.history.len(), 2);
+        assert_eq!(metrics.history[1].ram_mb, metrics.history[0].ram_mb);
+        // TPS still follows the in-memory timers
+        assert_eq!(metrics.history[1].tps, 200.0);
+
+        metrics.set_sample_interval(Duration::ZERO);
+        metrics.record_metrics(64);
+        assert!(metrics.last_system_sample.unwrap() > sampled_at);
+    }
+
+    #[test]
+    fn test_tick_time_percentiles_pick_ranked_samples() {
+        let mut metrics = MetricsCollector::new();
+        assert_eq!(metrics.tick_time_percentile(0.5), 0.0);
+        assert_eq!(metrics.frame_time_max(), 0.0);
+
+        // 1ms..=100ms, recorded out of order
+        for ms in (1..=100u64).rev() {
+            metrics.record_tick(Duration::from_millis(ms));
+        }
+        assert_eq!(metrics.tick_time_percentile(0.5), 50.0);
+        assert_eq!(metrics.tick_time_percentile(0.99), 99.0);
+        assert_eq!(metrics.tick_time_percentile(1.5), 100.0);
+        assert_eq!(metrics.tick_time_percentile(-1.0), 1.0);
+
+        metrics.record_frame();
+        metrics.set_show_jitter(true);
+        assert!(metrics.get_status_string(0, true).contains("p99 Frame:"));
+    }
+}
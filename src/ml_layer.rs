@@ -1,10 +1,10 @@
-/// ML Layer: CNN-based Rule Parameter Generator
-/// 
-/// This module handles inference from a trained CNN to generate dynamic rule parameters
-/// that adapt to the current grid state. Parameters can come from:
-/// - Trained neural network (ONNX, TensorFlow, PyTorch)
-/// - Stubs/heuristics during development
-/// - Hybrid approach (NN-guided + hand-crafted rules)
+//! ML Layer: CNN-based Rule Parameter Generator
+//! 
+//! This module handles inference from a trained CNN to generate dynamic rule parameters
+//! that adapt to the current grid state. Parameters can come from:
+//! - Trained neural network (ONNX, TensorFlow, PyTorch)
+//! - Stubs/heuristics during development
+//! - Hybrid approach (NN-guided + hand-crafted rules)
 
 use serde::{Serialize, Deserialize};
 use crate::cell::CellType;
@@ -140,10 +140,7 @@ impl RuleGenerator for StubRuleGenerator {
         Vec<LocalRuleParams>,
         GlobalRuleParams,
     ) {
-        let num_regions = (
-            (width + region_size - 1) / region_size * 
-            (height + region_size - 1) / region_size
-        ) as usize;
+        let num_regions = (width.div_ceil(region_size) * height.div_ceil(region_size)) as usize;
         
         let num_cells = (width * height) as usize;
         
@@ -168,8 +165,9 @@ impl RuleGenerator for StubRuleGenerator {
                     params.ecosystem_health = 0.5;
                 }
                 
-                // If orange is dying, increase diversity pressure
-                if orange_density < 0.1 {
+                // If orange is dying, increase diversity pressure; with
+                // none at all there's nothing left to die out
+                if orange_density > 0.0 && orange_density < 0.1 {
                     params.diversity_pressure = 0.8;
                     params.mutation_rate = 0.7;
                 }
@@ -241,7 +239,7 @@ pub fn get_region_params(
 ) -> RegionRuleParams {
     let region_x = (x / region_size) as usize;
     let region_y = (y / region_size) as usize;
-    let regions_per_row = ((width + region_size - 1) / region_size) as usize;
+    let regions_per_row = width.div_ceil(region_size) as usize;
     let idx = region_y * regions_per_row + region_x;
     
     region_params
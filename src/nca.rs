@@ -1,14 +1,17 @@
-/// Neural Cellular Automaton (NCA) Layer
-/// 
-/// Predicts the next cell state based on:
-/// - Current cell type and genes
-/// - Local neighborhood state
-/// - Rule parameters from CNN
-/// - Stochastic outputs for diversity
+//! Neural Cellular Automaton (NCA) Layer
+//! 
+//! Predicts the next cell state based on:
+//! - Current cell type and genes
+//! - Local neighborhood state
+//! - Rule parameters from CNN
+//! - Stochastic outputs for diversity
 
 use crate::cell::{Cell, CellType};
-use crate::ml_layer::{RegionRuleParams, LocalRuleParams, GlobalRuleParams};
+use crate::grid::{Grid, CHUNK_SIZE};
+use crate::ml_layer::{get_region_params, RegionRuleParams, LocalRuleParams, GlobalRuleParams, RuleGenerator};
+use crate::rules::for_each_chunk_layered;
 use rand::Rng;
+use std::cmp::Ordering;
 
 /// Embedding for a cell in the NCA input
 #[derive(Debug, Clone)]
@@ -22,8 +25,8 @@ pub struct CellEmbedding {
     /// Cell's genetic traits (4 dims: spread, aggression, vitality, mutatability)
     pub genetic_traits: [f32; 4],
     
-    /// Local cell type density (10 dims: counts of major types)
-    pub local_density: [f32; 10],
+    /// Local cell type density (37 dims: fraction of neighbors of each type)
+    pub local_density: [f32; CellType::COUNT],
 }
 
 /// Output prediction from NCA for a single cell
@@ -231,7 +234,8 @@ fn get_mutation_alternatives(
             .map(|(i, &l)| (i, l))
             .collect();
         
-        indexed.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+        // Stable sort, so equal logits keep ascending index order
+        indexed.sort_by(|a, b| cmp_logits(b.1, a.1));
         
         for (idx, logit) in indexed.iter().take(3) {
             if *idx != current_type.to_u8() as usize {
@@ -247,6 +251,27 @@ fn get_mutation_alternatives(
     alternatives
 }
 
+/// Total order on logits: NaN ranks below every number
+fn cmp_logits(a: f32, b: f32) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        (false, false) => a.partial_cmp(&b).unwrap(),
+    }
+}
+
+/// Index of the largest logit; ties resolve to the lowest index
+fn argmax(logits: &[f32]) -> usize {
+    let mut best = 0;
+    for (idx, &logit) in logits.iter().enumerate().skip(1) {
+        if cmp_logits(logit, logits[best]) == Ordering::Greater {
+            best = idx;
+        }
+    }
+    best
+}
+
 /// Get confidence in the main prediction (stochasticity)
 fn get_confidence(
     logits: &[f32],
@@ -264,8 +289,7 @@ fn get_confidence(
     };
     
     // Chaos reduces confidence (makes predictions more stochastic)
-    let adjusted = (confidence - chaos_level * 0.3).clamp(0.0, 1.0);
-    adjusted
+    (confidence - chaos_level * 0.3).clamp(0.0, 1.0)
 }
 
 /// Apply NCA prediction to a cell
@@ -283,11 +307,7 @@ pub fn apply_nca_prediction(
     
     let next_type = if use_main {
         // Argmax the logits
-        let next_idx = prediction.next_cell_logits.iter()
-            .enumerate()
-            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
-            .map(|(idx, _)| idx)
-            .unwrap_or(0);
+        let next_idx = argmax(&prediction.next_cell_logits);
         
         CellType::from_u8(next_idx as u8).unwrap_or(CellType::Black)
     } else {
@@ -320,7 +340,7 @@ pub fn apply_nca_prediction(
     new_genes.mutatability = (new_genes.mutatability + prediction.trait_deltas[3] as f64)
         .clamp(0.0, 1.0);
     
-    Cell::with_genes(next_type, new_genes)
+    Cell::with_genes_and_rng(next_type, new_genes, rng)
 }
 
 /// Create embedding for a cell (used as NCA input)
@@ -348,22 +368,9 @@ pub fn create_embedding(
         cell.genes.mutatability as f32,
     ];
     
-    // Local density (simplified: just count major types)
-    let mut local_density = [0.0f32; 10];
-    let major_types = [
-        CellType::Green,
-        CellType::Orange,
-        CellType::Purple,
-        CellType::Red,
-        CellType::White,
-        CellType::Blue,
-        CellType::Gray,
-        CellType::Black,
-        CellType::Brown,
-        CellType::Cyan,
-    ];
-    
-    for (i, &cell_type) in major_types.iter().enumerate() {
+    // Local density of every type among the neighbors
+    let mut local_density = [0.0f32; CellType::COUNT];
+    for (i, cell_type) in CellType::all().enumerate() {
         let count = neighborhood.iter()
             .filter(|n| n.cell_type == cell_type)
             .count() as f32;
@@ -378,6 +385,44 @@ pub fn create_embedding(
     }
 }
 
+/// `create_embedding` for the cell at (x, y), using its `Grid::neighborhood`
+///
+/// None if (x, y) is outside the grid.
+pub fn embed_from_grid(grid: &Grid, x: u32, y: u32, rng: &mut impl Rng) -> Option<CellEmbedding> {
+    let cell = grid.get_cell(x, y)?;
+    Some(create_embedding(&cell, &grid.neighborhood(x, y), rng))
+}
+
+/// Tick the grid with a learned automaton instead of the hand-written rules
+///
+/// `generator` sees the grid at the start of the tick with a region size of
+/// `CHUNK_SIZE`, like `rules::apply_rules_ml`. Each non-empty cell is
+/// embedded from its isolated neighborhood, predicted by `nca`, and the
+/// result written to next_cells; empty cells stay as they are. Chunks are
+/// visited in the same isolated layers as `rules::apply_rules`.
+pub fn step(grid: &mut Grid, nca: &dyn CellularAutomaton, generator: &dyn RuleGenerator, rng: &mut impl Rng) {
+    let width = grid.width;
+    let (region_params, local_params, global_params) =
+        generator.generate_rules(&grid.to_bytes(), width, grid.height, CHUNK_SIZE);
+
+    for_each_chunk_layered(grid, |grid, chunk_x, chunk_y| {
+        let (start_x, start_y) = (chunk_x * CHUNK_SIZE, chunk_y * CHUNK_SIZE);
+        for y in start_y..(start_y + CHUNK_SIZE).min(grid.height) {
+            for x in start_x..(start_x + CHUNK_SIZE).min(width) {
+                let Some(cell) = grid.get_cell_from_boundary(x, y) else { continue };
+                if cell.cell_type == grid.empty_type() {
+                    continue;
+                }
+                let embedding = create_embedding(&cell, &grid.neighborhood_isolated(x, y), rng);
+                let region = get_region_params(&region_params, x, y, width, CHUNK_SIZE);
+                let local = local_params.get((y * width + x) as usize).copied().unwrap_or_default();
+                let prediction = nca.predict(&embedding, &region, &local, &global_params);
+                grid.set_next_cell(x, y, apply_nca_prediction(&cell, &prediction, rng, &region));
+            }
+        }
+    });
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -390,7 +435,7 @@ mod tests {
             cell_type_encoding: vec![0.0; 37],
             neighborhood_encoding: vec![0.0; 296],
             genetic_traits: [0.5; 4],
-            local_density: [0.1; 10],
+            local_density: [0.1; CellType::COUNT],
         };
         embedding.cell_type_encoding[1] = 1.0; // Green
         
@@ -403,4 +448,74 @@ mod tests {
         assert_eq!(prediction.next_cell_logits.len(), 37);
         assert_eq!(prediction.trait_deltas.len(), 4);
     }
+
+    #[test]
+    fn test_argmax_ties_and_nan() {
+        let mut logits = vec![0.0f32; 37];
+        logits[0] = f32::NAN;
+        logits[5] = 2.0;
+        logits[9] = 2.0;
+        logits[30] = 2.0;
+        assert_eq!(argmax(&logits), 5);
+        assert_eq!(argmax(&[f32::NAN, f32::NAN]), 0);
+
+        // Main prediction path picks the same cell every time
+        let prediction = NCAPrediction {
+            next_cell_logits: logits.clone(),
+            trait_deltas: [0.0; 4],
+            mutation_alternatives: Vec::new(),
+            stochastic_confidence: 1.0,
+        };
+        let cell = Cell::new(CellType::Green);
+        let mut rng = rand::thread_rng();
+        for _ in 0..20 {
+            let next = apply_nca_prediction(&cell, &prediction, &mut rng, &RegionRuleParams::default());
+            assert_eq!(next.cell_type, CellType::Red);
+        }
+
+        // Alternatives list tied logits in index order, NaN never wins
+        let region_params = RegionRuleParams { mutation_rate: 0.9, ..RegionRuleParams::default() };
+        let alternatives = get_mutation_alternatives(CellType::Green, &logits, &region_params);
+        let indices: Vec<u8> = alternatives.iter().map(|(idx, _)| *idx).collect();
+        assert_eq!(indices, vec![5, 9, 30]);
+    }
+
+    #[test]
+    fn test_embed_from_grid_reads_live_neighbors() {
+        let mut grid = Grid::new(6, 6);
+        grid.fill_rect(1, 1, 4, 4, CellType::Green);
+        grid.set_cell(2, 2, CellType::Orange);
+        let mut rng = rand::thread_rng();
+
+        let embedding = embed_from_grid(&grid, 2, 2, &mut rng).unwrap();
+        let green = CellType::Green.to_u8() as usize;
+        assert_eq!(embedding.local_density[green], 1.0);
+        assert_eq!(embedding.local_density[CellType::Black.to_u8() as usize], 0.0);
+        assert_eq!(embedding.cell_type_encoding[CellType::Orange.to_u8() as usize], 1.0);
+        // Every neighbor slot is one-hot Green
+        for slot in 0..8 {
+            assert_eq!(embedding.neighborhood_encoding[slot * 37 + green], 1.0);
+        }
+
+        assert!(embed_from_grid(&grid, 6, 0, &mut rng).is_none());
+    }
+
+    #[test]
+    fn test_step_keeps_green_mostly_green() {
+        use crate::ml_layer::StubRuleGenerator;
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let mut grid = Grid::new(40, 40);
+        grid.fill_rect(0, 0, 40, 40, CellType::Green);
+        let start = grid.population(CellType::Green);
+        let mut rng = StdRng::seed_from_u64(6);
+        for _ in 0..3 {
+            step(&mut grid, &StubNCA, &StubRuleGenerator, &mut rng);
+        }
+
+        let green = grid.population(CellType::Green);
+        assert!(green * 10 > start * 9, "{} of {} still green", green, start);
+        assert_eq!(grid.type_counts().iter().sum::<u32>(), 40 * 40);
+    }
 }
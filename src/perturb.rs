@@ -0,0 +1,120 @@
+//! External Perturbations
+//!
+//! Structured disturbances injected from outside the rules (experiments,
+//! tick hooks, the GUI). Every change goes through `Grid::spawn_with_rng`,
+//! so cached populations and dirty chunks stay in step and new cells draw
+//! their seeds from the caller's RNG.
+
+use rand::Rng;
+
+use crate::cell::CellType;
+use crate::grid::Grid;
+
+/// Half-open rectangle [x, x + width) x [y, y + height), clipped to the grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Perturbation {
+    /// Fill a disc of `radius` around (x, y) with `cell_type`
+    SpawnCluster { cell_type: CellType, x: u32, y: u32, radius: u32 },
+    /// Clear every cell in `rect` to the grid's empty type
+    Kill { rect: Rect },
+    /// Turn every orange and gray in a disc of `radius` into purple
+    /// (the types plague spreads onto)
+    Infect { x: u32, y: u32, radius: u32 },
+    /// Drop `count` crimson predators on random empty cells
+    AddPredators { count: u32 },
+}
+
+impl Perturbation {
+    /// Apply to `grid`, returning how many cells changed type
+    pub fn apply(&self, grid: &mut Grid, rng: &mut impl Rng) -> usize {
+        match *self {
+            Perturbation::SpawnCluster { cell_type, x, y, radius } => {
+                let mut changed = 0;
+                for (cx, cy) in grid.disc(x, y, radius) {
+                    if grid.spawn_with_rng(cx, cy, cell_type, rng) != Some(cell_type) {
+                        changed += 1;
+                    }
+                }
+                changed
+            }
+            Perturbation::Kill { rect } => {
+                let x_end = rect.x.saturating_add(rect.width).min(grid.width);
+                let y_end = rect.y.saturating_add(rect.height).min(grid.height);
+                let mut changed = 0;
+                for cy in rect.y..y_end {
+                    for cx in rect.x..x_end {
+                        let empty = grid.empty_type();
+                        if grid.spawn_with_rng(cx, cy, empty, rng).is_some_and(|old| old != empty) {
+                            changed += 1;
+                        }
+                    }
+                }
+                changed
+            }
+            Perturbation::Infect { x, y, radius } => {
+                let mut changed = 0;
+                for (cx, cy) in grid.disc(x, y, radius) {
+                    let current = grid.get_cell(cx, cy).map(|c| c.cell_type);
+                    if matches!(current, Some(CellType::Orange | CellType::Gray)) {
+                        grid.spawn_with_rng(cx, cy, CellType::Purple, rng);
+                        changed += 1;
+                    }
+                }
+                changed
+            }
+            Perturbation::AddPredators { count } => {
+                let empty = grid.empty_type();
+                let count = count.min(grid.population(empty));
+                let mut placed = 0;
+                while placed < count {
+                    let cx = rng.gen_range(0..grid.width);
+                    let cy = rng.gen_range(0..grid.height);
+                    if grid.get_cell(cx, cy).is_some_and(|c| c.cell_type == empty) {
+                        grid.spawn_with_rng(cx, cy, CellType::Crimson, rng);
+                        placed += 1;
+                    }
+                }
+                placed as usize
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Simulator;
+
+    #[test]
+    fn test_infect_converts_orange_and_gray_in_radius() {
+        let mut sim = Simulator::new(32, 32);
+        // Inside radius 3 of (10, 10): 3 orange, 1 gray, 1 green (immune)
+        for (x, y) in [(10, 10), (12, 10), (10, 7)] {
+            sim.grid.set_cell(x, y, CellType::Orange);
+        }
+        sim.grid.set_cell(8, 12, CellType::Gray);
+        sim.grid.set_cell(11, 11, CellType::Green);
+        // Outside the disc (corner of the bounding square)
+        sim.grid.set_cell(13, 13, CellType::Orange);
+        sim.grid.take_dirty_chunks();
+
+        let before = sim.grid.population(CellType::Purple);
+        let changed = sim.perturb(Perturbation::Infect { x: 10, y: 10, radius: 3 });
+
+        assert_eq!(changed, 4);
+        assert_eq!(sim.grid.population(CellType::Purple), before + 4);
+        assert_eq!(sim.grid.population(CellType::Orange), 1);
+        assert_eq!(sim.grid.take_dirty_chunks(), vec![(0, 0)]);
+
+        assert_eq!(sim.perturb(Perturbation::AddPredators { count: 5 }), 5);
+        assert_eq!(sim.grid.population(CellType::Crimson), 5);
+    }
+}
@@ -1,7 +1,72 @@
+use std::fmt;
+use std::io;
+use std::path::Path;
+
 use serde_json::{json, Map, Value};
 
+use crate::cell::CellType;
+
 pub type Preset = Map<String, Value>;
 
+#[derive(Debug)]
+pub enum PresetError {
+    Io(io::Error),
+    /// The file isn't valid JSON
+    Json(serde_json::Error),
+    /// The top-level JSON value isn't an object
+    NotAnObject,
+    /// `load_preset_strict` was given a name that isn't a built-in preset
+    UnknownPreset(String),
+    /// Every problem `validate` found
+    Invalid(Vec<PresetValidationError>),
+}
+
+/// One problem with a density map, as reported by `validate`
+#[derive(Debug, Clone, PartialEq)]
+pub enum PresetValidationError {
+    /// A key that isn't a cell-type name
+    UnknownCellType(String),
+    /// A weight below zero
+    NegativeWeight(String, f64),
+    /// A value that isn't a number
+    NotANumber(String),
+}
+
+impl fmt::Display for PresetValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PresetValidationError::UnknownCellType(name) => write!(f, "unknown cell type {:?}", name),
+            PresetValidationError::NegativeWeight(name, weight) => {
+                write!(f, "weight for {} is negative ({})", name, weight)
+            }
+            PresetValidationError::NotANumber(name) => write!(f, "weight for {} is not a number", name),
+        }
+    }
+}
+
+impl fmt::Display for PresetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PresetError::Io(e) => write!(f, "preset I/O error: {}", e),
+            PresetError::Json(e) => write!(f, "preset is not valid JSON: {}", e),
+            PresetError::NotAnObject => write!(f, "preset must be a JSON object of cell type densities"),
+            PresetError::UnknownPreset(name) => write!(f, "no built-in preset named {:?}", name),
+            PresetError::Invalid(errors) => {
+                let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+                write!(f, "invalid preset: {}", messages.join("; "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for PresetError {}
+
+impl From<io::Error> for PresetError {
+    fn from(e: io::Error) -> Self {
+        PresetError::Io(e)
+    }
+}
+
 pub fn load_preset(name: &str) -> Option<Preset> {
     match name {
         "balanced" => Some(balanced()),
@@ -15,6 +80,59 @@ pub fn load_preset(name: &str) -> Option<Preset> {
     }
 }
 
+/// Check a density map, reporting every problem rather than the first
+///
+/// Weights are percentages; a total above 100 is allowed (they are
+/// normalized when the grid is seeded) but logged as a warning.
+pub fn validate(map: &Preset) -> Result<(), Vec<PresetValidationError>> {
+    let mut errors = Vec::new();
+    let mut total = 0.0;
+    for (name, value) in map {
+        if CellType::from_name(name).is_none() {
+            errors.push(PresetValidationError::UnknownCellType(name.clone()));
+        }
+        match value.as_f64() {
+            None => errors.push(PresetValidationError::NotANumber(name.clone())),
+            Some(weight) if weight < 0.0 => errors.push(PresetValidationError::NegativeWeight(name.clone(), weight)),
+            Some(weight) => total += weight,
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    if total > 100.0 {
+        log::warn!("Preset weights sum to {} (over 100%); they will be normalized", total);
+    }
+    Ok(())
+}
+
+/// Built-in preset by name, checked with `validate`
+///
+/// Unlike `load_preset`, an unknown name is an error rather than None.
+pub fn load_preset_strict(name: &str) -> Result<Preset, PresetError> {
+    let preset = load_preset(name).ok_or_else(|| PresetError::UnknownPreset(name.to_string()))?;
+    validate(&preset).map_err(PresetError::Invalid)?;
+    Ok(preset)
+}
+
+/// Load a custom preset: a JSON object of `{ "Green": 20, ... }` density weights
+///
+/// The map is checked with `validate`, so every unknown name and bad weight
+/// is reported together and typos can be fixed in one go.
+pub fn load_preset_file(path: &Path) -> Result<Preset, PresetError> {
+    parse_preset(&std::fs::read_to_string(path)?)
+}
+
+fn parse_preset(text: &str) -> Result<Preset, PresetError> {
+    let Value::Object(preset) = serde_json::from_str(text).map_err(PresetError::Json)? else {
+        return Err(PresetError::NotAnObject);
+    };
+
+    validate(&preset).map_err(PresetError::Invalid)?;
+    Ok(preset)
+}
+
 /// Balanced ecosystem with healthy populations of all types
 fn balanced() -> Preset {
     let json = json!({
@@ -148,3 +266,84 @@ fn sparse_genesis() -> Preset {
     });
     json.as_object().unwrap().clone()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_temp(name: &str, contents: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("cellular_sim_{}_{}.json", name, std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn parse_json(text: &str) -> Preset {
+        serde_json::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn test_load_preset_file_accepts_valid_densities() {
+        let path = write_temp("valid", r#"{ "Black": 70, "Green": 20.5, "Crimson": 1 }"#);
+        let preset = load_preset_file(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(preset.len(), 3);
+        assert_eq!(preset["Green"].as_f64(), Some(20.5));
+    }
+
+    #[test]
+    fn test_load_preset_file_lists_unknown_colors() {
+        let path = write_temp("typo", r#"{ "Green": 20, "Gren": 5, "Teel": 1 }"#);
+        let err = load_preset_file(&path).unwrap_err();
+        std::fs::remove_file(&path).ok();
+
+        match err {
+            PresetError::Invalid(errors) => assert_eq!(
+                errors,
+                vec![
+                    PresetValidationError::UnknownCellType("Gren".to_string()),
+                    PresetValidationError::UnknownCellType("Teel".to_string()),
+                ]
+            ),
+            other => panic!("expected unknown cell types, got {}", other),
+        }
+    }
+
+    #[test]
+    fn test_load_preset_file_rejects_malformed_json() {
+        let path = write_temp("malformed", r#"{ "Green": 20, "#);
+        let err = load_preset_file(&path).unwrap_err();
+        std::fs::remove_file(&path).ok();
+
+        assert!(matches!(err, PresetError::Json(_)));
+        assert!(matches!(parse_preset("[1, 2]"), Err(PresetError::NotAnObject)));
+        assert!(matches!(parse_preset(r#"{ "Green": -3 }"#), Err(PresetError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_validate_reports_unknown_key() {
+        let preset = parse_json(r#"{ "Green": 20, "Grean": 5 }"#);
+        assert_eq!(validate(&preset), Err(vec![PresetValidationError::UnknownCellType("Grean".to_string())]));
+    }
+
+    #[test]
+    fn test_validate_reports_negative_and_non_numeric_weights() {
+        let preset = parse_json(r#"{ "Green": -4, "Orange": "ten", "Blue": 5 }"#);
+        assert_eq!(
+            validate(&preset),
+            Err(vec![
+                PresetValidationError::NegativeWeight("Green".to_string(), -4.0),
+                PresetValidationError::NotANumber("Orange".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_validate_accepts_builtin_presets() {
+        assert_eq!(validate(&parse_json(r#"{ "Black": 70, "Green": 20.5 }"#)), Ok(()));
+        for name in ["balanced", "sparse_genesis", "recovery"] {
+            assert!(load_preset_strict(name).is_ok(), "{}", name);
+        }
+        assert!(matches!(load_preset_strict("balancd"), Err(PresetError::UnknownPreset(_))));
+    }
+}
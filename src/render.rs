@@ -0,0 +1,225 @@
+//! Image Export
+//!
+//! Grid-to-image rendering (PNG snapshots, animated GIFs) that doesn't depend
+//! on the GUI, for dumping frames from headless runs. Cells are drawn in
+//! their `SpeciesRegistry::builtin()` colors unless a `Palette` is supplied.
+
+use std::io::{self, Write};
+use std::time::Duration;
+
+use image::codecs::png::PngEncoder;
+use image::{ColorType, ImageEncoder};
+
+use crate::cell::CellType;
+use crate::grid::Grid;
+use crate::species::SpeciesRegistry;
+
+/// RGB color for every cell type, e.g. a colorblind-friendly theme
+///
+/// The default is each species' color from `SpeciesRegistry::builtin()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette {
+    colors: [(u8, u8, u8); CellType::COUNT],
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        let mut colors = [(0, 0, 0); CellType::COUNT];
+        for species in SpeciesRegistry::builtin().iter() {
+            colors[species.id as usize] = species.color;
+        }
+        Palette { colors }
+    }
+}
+
+impl Palette {
+    pub fn get(&self, cell_type: CellType) -> (u8, u8, u8) {
+        self.colors[cell_type.to_u8() as usize]
+    }
+
+    pub fn set(&mut self, cell_type: CellType, color: (u8, u8, u8)) {
+        self.colors[cell_type.to_u8() as usize] = color;
+    }
+
+    /// Builder form of `set`
+    pub fn with(mut self, cell_type: CellType, color: (u8, u8, u8)) -> Self {
+        self.set(cell_type, color);
+        self
+    }
+}
+
+/// RGB bytes with every cell drawn as a `scale` x `scale` block
+///
+/// The image is (width * scale) x (height * scale); a `scale` of 0 is
+/// treated as 1. Colors come from `palette`, or the default `Palette`
+/// when it's None.
+pub fn render_rgb(grid: &Grid, scale: u32, palette: Option<&Palette>) -> Vec<u8> {
+    let default = Palette::default();
+    render_scaled(grid, scale, |cell_type| {
+        let (r, g, b) = palette.unwrap_or(&default).get(cell_type);
+        [r, g, b]
+    })
+}
+
+/// Draw each cell as a `scale` x `scale` block of `pixel(cell_type)`
+fn render_scaled<const N: usize>(grid: &Grid, scale: u32, pixel: impl Fn(CellType) -> [u8; N]) -> Vec<u8> {
+    let scale = scale.max(1) as usize;
+    let row_len = grid.width as usize * scale * N;
+    let mut pixels = Vec::with_capacity(row_len * grid.height as usize * scale);
+
+    for y in 0..grid.height {
+        let row_start = pixels.len();
+        for x in 0..grid.width {
+            let value = pixel(grid.get_cell(x, y).map_or(CellType::Black, |c| c.cell_type));
+            for _ in 0..scale {
+                pixels.extend_from_slice(&value);
+            }
+        }
+        // The remaining rows of this cell band are copies of the first
+        for _ in 1..scale {
+            pixels.extend_from_within(row_start..row_start + row_len);
+        }
+    }
+    pixels
+}
+
+/// PNG of the grid at `scale` pixels per cell (see `render_rgb`)
+pub fn render_png(grid: &Grid, scale: u32, palette: Option<&Palette>) -> Vec<u8> {
+    let scale = scale.max(1);
+    encode_png(&render_rgb(grid, scale, palette), grid.width * scale, grid.height * scale)
+}
+
+/// Encode `width` x `height` RGB bytes as a PNG
+///
+/// Returns an empty buffer if the encoder rejects the input (e.g. a size
+/// that doesn't match `pixels`).
+pub fn encode_png(pixels: &[u8], width: u32, height: u32) -> Vec<u8> {
+    let mut png_data = Vec::with_capacity(pixels.len() / 4);
+    let encoder = PngEncoder::new(&mut png_data);
+    if encoder.write_image(pixels, width, height, ColorType::Rgb8).is_err() {
+        return Vec::new();
+    }
+    png_data
+}
+
+/// Streams grid frames into an animated GIF
+///
+/// The palette is the 37 cell colors indexed by type id, so frames are
+/// lossless. GIF dimensions are 16-bit: (width * scale) and
+/// (height * scale) must each fit in a u16.
+pub struct GifWriter<W: Write> {
+    encoder: gif::Encoder<W>,
+    scale: u32,
+    width: u16,
+    height: u16,
+    delay: u16,
+}
+
+impl<W: Write> GifWriter<W> {
+    /// Start a looping GIF for a `grid_width` x `grid_height` grid
+    ///
+    /// `frame_delay` is rounded down to the format's 10ms resolution.
+    pub fn new(out: W, grid_width: u32, grid_height: u32, scale: u32, frame_delay: Duration) -> io::Result<Self> {
+        let scale = scale.max(1);
+        let dimension = |cells: u32| {
+            cells
+                .checked_mul(scale)
+                .and_then(|px| u16::try_from(px).ok())
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "GIF frames are limited to 65535 pixels a side"))
+        };
+        let (width, height) = (dimension(grid_width)?, dimension(grid_height)?);
+
+        let palette: Vec<u8> = Palette::default().colors.iter().flat_map(|&(r, g, b)| [r, g, b]).collect();
+        let mut encoder = gif::Encoder::new(out, width, height, &palette).map_err(gif_error)?;
+        encoder.set_repeat(gif::Repeat::Infinite).map_err(gif_error)?;
+
+        let delay = u16::try_from(frame_delay.as_millis() / 10).unwrap_or(u16::MAX);
+        Ok(GifWriter { encoder, scale, width, height, delay })
+    }
+
+    /// Append the grid as the next frame; it must have the size given to `new`
+    pub fn write_frame(&mut self, grid: &Grid) -> io::Result<()> {
+        let frame = gif::Frame {
+            width: self.width,
+            height: self.height,
+            delay: self.delay,
+            buffer: render_scaled(grid, self.scale, |cell_type| [cell_type.to_u8()]).into(),
+            ..gif::Frame::default()
+        };
+        self.encoder.write_frame(&frame).map_err(gif_error)
+    }
+}
+
+fn gif_error(err: gif::EncodingError) -> io::Error {
+    match err {
+        gif::EncodingError::Io(err) => err,
+        gif::EncodingError::Format(err) => io::Error::new(io::ErrorKind::InvalidInput, err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::CellType;
+
+    #[test]
+    fn test_render_png_scales_each_cell() {
+        let mut grid = Grid::new(4, 4);
+        grid.set_cell(1, 2, CellType::Green);
+
+        let png = render_png(&grid, 2, None);
+        let decoded = image::load_from_memory(&png).unwrap().to_rgb8();
+        assert_eq!(decoded.dimensions(), (8, 8));
+        for (x, y) in [(2, 4), (3, 5)] {
+            assert_eq!(decoded.get_pixel(x, y).0, [0, 204, 0]);
+        }
+        assert_eq!(decoded.get_pixel(4, 4).0, [0, 0, 0]);
+
+        let unscaled = image::load_from_memory(&render_png(&grid, 0, None)).unwrap();
+        assert_eq!((unscaled.width(), unscaled.height()), (4, 4));
+    }
+
+    #[test]
+    fn test_custom_palette_recolors_green() {
+        let mut grid = Grid::new(2, 1);
+        grid.set_cell(0, 0, CellType::Green);
+
+        assert_eq!(&render_rgb(&grid, 1, None)[..3], &[0, 204, 0]);
+        assert_eq!(&render_rgb(&grid, 1, Some(&Palette::default()))[..3], &[0, 204, 0]);
+        let themed = Palette::default().with(CellType::Green, (0, 114, 178));
+        let png = render_png(&grid, 1, Some(&themed));
+        let decoded = image::load_from_memory(&png).unwrap().to_rgb8();
+        assert_eq!(decoded.get_pixel(0, 0).0, [0, 114, 178]);
+        assert_eq!(decoded.get_pixel(1, 0).0, [0, 0, 0]);
+
+        let default = Palette::default();
+        assert_ne!(default.get(CellType::Aqua), default.get(CellType::Cyan));
+    }
+
+    #[test]
+    fn test_record_gif_writes_frame_per_stride() {
+        let mut sim = crate::Simulator::new_seeded(20, 16, 8);
+        assert!(sim.load_preset("balanced"));
+        let path = std::env::temp_dir().join(format!("cellular_sim_run_{}.gif", std::process::id()));
+        let frames = sim.record_gif(&path, 10, 3, 2, Duration::from_millis(80)).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(frames, 5);
+        assert_eq!(sim.tick_count, 10);
+
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::Indexed);
+        let mut decoder = options.read_info(bytes.as_slice()).unwrap();
+        assert_eq!((decoder.width(), decoder.height()), (60, 48));
+        let mut decoded = 0;
+        let mut last = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            assert_eq!(frame.delay, 8);
+            decoded += 1;
+            last = frame.buffer.to_vec();
+        }
+        assert_eq!(decoded, 5);
+        // Palette indices are type ids, so the last frame is the final grid exactly
+        assert_eq!(last, render_scaled(&sim.grid, 3, |cell_type| [cell_type.to_u8()]));
+    }
+}
@@ -0,0 +1,252 @@
+//! Session Recording & Replay
+//!
+//! A `Recorder` logs user actions (preset loads, paints, speed changes,
+//! play/pause) with the tick they happened on; a `Player` re-applies them
+//! to a fresh `Simulator`, ticking in between, to reconstruct the session.
+//!
+//! Recordings are JSON lines: a header line with the grid size and the
+//! tick RNG seed, then one `RecordedAction` per line. Preset loads carry the
+//! seed used for cell placement, so with both seeds a session replays
+//! exactly. Recordings made before the header carried a seed still replay
+//! their actions, but ticks in between diverge.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+
+use crate::cell::CellType;
+use crate::Simulator;
+
+/// A single user action
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Action {
+    /// Load a preset with seeded placement and restart the tick count
+    LoadPreset { name: String, seed: u64 },
+    /// Clear the grid and restart the tick count
+    Reset,
+    /// Paint a single cell
+    SetCell { x: u32, y: u32, cell_type: CellType },
+    /// Playback speed change in ticks per second (informational; doesn't affect grid state)
+    SpeedChanged(f32),
+    /// Playback started (informational)
+    Play,
+    /// Playback paused (informational)
+    Pause,
+}
+
+impl Action {
+    /// Apply this action to `sim`
+    pub fn apply(&self, sim: &mut Simulator) {
+        match self {
+            Action::LoadPreset { name, seed } => {
+                if sim.load_preset_with_rng(name, &mut StdRng::seed_from_u64(*seed)) {
+                    sim.tick_count = 0;
+                }
+            }
+            Action::Reset => sim.reset(),
+            Action::SetCell { x, y, cell_type } => sim.set_cell(*x, *y, cell_type.to_u8()),
+            Action::SpeedChanged(_) | Action::Play | Action::Pause => {}
+        }
+    }
+}
+
+/// An action and the tick count it was applied at
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedAction {
+    pub tick: u64,
+    pub action: Action,
+}
+
+/// First line of a recording
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordingHeader {
+    pub width: u32,
+    pub height: u32,
+    /// Seed of the RNG the recorded ticks drew from
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(io::Error),
+    /// The recording has no header line
+    MissingHeader,
+    /// Line `line` (1-based) isn't valid JSON for its slot
+    Parse { line: usize, source: serde_json::Error },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Io(e) => write!(f, "replay I/O error: {}", e),
+            ReplayError::MissingHeader => write!(f, "recording is empty (no header line)"),
+            ReplayError::Parse { line, source } => write!(f, "recording line {}: {}", line, source),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl From<io::Error> for ReplayError {
+    fn from(e: io::Error) -> Self {
+        ReplayError::Io(e)
+    }
+}
+
+/// Applies actions to a simulator and appends them to a recording
+///
+/// Every action is flushed as it's written, so a crash still leaves a
+/// usable recording behind.
+pub struct Recorder<W: Write> {
+    out: W,
+}
+
+impl Recorder<File> {
+    /// Start a recording file at `path`, truncating any existing one
+    pub fn create(path: impl AsRef<Path>, width: u32, height: u32, seed: u64) -> io::Result<Self> {
+        Recorder::new(File::create(path)?, width, height, seed)
+    }
+}
+
+impl<W: Write> Recorder<W> {
+    /// Start a recording on `out`, writing the header immediately
+    ///
+    /// `seed` is the tick RNG seed of the session, e.g. `Simulator::seed`.
+    pub fn new(out: W, width: u32, height: u32, seed: u64) -> io::Result<Self> {
+        let mut recorder = Recorder { out };
+        recorder.write_line(&RecordingHeader { width, height, seed: Some(seed) })?;
+        Ok(recorder)
+    }
+
+    /// Log `action` as happening at `tick` without applying it
+    pub fn record(&mut self, tick: u64, action: Action) -> io::Result<()> {
+        self.write_line(&RecordedAction { tick, action })
+    }
+
+    /// Apply `action` to `sim` and log it at the current tick
+    pub fn perform(&mut self, sim: &mut Simulator, action: Action) -> io::Result<()> {
+        action.apply(sim);
+        // Stamped after applying, so preset loads and resets log tick 0
+        self.record(sim.tick_count, action)
+    }
+
+    /// Consume the recorder, returning the underlying writer
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+
+    fn write_line<T: Serialize>(&mut self, value: &T) -> io::Result<()> {
+        serde_json::to_writer(&mut self.out, value)?;
+        self.out.write_all(b"\n")?;
+        self.out.flush()
+    }
+}
+
+/// Reconstructs a session from a recording
+pub struct Player {
+    header: RecordingHeader,
+    actions: Vec<RecordedAction>,
+}
+
+impl Player {
+    pub fn open(path: impl AsRef<Path>) -> Result<Player, ReplayError> {
+        Player::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    pub fn from_reader(reader: impl BufRead) -> Result<Player, ReplayError> {
+        let mut lines = reader.lines().enumerate().filter(|(_, line)| {
+            line.as_ref().map(|l| !l.trim().is_empty()).unwrap_or(true)
+        });
+
+        let (idx, first) = lines.next().ok_or(ReplayError::MissingHeader)?;
+        let header = serde_json::from_str(&first?)
+            .map_err(|source| ReplayError::Parse { line: idx + 1, source })?;
+
+        let mut actions = Vec::new();
+        for (idx, line) in lines {
+            let action = serde_json::from_str(&line?)
+                .map_err(|source| ReplayError::Parse { line: idx + 1, source })?;
+            actions.push(action);
+        }
+        Ok(Player { header, actions })
+    }
+
+    pub fn header(&self) -> RecordingHeader {
+        self.header
+    }
+
+    pub fn actions(&self) -> &[RecordedAction] {
+        &self.actions
+    }
+
+    /// Replay every action, stopping right after the last one
+    pub fn replay(&self) -> Simulator {
+        let end = self.actions.last().map_or(0, |a| a.tick);
+        self.replay_to(end)
+    }
+
+    /// Replay every action, then keep ticking until the tick count is `end_tick`
+    ///
+    /// Before each action the simulator is ticked up to the action's
+    /// timestamp. Preset loads and resets restart the tick count, so
+    /// timestamps may go backwards across them.
+    pub fn replay_to(&self, end_tick: u64) -> Simulator {
+        let (width, height) = (self.header.width, self.header.height);
+        let mut sim = match self.header.seed {
+            Some(seed) => Simulator::new_seeded(width, height, seed),
+            None => Simulator::new(width, height),
+        };
+        for recorded in &self.actions {
+            while sim.tick_count < recorded.tick {
+                sim.tick();
+            }
+            recorded.action.apply(&mut sim);
+        }
+        while sim.tick_count < end_tick {
+            sim.tick();
+        }
+        sim
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replayed_session_reaches_same_grid() {
+        let mut live = Simulator::new(48, 48);
+        let mut recorder = Recorder::new(Vec::new(), 48, 48, live.seed()).unwrap();
+
+        recorder
+            .perform(&mut live, Action::LoadPreset { name: "balanced".to_string(), seed: 3 })
+            .unwrap();
+        for _ in 0..5 {
+            live.tick();
+        }
+        recorder
+            .perform(&mut live, Action::SetCell { x: 10, y: 10, cell_type: CellType::Indigo })
+            .unwrap();
+        live.tick();
+        recorder.perform(&mut live, Action::Pause).unwrap();
+
+        let bytes = recorder.into_inner();
+        let player = Player::from_reader(bytes.as_slice()).unwrap();
+        assert_eq!(
+            player.header(),
+            RecordingHeader { width: 48, height: 48, seed: Some(live.seed()) }
+        );
+        assert_eq!(player.actions().len(), 3);
+        assert_eq!(player.actions()[1].tick, 5);
+
+        let replayed = player.replay();
+        assert_eq!(replayed.tick_count, live.tick_count);
+        assert_eq!(replayed.get_grid_data(), live.get_grid_data());
+    }
+}
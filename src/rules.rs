@@ -1,13 +1,76 @@
 use crate::cell::{Cell, CellType};
-use crate::grid::{Grid, CHUNK_SIZE};
+use crate::config::{GrazerConfig, Neighborhood, RuleConfig};
+use crate::grid::{Grid, BOUNDARY_RADIUS, CHUNK_SIZE};
 use crate::genetics::check_reproduction;
-use rand::Rng;
+use crate::ml_layer::{get_region_params, RegionRuleParams, RuleGenerator};
+use crate::species::{SpeciesRegistry, SpeciesRule};
+use rand::{Rng, RngCore};
+
+/// RNG handed to the per-type rules
+///
+/// In discrete mode (`delta_time` is None) every probability is a per-tick
+/// chance and cells age one tick at a time. In delta-time mode each chance
+/// `p` is treated as a rate and becomes `1 - (1 - p)^dt`, and ages advance by
+/// `dt` (fractional parts rounded stochastically), so one tick of `dt = 2`
+/// matches two ticks of `dt = 1`.
+pub(crate) struct TickRng<R> {
+    inner: R,
+    delta_time: Option<f64>,
+}
+
+impl<R: RngCore> TickRng<R> {
+    pub(crate) fn new(inner: R, delta_time: Option<f64>) -> Self {
+        TickRng { inner, delta_time }
+    }
+
+    /// Roll a per-tick chance, scaled by dt in delta-time mode
+    #[inline]
+    fn chance(&mut self, p: f64) -> bool {
+        let p = match self.delta_time {
+            None => p,
+            Some(dt) => 1.0 - (1.0 - p.clamp(0.0, 1.0)).powf(dt.max(0.0)),
+        };
+        self.gen::<f64>() < p
+    }
+
+    /// Ticks of age to add this tick
+    #[inline]
+    fn age_step(&mut self) -> u8 {
+        match self.delta_time {
+            None => 1,
+            Some(dt) => {
+                let dt = dt.clamp(0.0, u8::MAX as f64);
+                let whole = dt.floor();
+                let extra = self.gen::<f64>() < dt - whole;
+                (whole as u8).saturating_add(extra as u8)
+            }
+        }
+    }
+}
+
+impl<R: RngCore> RngCore for TickRng<R> {
+    fn next_u32(&mut self) -> u32 {
+        self.inner.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.inner.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.inner.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.inner.try_fill_bytes(dest)
+    }
+}
 
 /// Apply rules with triple-buffering and chunk-based batching
 /// 
 /// This approach:
 /// 1. Copies current state to next buffer (global state)
-/// 2. Processes grid in non-overlapping 32x32 chunks sequentially
+/// 2. Processes grid in non-overlapping 32x32 chunks sequentially, skipping all-empty ones
 /// 3. For each chunk, copies boundary region to stable buffer BEFORE processing
 /// 4. All reads use boundary_buffer (isolated state) for consistency
 /// 5. All writes go to next_cells (protected by chunk isolation)
@@ -18,10 +81,123 @@ use rand::Rng;
 /// - 32x32 chunks fit well in L1 cache (reducing cache misses)
 /// - Boundary buffer provides consistent state for all neighbor lookups
 /// - Can easily parallelize later (chunks at (x%2, y%2) don't overlap)
-pub fn apply_rules(grid: &mut Grid) {
+pub fn apply_rules(grid: &mut Grid, config: &RuleConfig) {
+    apply_rules_with_rng(grid, config, &mut rand::thread_rng());
+}
+
+/// Same as `apply_rules`, but every random draw this tick comes from `rng`
+///
+/// With a seeded `rng` the tick is fully reproducible: chunks and cells are
+/// visited in a fixed order and all draws share the one stream.
+pub fn apply_rules_with_rng(grid: &mut Grid, config: &RuleConfig, rng: &mut impl RngCore) {
+    run_chunk_layers(grid, config.time.delta_time, rng, |_, _| config);
+}
+
+/// Same as `apply_rules`, with rates modulated per region by an ML generator
+///
+/// `generator` sees the grid at the start of the tick with a region size of
+/// `CHUNK_SIZE`, so every cell in a chunk shares its region's parameters.
+/// Each region gets a copy of `config` adjusted by `modulate_config`.
+pub fn apply_rules_ml(grid: &mut Grid, config: &RuleConfig, generator: &dyn RuleGenerator) {
+    apply_rules_ml_with_rng(grid, config, generator, &mut rand::thread_rng());
+}
+
+/// Same as `apply_rules_ml`, but every random draw this tick comes from `rng`
+pub fn apply_rules_ml_with_rng(
+    grid: &mut Grid,
+    config: &RuleConfig,
+    generator: &dyn RuleGenerator,
+    rng: &mut impl RngCore,
+) {
+    let (region_params, _, _) = generator.generate_rules(&grid.to_bytes(), grid.width, grid.height, CHUNK_SIZE);
+    let (width, chunks_x) = (grid.width, grid.width.div_ceil(CHUNK_SIZE));
+    let chunk_configs: Vec<RuleConfig> = (0..grid.height.div_ceil(CHUNK_SIZE))
+        .flat_map(|cy| (0..chunks_x).map(move |cx| (cx, cy)))
+        .map(|(cx, cy)| {
+            let params = get_region_params(&region_params, cx * CHUNK_SIZE, cy * CHUNK_SIZE, width, CHUNK_SIZE);
+            modulate_config(config, &params)
+        })
+        .collect();
+
+    run_chunk_layers(grid, config.time.delta_time, rng, |cx, cy| {
+        &chunk_configs[(cy * chunks_x + cx) as usize]
+    });
+}
+
+/// `config` adjusted by one region's ML parameters
+///
+/// - Green spread scales with `spread_modifier`
+/// - Purple spread scales with `infection_rate`
+/// - Orange needs `1 / resource_abundance` times as much green to survive
+/// - Gray decay (which reseeds green) scales with `resource_abundance`
+pub fn modulate_config(config: &RuleConfig, params: &RegionRuleParams) -> RuleConfig {
+    let mut modulated = config.clone();
+    let abundance = params.resource_abundance.max(0.1) as f64;
+    modulated.green.spread_rate = (config.green.spread_rate * params.spread_modifier as f64).clamp(0.0, 1.0);
+    modulated.purple.spread_rate = (config.purple.spread_rate * params.infection_rate as f64).clamp(0.0, 1.0);
+    modulated.orange.survival_threshold = (config.orange.survival_threshold as f64 / abundance).round() as usize;
+    modulated.gray.decay_chance = (config.gray.decay_chance * abundance).clamp(0.0, 1.0);
+    modulated
+}
+
+/// True when no cell on the grid can change anything under `config`
+///
+/// Every type present must be empty, disabled, or one whose rule does
+/// nothing with these settings (Black, Pearl, Indigo without nutrient
+/// release, Slate without decomposition). Any non-zero reproduction chance
+/// or a smoke source among them keeps the grid live, since same-type
+/// neighbors can always breed.
+pub fn is_inert(grid: &Grid, config: &RuleConfig) -> bool {
+    let counts = grid.type_counts();
+    CellType::all()
+        .filter(|&cell_type| counts[cell_type.to_u8() as usize] > 0)
+        .all(|cell_type| {
+            if cell_type == grid.empty_type() || config.disabled.contains(cell_type) {
+                return true;
+            }
+            if config.reproduction.chance > 0.0 || config.smoke.source == Some(cell_type) {
+                return false;
+            }
+            match cell_type {
+                CellType::Black | CellType::Pearl => true,
+                CellType::Indigo => config.indigo.nutrient_release_rate <= 0.0,
+                CellType::Slate => config.slate.decompose_rate <= 0.0,
+                _ => false,
+            }
+        })
+}
+
+/// Run every active chunk through the rules in non-overlapping layers, then swap
+///
+/// `config_for(chunk_x, chunk_y)` picks the rules for each chunk. All-empty
+/// chunks (see `Grid::is_chunk_active`) are skipped. Returns how many cells
+/// were visited.
+fn run_chunk_layers<'c>(
+    grid: &mut Grid,
+    delta_time: Option<f64>,
+    rng: &mut impl RngCore,
+    config_for: impl Fn(u32, u32) -> &'c RuleConfig,
+) -> usize {
+    let mut rng = TickRng::new(rng, delta_time);
+    let mut visited = 0;
+    for_each_chunk_layered(grid, |grid, chunk_x, chunk_y| {
+        visited += process_chunk(grid, chunk_x, chunk_y, config_for(chunk_x, chunk_y), &mut rng);
+    });
+    visited
+}
+
+/// One tick's buffer discipline around `visit(grid, chunk_x, chunk_y)`
+///
+/// Carries `cells` into `next_cells`, then visits every active chunk in four
+/// non-overlapping layers, copying each layer's boundaries before any of
+/// its chunks run, and finally swaps buffers. `visit` should read through
+/// the isolated accessors and write only to `next_cells`.
+pub(crate) fn for_each_chunk_layered(grid: &mut Grid, mut visit: impl FnMut(&mut Grid, u32, u32)) {
+    grid.carry_cells_forward();
+
     // Calculate chunk grid dimensions
-    let chunks_x = (grid.width + CHUNK_SIZE - 1) / CHUNK_SIZE;
-    let chunks_y = (grid.height + CHUNK_SIZE - 1) / CHUNK_SIZE;
+    let chunks_x = grid.width.div_ceil(CHUNK_SIZE);
+    let chunks_y = grid.height.div_ceil(CHUNK_SIZE);
 
     // Process chunks in layers: (x%2, y%2) pattern ensures no overlap
     // Layer 0: (even, even), Layer 1: (odd, even), Layer 2: (even, odd), Layer 3: (odd, odd)
@@ -30,25 +206,26 @@ pub fn apply_rules(grid: &mut Grid) {
         let chunk_coords: Vec<(u32, u32)> = (0..chunks_y)
             .flat_map(|cy| (0..chunks_x).map(move |cx| (cx, cy)))
             .filter(|(cx, cy)| (cx % 2) as usize == (layer % 2) && (cy % 2) as usize == (layer / 2))
+            .filter(|&(cx, cy)| grid.is_chunk_active(cx, cy))
             .collect();
 
         // Copy all boundaries for this layer first
+        grid.reset_boundary_coverage();
         for (chunk_x, chunk_y) in &chunk_coords {
             grid.copy_chunk_boundary(*chunk_x, *chunk_y);
         }
 
         // Process all chunks in this layer (sequential to maintain mutation safety)
         for (chunk_x, chunk_y) in chunk_coords {
-            let mut local_rng = rand::thread_rng();
-            process_chunk(grid, chunk_x, chunk_y, &mut local_rng);
+            visit(grid, chunk_x, chunk_y);
         }
     }
 
     grid.swap_buffers();
 }
 
-/// Process a single 32x32 chunk of the grid
-fn process_chunk(grid: &mut Grid, chunk_x: u32, chunk_y: u32, rng: &mut impl Rng) {
+/// Process a single 32x32 chunk of the grid, returning the number of cells visited
+fn process_chunk(grid: &mut Grid, chunk_x: u32, chunk_y: u32, config: &RuleConfig, rng: &mut TickRng<impl RngCore>) -> usize {
     let start_x = chunk_x * CHUNK_SIZE;
     let start_y = chunk_y * CHUNK_SIZE;
     let end_x = (start_x + CHUNK_SIZE).min(grid.width);
@@ -58,69 +235,127 @@ fn process_chunk(grid: &mut Grid, chunk_x: u32, chunk_y: u32, rng: &mut impl Rng
     for y in start_y..end_y {
         for x in start_x..end_x {
             if let Some(cell) = grid.get_cell(x, y) {
-                apply_cell_rules(grid, x, y, &cell, rng);
+                apply_cell_rules(grid, x, y, &cell, config, rng);
             }
         }
     }
+    ((end_x - start_x) * (end_y - start_y)) as usize
 }
 
 /// Apply rules to a single cell
-fn apply_cell_rules(grid: &mut Grid, x: u32, y: u32, cell: &Cell, _rng: &mut impl Rng) {
-    // Fast path: black cells are inert unless reproduction occurs
-    if cell.cell_type == CellType::Black {
-        check_reproduction(grid, x, y, _rng);
+fn apply_cell_rules(grid: &mut Grid, x: u32, y: u32, cell: &Cell, config: &RuleConfig, rng: &mut TickRng<impl RngCore>) {
+    // Disabled types still occupy their cell but neither act nor reproduce
+    if config.disabled.contains(cell.cell_type) {
         return;
     }
 
-    let mut local_rng = rand::thread_rng();
+    // Fast path: empty cells are inert unless reproduction occurs
+    if cell.cell_type == grid.empty_type() {
+        check_reproduction(grid, x, y, config, rng);
+        return;
+    }
 
     // Check for reproduction (very rare)
-    check_reproduction(grid, x, y, &mut local_rng);
-
-    let modified = match cell.cell_type {
-        CellType::Red => { apply_red_rules(grid, x, y); true },
-        CellType::Purple => { apply_purple_rules(grid, x, y, &mut local_rng); true },
-        CellType::Gray => { apply_gray_rules(grid, x, y, &mut local_rng); true },
-        CellType::Orange => { apply_orange_rules(grid, x, y); true },
-        CellType::Green => { apply_green_rules(grid, x, y, &mut local_rng); true },
-        CellType::White => { apply_white_rules(grid, x, y, &mut local_rng); true },
-        CellType::Blue => { apply_blue_rules(grid, x, y, &mut local_rng); true },
-        CellType::Brown => { apply_brown_rules(grid, x, y, &mut local_rng); true },
-        CellType::Tan => { apply_tan_rules(grid, x, y, &mut local_rng); true },
-        CellType::Gold => { apply_gold_rules(grid, x, y, &mut local_rng); true },
-        CellType::Lime => { apply_lime_rules(grid, x, y); true },
-        CellType::Crimson => { apply_crimson_rules(grid, x, y, &mut local_rng); true },
-        CellType::Maroon => { apply_maroon_rules(grid, x, y, &mut local_rng); true },
-        CellType::Coral => { apply_coral_rules(grid, x, y, &mut local_rng); true },
-        CellType::Pink => { apply_pink_rules(grid, x, y, &mut local_rng); true },
-        CellType::Magenta => { apply_magenta_rules(grid, x, y, &mut local_rng); true },
-        CellType::Cyan => { apply_cyan_rules(grid, x, y, &mut local_rng); true },
-        CellType::Yellow => { apply_yellow_rules(grid, x, y, &mut local_rng); true },
-        CellType::Teal => { apply_teal_rules(grid, x, y, &mut local_rng); true },
-        CellType::Navy => { apply_navy_rules(grid, x, y, &mut local_rng); true },
-        CellType::Olive => { apply_olive_rules(grid, x, y, &mut local_rng); true },
-        CellType::Indigo => { apply_indigo_rules(grid, x, y); false },
-        CellType::Khaki => { apply_khaki_rules(grid, x, y, &mut local_rng); true },
-        CellType::Slate => { apply_slate_rules(grid, x, y); false },
-        CellType::Rust => { apply_rust_rules(grid, x, y, &mut local_rng); true },
-        CellType::Mint => { apply_mint_rules(grid, x, y, &mut local_rng); true },
-        CellType::Peach => { apply_peach_rules(grid, x, y, &mut local_rng); true },
-        CellType::Aqua => { apply_aqua_rules(grid, x, y, &mut local_rng); true },
-        CellType::Silver => { apply_silver_rules(grid, x, y, &mut local_rng); true },
-        CellType::Violet => { apply_violet_rules(grid, x, y, &mut local_rng); true },
-        CellType::Amber => { apply_amber_rules(grid, x, y, &mut local_rng); true },
-        CellType::Pearl => { apply_pearl_rules(grid, x, y); false },
-        CellType::Smoke => { apply_smoke_rules(grid, x, y, &mut local_rng); true },
-        CellType::Glint => { apply_glint_rules(grid, x, y, &mut local_rng); true },
-        CellType::Tint => { apply_tint_rules(grid, x, y, &mut local_rng); true },
-        CellType::Shade => { apply_shade_rules(grid, x, y, &mut local_rng); true },
-        CellType::Black => false, // Handled above
-    };
-    
-    // If no rule modified this cell, copy it to next state
-    if !modified && grid.get_next_cell(x, y).is_none() {
-        grid.set_next_cell(x, y, cell.clone());
+    check_reproduction(grid, x, y, config, rng);
+
+    match cell.cell_type {
+        CellType::Red => apply_red_rules(grid, x, y, config, rng),
+        CellType::Purple => apply_purple_rules(grid, x, y, config, rng),
+        CellType::Gray => apply_gray_rules(grid, x, y, config, rng),
+        CellType::Orange => apply_orange_rules(grid, x, y, config, rng),
+        CellType::Green => apply_green_rules(grid, x, y, config, rng),
+        CellType::White => apply_white_rules(grid, x, y, config, rng),
+        CellType::Blue => apply_blue_rules(grid, x, y, config, rng),
+        CellType::Brown => apply_grazer_rules(grid, x, y, &config.herbivore.brown, config.neighborhood, rng),
+        CellType::Tan => apply_grazer_rules(grid, x, y, &config.herbivore.tan, config.neighborhood, rng),
+        CellType::Gold => apply_gold_rules(grid, x, y, config, rng),
+        CellType::Lime => apply_lime_rules(grid, x, y, config, rng),
+        CellType::Crimson => apply_crimson_rules(grid, x, y, config, rng),
+        CellType::Maroon => apply_maroon_rules(grid, x, y, config, rng),
+        CellType::Coral => apply_coral_rules(grid, x, y, config, rng),
+        CellType::Pink => apply_pink_rules(grid, x, y, config, rng),
+        CellType::Magenta => apply_magenta_rules(grid, x, y, config, rng),
+        CellType::Cyan => apply_cyan_rules(grid, x, y, config, rng),
+        CellType::Yellow => apply_yellow_rules(grid, x, y, config, rng),
+        CellType::Teal => apply_teal_rules(grid, x, y, config, rng),
+        CellType::Navy => apply_navy_rules(grid, x, y, config, rng),
+        CellType::Olive => apply_olive_rules(grid, x, y, config, rng),
+        CellType::Indigo => apply_indigo_rules(grid, x, y, config, rng),
+        CellType::Khaki => apply_khaki_rules(grid, x, y, config, rng),
+        CellType::Slate => apply_slate_rules(grid, x, y, config, rng),
+        CellType::Rust => apply_rust_rules(grid, x, y, config, rng),
+        CellType::Mint => apply_mint_rules(grid, x, y, config, rng),
+        CellType::Peach => apply_peach_rules(grid, x, y, config, rng),
+        CellType::Aqua => apply_aqua_rules(grid, x, y, config, rng),
+        CellType::Silver => apply_silver_rules(grid, x, y, config, rng),
+        CellType::Violet => apply_violet_rules(grid, x, y, config, rng),
+        CellType::Amber => apply_amber_rules(grid, x, y, rng),
+        CellType::Pearl => apply_pearl_rules(grid, x, y),
+        CellType::Smoke => apply_smoke_rules(grid, x, y, config, rng),
+        CellType::Glint => apply_glint_rules(grid, x, y, rng),
+        CellType::Tint => apply_tint_rules(grid, x, y, config, rng),
+        CellType::Shade => apply_shade_rules(grid, x, y, config, rng),
+        CellType::Black => {} // No rule of its own
     }
+
+    if config.smoke.source == Some(cell.cell_type) {
+        emit_smoke(grid, x, y, config, rng);
+    }
+}
+
+/// Call `f(grid, nx, ny)` for each neighbor of (x, y), in row-major order
+///
+/// Coordinates come from `Grid::neighbor_coord`: neighbors off the edge are
+/// skipped, or wrap around on a toroidal grid.
+#[inline]
+fn for_each_neighbor(grid: &mut Grid, x: u32, y: u32, neighborhood: Neighborhood, mut f: impl FnMut(&mut Grid, u32, u32)) {
+    for &(dx, dy) in neighborhood.offsets() {
+        if let Some((nx, ny)) = grid.neighbor_coord(x, y, dx, dy) {
+            f(grid, nx, ny);
+        }
+    }
+}
+
+/// `for_each_neighbor` out to `radius` steps, in row-major order
+///
+/// Moore covers the (2r+1)^2 square and von Neumann the diamond of
+/// Manhattan distance r, center excluded; radius 1 visits exactly the
+/// cells `for_each_neighbor` does.
+fn for_each_neighbor_within(
+    grid: &mut Grid,
+    x: u32,
+    y: u32,
+    neighborhood: Neighborhood,
+    radius: u32,
+    mut f: impl FnMut(&mut Grid, u32, u32),
+) {
+    let r = radius as i32;
+    for dy in -r..=r {
+        for dx in -r..=r {
+            let inside = match neighborhood {
+                Neighborhood::Moore => true,
+                Neighborhood::VonNeumann => dx.abs() + dy.abs() <= r,
+            };
+            if (dx, dy) == (0, 0) || !inside {
+                continue;
+            }
+            if let Some((nx, ny)) = grid.neighbor_coord(x, y, dx, dy) {
+                f(grid, nx, ny);
+            }
+        }
+    }
+}
+
+/// Multiplier a gene applies to a rate: 0 at 0.0, 1 at the default 0.5, 2 at 1.0
+#[inline]
+fn gene_scale(gene: f64) -> f64 {
+    gene.clamp(0.0, 1.0) * 2.0
+}
+
+/// `p` scaled by a gene, kept a valid probability
+#[inline]
+fn gene_rate(p: f64, gene: f64) -> f64 {
+    (p * gene_scale(gene)).min(1.0)
 }
 
 // ============================================================================
@@ -128,784 +363,695 @@ fn apply_cell_rules(grid: &mut Grid, x: u32, y: u32, cell: &Cell, _rng: &mut imp
 // ============================================================================
 // All functions use count_*_isolated() which read from boundary_buffer
 // instead of the live grid. This ensures consistent reads across all neighbors.
+//
+// Read/write discipline: rules decide from the current state only (the
+// boundary buffer or `get_cell`) and write only to `next_cells`. Never branch
+// on `get_next_cell` for another cell: whether a neighbor in another chunk has
+// been processed yet depends on layer order, so its next value may or may not
+// include that chunk's writes. Each tick starts with `next_cells` equal to
+// `cells`, so a cell no rule writes stays as it is. When two rules write the
+// same cell the last one wins, so a conversion that must hold regardless of
+// order (White curing Orange/Gray) is also honored by the target's own rule.
 
-fn apply_red_rules(grid: &mut Grid, x: u32, y: u32) {
-    for dy in -1..=1i32 {
-        for dx in -1..=1i32 {
-            if dx == 0 && dy == 0 {
-                continue;
-            }
-            let nx = (x as i32 + dx) as u32;
-            let ny = (y as i32 + dy) as u32;
-            if let Some(neighbor) = grid.get_cell(nx, ny) {
-                if neighbor.cell_type == CellType::Purple {
-                    grid.set_next_cell(nx, ny, Cell::new(CellType::Black));
-                } else if neighbor.cell_type == CellType::Gray {
-                    grid.set_next_cell(nx, ny, Cell::new(CellType::Orange));
-                } else if neighbor.cell_type == CellType::Pink {
-                    grid.set_next_cell(nx, ny, Cell::new(CellType::Black));
-                }
+fn apply_red_rules(grid: &mut Grid, x: u32, y: u32, config: &RuleConfig, rng: &mut TickRng<impl RngCore>) {
+    for_each_neighbor(grid, x, y, config.neighborhood, |grid, nx, ny| {
+        if let Some(neighbor) = grid.get_cell(nx, ny) {
+            if neighbor.cell_type == CellType::Purple {
+                grid.set_next_cell(nx, ny, Cell::new_with_rng(grid.empty_type(), rng));
+            } else if neighbor.cell_type == CellType::Gray {
+                grid.set_next_cell(nx, ny, Cell::new_with_rng(CellType::Orange, rng));
+            } else if neighbor.cell_type == CellType::Pink {
+                grid.set_next_cell(nx, ny, Cell::new_with_rng(grid.empty_type(), rng));
             }
         }
-    }
+    });
 }
 
-fn apply_purple_rules(grid: &mut Grid, x: u32, y: u32, rng: &mut impl Rng) {
-    const SPREAD_RATE: f64 = 0.30;
-    
-    for dy in -1..=1i32 {
-        for dx in -1..=1i32 {
-            if dx == 0 && dy == 0 {
-                continue;
-            }
-            let nx = (x as i32 + dx) as u32;
-            let ny = (y as i32 + dy) as u32;
-            if let Some(neighbor) = grid.get_cell(nx, ny) {
-                let has_peach = grid.count_neighbors_isolated(nx, ny, CellType::Peach) > 0;
-                let has_indigo = grid.count_neighbors_isolated(nx, ny, CellType::Indigo) > 0;
-                let has_olive = grid.count_neighbors_isolated(nx, ny, CellType::Olive) > 0;
+fn apply_purple_rules(grid: &mut Grid, x: u32, y: u32, config: &RuleConfig, rng: &mut TickRng<impl RngCore>) {
+    let insulation = config.peach.insulation_radius.clamp(1, BOUNDARY_RADIUS - 1);
+    
+    for_each_neighbor(grid, x, y, config.neighborhood, |grid, nx, ny| {
+        if let Some(neighbor) = grid.get_cell(nx, ny) {
+            let has_peach = grid.count_in_radius_isolated(nx, ny, CellType::Peach, insulation) > 0;
+            let has_indigo = grid.count_neighbors_isolated(nx, ny, CellType::Indigo, config.neighborhood) > 0;
+            let has_olive = grid.count_neighbors_isolated(nx, ny, CellType::Olive, config.neighborhood) > 0;
                 
-                if !has_peach && !has_indigo && !has_olive {
-                    if (neighbor.cell_type == CellType::Orange || neighbor.cell_type == CellType::Gray)
-                        && rng.gen::<f64>() < SPREAD_RATE
-                    {
-                        grid.set_next_cell(nx, ny, Cell::new(CellType::Purple));
-                    }
-                }
+            if !has_peach && !has_indigo && !has_olive
+                && (neighbor.cell_type == CellType::Orange || neighbor.cell_type == CellType::Gray)
+                && rng.chance(config.purple.spread_rate)
+            {
+                grid.set_next_cell(nx, ny, Cell::new_with_rng(CellType::Purple, rng));
             }
         }
-    }
+    });
 }
 
-fn apply_gray_rules(grid: &mut Grid, x: u32, y: u32, rng: &mut impl Rng) {
+fn apply_gray_rules(grid: &mut Grid, x: u32, y: u32, config: &RuleConfig, rng: &mut TickRng<impl RngCore>) {
     let mut cell = grid.get_cell(x, y).unwrap();
-    cell.age += 1;
+    cell.age = cell.age.saturating_add(rng.age_step());
     
     let num_purple = if rng.gen::<f64>() < 0.5 { 1 } else { 2 };
     let mut produced = 0;
     
-    for dy in -1..=1i32 {
-        for dx in -1..=1i32 {
-            if dx == 0 && dy == 0 || produced >= num_purple {
-                continue;
-            }
-            let nx = (x as i32 + dx) as u32;
-            let ny = (y as i32 + dy) as u32;
-            if let Some(neighbor) = grid.get_cell(nx, ny) {
-                if neighbor.cell_type == CellType::Black {
-                    grid.set_next_cell(nx, ny, Cell::new(CellType::Purple));
-                    produced += 1;
-                }
+    for_each_neighbor(grid, x, y, config.neighborhood, |grid, nx, ny| {
+        if produced >= num_purple {
+            return;
+        }
+        if let Some(neighbor) = grid.get_cell(nx, ny) {
+            if neighbor.cell_type == grid.empty_type() {
+                grid.set_next_cell(nx, ny, Cell::new_with_rng(CellType::Purple, rng));
+                produced += 1;
             }
         }
-    }
-    
-    if cell.age >= 4 && rng.gen::<f64>() < 0.5 {
-        grid.set_next_cell(x, y, Cell::new(CellType::Black));
-        for dy in -1..=1i32 {
-            for dx in -1..=1i32 {
-                if rng.gen::<f64>() < 0.5 {
-                    let nx = (x as i32 + dx) as u32;
-                    let ny = (y as i32 + dy) as u32;
-                    if let Some(neighbor) = grid.get_cell(nx, ny) {
-                        if neighbor.cell_type == CellType::Black {
-                            grid.set_next_cell(nx, ny, Cell::new(CellType::Green));
-                        }
+    });
+    
+    if grid.count_neighbors_isolated(x, y, CellType::White, config.neighborhood) > 0 {
+        // Same conversion a white neighbor writes here
+        grid.set_next_cell(x, y, Cell::new_with_rng(CellType::Red, rng));
+    } else if cell.age >= config.gray.decay_age && rng.chance(gene_rate(config.gray.decay_chance, 1.0 - cell.genes.vitality)) {
+        grid.set_next_cell(x, y, Cell::new_with_rng(grid.empty_type(), rng));
+        for_each_neighbor(grid, x, y, config.neighborhood, |grid, nx, ny| {
+            if rng.gen::<f64>() < 0.5 {
+                if let Some(neighbor) = grid.get_cell(nx, ny) {
+                    if neighbor.cell_type == grid.empty_type() {
+                        grid.set_next_cell(nx, ny, Cell::new_with_rng(CellType::Green, rng));
                     }
                 }
             }
-        }
+        });
     } else {
         grid.set_next_cell(x, y, cell);
     }
 }
 
-fn apply_orange_rules(grid: &mut Grid, x: u32, y: u32) {
-    const SURVIVAL_RADIUS: u32 = 5;
-    const SURVIVAL_THRESHOLD: usize = 3;
-    
-    let green_count = grid.count_in_radius_isolated(x, y, CellType::Green, SURVIVAL_RADIUS);
-    let purple_neighbors = grid.count_neighbors_isolated(x, y, CellType::Purple);
-    let white_neighbors = grid.count_neighbors_isolated(x, y, CellType::White);
-    
-    if green_count < SURVIVAL_THRESHOLD {
-        grid.set_next_cell(x, y, Cell::new(CellType::Gray));
-    } else if purple_neighbors > 0 || white_neighbors > 0 {
-        grid.set_next_cell(x, y, Cell::new(CellType::Red));
+fn apply_orange_rules(grid: &mut Grid, x: u32, y: u32, config: &RuleConfig, rng: &mut TickRng<impl RngCore>) {
+    let orange = &config.orange;
+    let green_count = grid.count_in_radius_isolated(x, y, CellType::Green, orange.survival_radius.min(BOUNDARY_RADIUS));
+    let purple_neighbors = grid.count_neighbors_isolated(x, y, CellType::Purple, config.neighborhood);
+    let white_neighbors = grid.count_neighbors_isolated(x, y, CellType::White, config.neighborhood);
+    
+    // A white neighbor converts this cell too; agree with it so the result
+    // doesn't depend on which of the two runs last
+    if white_neighbors > 0 {
+        grid.set_next_cell(x, y, Cell::new_with_rng(CellType::Red, rng));
+    } else if green_count < orange.survival_threshold {
+        grid.set_next_cell(x, y, Cell::new_with_rng(CellType::Gray, rng));
+    } else if purple_neighbors > 0 {
+        grid.set_next_cell(x, y, Cell::new_with_rng(CellType::Red, rng));
     }
 }
 
-fn apply_green_rules(grid: &mut Grid, x: u32, y: u32, rng: &mut impl Rng) {
-    let mut spread_rate: f64 = 0.40;
+fn apply_green_rules(grid: &mut Grid, x: u32, y: u32, config: &RuleConfig, rng: &mut TickRng<impl RngCore>) {
+    let mut spread_rate = config.green.spread_rate;
     
     // Check local green density to prevent explosive growth
     let local_green = grid.count_in_radius_isolated(x, y, CellType::Green, 5);
     if local_green >= 12 {
         spread_rate = 0.0; // Stop spreading if too dense
     } else if local_green >= 8 {
-        spread_rate = spread_rate * 0.25; // Heavily reduce if moderately dense
+        spread_rate *= 0.25; // Heavily reduce if moderately dense
+    }
+    
+    if grid.count_neighbors_isolated(x, y, CellType::Blue, config.neighborhood) > 0 {
+        spread_rate = (spread_rate * config.blue.green_boost_multiplier).min(config.blue.green_boost_cap);
     }
     
-    if grid.count_neighbors_isolated(x, y, CellType::Blue) > 0 {
-        spread_rate = (spread_rate * 1.75_f64).min(0.70_f64);
+    // Watershed: any water-family cell in range moistens the soil
+    let water = &config.water;
+    if water.moisture_radius > 0 && water.moisture_boost > 0.0 {
+        let radius = water.moisture_radius.min(BOUNDARY_RADIUS);
+        let moist = [CellType::Blue, CellType::Teal, CellType::Navy]
+            .iter()
+            .any(|&t| grid.count_in_radius_isolated(x, y, t, radius) > 0);
+        if moist {
+            spread_rate = (spread_rate + water.moisture_boost).min(water.max_moist_rate);
+        }
     }
     
-    if grid.count_neighbors_isolated(x, y, CellType::Cyan) > 0 
-        || grid.count_neighbors_isolated(x, y, CellType::Olive) > 0 {
+    if grid.count_neighbors_isolated(x, y, CellType::Cyan, config.neighborhood) > 0 
+        || grid.count_neighbors_isolated(x, y, CellType::Olive, config.neighborhood) > 0 {
         spread_rate = (spread_rate * 1.5_f64).min(0.60_f64);
     }
     
-    if grid.count_neighbors_isolated(x, y, CellType::Yellow) > 0 {
-        spread_rate = ((spread_rate + 0.20) as f64).min(0.90);
+    // Photosynthesis: light falls off with distance to the nearest yellow
+    let yellow = &config.yellow;
+    if let Some(distance) = grid.nearest_distance_isolated(x, y, CellType::Yellow, yellow.light_radius) {
+        let boost = yellow.photosynthesis_boost / distance as f64;
+        spread_rate = (spread_rate + boost).min(yellow.max_boosted_rate);
     }
     
-    if grid.count_neighbors_isolated(x, y, CellType::Smoke) > 0 {
-        spread_rate = ((spread_rate - 0.30) as f64).max(0.1);
+    if grid.count_neighbors_isolated(x, y, CellType::Smoke, config.neighborhood) > 0 {
+        spread_rate = (spread_rate - 0.30).max(0.1);
     }
     
-    for dy in -1..=1i32 {
-        for dx in -1..=1i32 {
-            if dx == 0 && dy == 0 {
-                continue;
-            }
-            let nx = (x as i32 + dx) as u32;
-            let ny = (y as i32 + dy) as u32;
-            if let Some(neighbor) = grid.get_cell(nx, ny) {
-                if neighbor.cell_type == CellType::Black && rng.gen::<f64>() < spread_rate {
-                    grid.set_next_cell(nx, ny, Cell::new(CellType::Green));
-                }
+    // The plant's own spread tendency scales whatever the environment allows
+    let spread_tendency = grid.get_cell(x, y).map_or(0.5, |cell| cell.genes.spread_tendency);
+    let spread_rate = gene_rate(spread_rate, spread_tendency);
+    
+    for_each_neighbor(grid, x, y, config.neighborhood, |grid, nx, ny| {
+        if let Some(neighbor) = grid.get_cell(nx, ny) {
+            if neighbor.cell_type == grid.empty_type() && rng.chance(spread_rate) {
+                grid.set_next_cell(nx, ny, Cell::new_with_rng(CellType::Green, rng));
             }
         }
-    }
+    });
 }
 
-fn apply_white_rules(grid: &mut Grid, x: u32, y: u32, rng: &mut impl Rng) {
+fn apply_white_rules(grid: &mut Grid, x: u32, y: u32, config: &RuleConfig, rng: &mut TickRng<impl RngCore>) {
     let green_count = grid.count_in_radius_isolated(x, y, CellType::Green, 5);
     let purple_count = grid.count_in_radius_isolated(x, y, CellType::Purple, 5);
     
     if green_count < 2 && purple_count >= 4 {
-        grid.set_next_cell(x, y, Cell::new(CellType::Black));
+        grid.set_next_cell(x, y, Cell::new_with_rng(grid.empty_type(), rng));
         return;
     }
     
-    for dy in -1..=1i32 {
-        for dx in -1..=1i32 {
-            if dx == 0 && dy == 0 {
-                continue;
-            }
-            let nx = (x as i32 + dx) as u32;
-            let ny = (y as i32 + dy) as u32;
-            if let Some(neighbor) = grid.get_cell(nx, ny) {
-                if neighbor.cell_type != CellType::White {
-                    if let Some(next) = grid.get_next_cell(nx, ny) {
-                        if next.cell_type == CellType::Orange || next.cell_type == CellType::Gray {
-                            grid.set_next_cell(nx, ny, Cell::new(CellType::Red));
-                        }
-                    }
-                }
+    for_each_neighbor(grid, x, y, config.neighborhood, |grid, nx, ny| {
+        if let Some(neighbor) = grid.get_cell_from_boundary(nx, ny) {
+            if neighbor.cell_type == CellType::Orange || neighbor.cell_type == CellType::Gray {
+                grid.set_next_cell(nx, ny, Cell::new_with_rng(CellType::Red, rng));
             }
         }
-    }
+    });
     
-    for dy in -1..=1i32 {
-        for dx in -1..=1i32 {
-            if dx == 0 && dy == 0 {
-                continue;
-            }
-            let nx = (x as i32 + dx) as u32;
-            let ny = (y as i32 + dy) as u32;
-            if let Some(neighbor) = grid.get_cell(nx, ny) {
-                if neighbor.cell_type == CellType::Black && rng.gen::<f64>() < 0.25 {
-                    grid.set_next_cell(nx, ny, Cell::new(CellType::White));
-                }
+    for_each_neighbor(grid, x, y, config.neighborhood, |grid, nx, ny| {
+        if let Some(neighbor) = grid.get_cell(nx, ny) {
+            if neighbor.cell_type == grid.empty_type() && rng.chance(0.25) {
+                grid.set_next_cell(nx, ny, Cell::new_with_rng(CellType::White, rng));
             }
         }
-    }
+    });
 }
 
-fn apply_blue_rules(grid: &mut Grid, x: u32, y: u32, rng: &mut impl Rng) {
+fn apply_blue_rules(grid: &mut Grid, x: u32, y: u32, config: &RuleConfig, rng: &mut TickRng<impl RngCore>) {
+    let blue = &config.blue;
     let mut cell = grid.get_cell(x, y).unwrap();
-    cell.age += 1;
+    cell.age = cell.age.saturating_add(rng.age_step());
     
-    if cell.age >= 8 && rng.gen::<f64>() < 0.3 {
-        grid.set_next_cell(x, y, Cell::new(CellType::Black));
+    if cell.age >= blue.lifespan && rng.chance(blue.evaporation_chance) {
+        grid.set_next_cell(x, y, Cell::new_with_rng(grid.empty_type(), rng));
     } else {
         grid.set_next_cell(x, y, cell);
     }
     
-    for dy in -1..=1i32 {
-        for dx in -1..=1i32 {
-            if dx == 0 && dy == 0 {
-                continue;
-            }
-            let nx = (x as i32 + dx) as u32;
-            let ny = (y as i32 + dy) as u32;
-            if let Some(neighbor) = grid.get_cell(nx, ny) {
-                if neighbor.cell_type == CellType::Black && rng.gen::<f64>() < 0.20 {
-                    grid.set_next_cell(nx, ny, Cell::new(CellType::Green));
-                }
-            }
+    if let Some(saturation) = blue.green_saturation {
+        if grid.count_in_radius_isolated(x, y, CellType::Green, 5) >= saturation {
+            return;
         }
     }
-}
-
-fn apply_brown_rules(grid: &mut Grid, x: u32, y: u32, rng: &mut impl Rng) {
-    let green_count = grid.count_in_radius_isolated(x, y, CellType::Green, 5);
     
-    for dy in -1..=1i32 {
-        for dx in -1..=1i32 {
-            if dx == 0 && dy == 0 {
-                continue;
-            }
-            let nx = (x as i32 + dx) as u32;
-            let ny = (y as i32 + dy) as u32;
-            if let Some(neighbor) = grid.get_cell(nx, ny) {
-                if neighbor.cell_type == CellType::Green && rng.gen::<f64>() < 0.8 {
-                    grid.set_next_cell(nx, ny, Cell::new(CellType::Black));
-                }
+    for_each_neighbor(grid, x, y, config.neighborhood, |grid, nx, ny| {
+        if let Some(neighbor) = grid.get_cell(nx, ny) {
+            if neighbor.cell_type == grid.empty_type() && rng.chance(blue.green_spawn_rate) {
+                grid.set_next_cell(nx, ny, Cell::new_with_rng(CellType::Green, rng));
             }
         }
-    }
-    
-    if green_count == 0 && rng.gen::<f64>() < 0.5 {
-        grid.set_next_cell(x, y, Cell::new(CellType::Gray));
-    }
+    });
 }
 
-fn apply_tan_rules(grid: &mut Grid, x: u32, y: u32, rng: &mut impl Rng) {
-    let green_count = grid.count_in_radius_isolated(x, y, CellType::Green, 5);
-    let orange_count = grid.count_in_radius_isolated(x, y, CellType::Orange, 5);
-    
-    for dy in -1..=1i32 {
-        for dx in -1..=1i32 {
-            if dx == 0 && dy == 0 {
-                continue;
-            }
-            let nx = (x as i32 + dx) as u32;
-            let ny = (y as i32 + dy) as u32;
-            if let Some(neighbor) = grid.get_cell(nx, ny) {
-                if (neighbor.cell_type == CellType::Green || neighbor.cell_type == CellType::Orange) 
-                    && rng.gen::<f64>() < 0.7 {
-                    grid.set_next_cell(nx, ny, Cell::new(CellType::Black));
-                }
+/// Brown and tan: graze adjacent food, starve when little is in range
+fn apply_grazer_rules(
+    grid: &mut Grid,
+    x: u32,
+    y: u32,
+    grazer: &GrazerConfig,
+    neighborhood: Neighborhood,
+    rng: &mut TickRng<impl RngCore>,
+) {
+    let radius = grazer.food_radius.min(BOUNDARY_RADIUS);
+    let food_count: usize = grazer
+        .diet
+        .iter()
+        .map(|&food| grid.count_in_radius_isolated(x, y, food, radius))
+        .sum();
+    
+    for_each_neighbor(grid, x, y, neighborhood, |grid, nx, ny| {
+        if let Some(neighbor) = grid.get_cell(nx, ny) {
+            if grazer.diet.contains(&neighbor.cell_type) && rng.chance(grazer.graze_rate) {
+                grid.set_next_cell(nx, ny, Cell::new_with_rng(grid.empty_type(), rng));
             }
         }
-    }
+    });
     
-    if green_count + orange_count < 2 {
-        grid.set_next_cell(x, y, Cell::new(CellType::Gray));
+    if food_count < grazer.min_food && rng.chance(grazer.starvation_chance) {
+        grid.set_next_cell(x, y, Cell::new_with_rng(grazer.starves_into, rng));
     }
 }
 
-fn apply_gold_rules(grid: &mut Grid, x: u32, y: u32, rng: &mut impl Rng) {
-    let gray_count = grid.count_in_radius_isolated(x, y, CellType::Gray, 5);
+fn apply_gold_rules(grid: &mut Grid, x: u32, y: u32, config: &RuleConfig, rng: &mut TickRng<impl RngCore>) {
+    let gold = &config.gold;
+    let radius = gold.food_radius.min(BOUNDARY_RADIUS);
+    let food_count: usize = gold
+        .scavenges
+        .iter()
+        .map(|&food| grid.count_in_radius_isolated(x, y, food, radius))
+        .sum();
+    
+    if food_count == 0 && rng.chance(gold.starvation_chance) {
+        grid.set_next_cell(x, y, Cell::new_with_rng(grid.empty_type(), rng));
+    } else {
+        grid.set_next_cell(x, y, grid.get_cell(x, y).unwrap());
+    }
     
-    for dy in -1..=1i32 {
-        for dx in -1..=1i32 {
-            if dx == 0 && dy == 0 {
-                continue;
-            }
-            let nx = (x as i32 + dx) as u32;
-            let ny = (y as i32 + dy) as u32;
-            if let Some(neighbor) = grid.get_cell(nx, ny) {
-                if neighbor.cell_type == CellType::Gray && rng.gen::<f64>() < 0.50 {
-                    grid.set_next_cell(nx, ny, Cell::new(CellType::Black));
-                }
+    for_each_neighbor(grid, x, y, config.neighborhood, |grid, nx, ny| {
+        if let Some(neighbor) = grid.get_cell(nx, ny) {
+            if gold.scavenges.contains(&neighbor.cell_type) && rng.chance(gold.scavenge_rate) {
+                grid.set_next_cell(nx, ny, Cell::new_with_rng(grid.empty_type(), rng));
             }
         }
-    }
-    
-    if gray_count == 0 {
-        grid.set_next_cell(x, y, Cell::new(CellType::Black));
-    }
+    });
 }
 
-fn apply_lime_rules(grid: &mut Grid, x: u32, y: u32) {
-    let green_count = grid.count_in_radius_isolated(x, y, CellType::Green, 5);
+fn apply_lime_rules(grid: &mut Grid, x: u32, y: u32, config: &RuleConfig, rng: &mut TickRng<impl RngCore>) {
+    let lime = &config.lime;
+    let radius = lime.dependency_radius.min(BOUNDARY_RADIUS);
+    let host_count = grid.count_in_radius_isolated(x, y, lime.host, radius);
+    
+    if host_count == 0 {
+        grid.set_next_cell(x, y, Cell::new_with_rng(grid.empty_type(), rng));
+        return;
+    }
+    grid.set_next_cell(x, y, grid.get_cell(x, y).unwrap());
     
-    if green_count == 0 {
-        grid.set_next_cell(x, y, Cell::new(CellType::Black));
+    if lime.host_spread_bonus <= 0.0 || grid.count_neighbors_isolated(x, y, lime.host, config.neighborhood) == 0 {
+        return;
     }
+    
+    // Give back: help adjacent hosts grow into the space around the lime
+    for_each_neighbor(grid, x, y, config.neighborhood, |grid, nx, ny| {
+        if let Some(neighbor) = grid.get_cell(nx, ny) {
+            if neighbor.cell_type == grid.empty_type()
+                && grid.count_neighbors_isolated(nx, ny, lime.host, config.neighborhood) > 0
+                && rng.chance(lime.host_spread_bonus)
+            {
+                grid.set_next_cell(nx, ny, Cell::new_with_rng(lime.host, rng));
+            }
+        }
+    });
 }
 
-fn apply_crimson_rules(grid: &mut Grid, x: u32, y: u32, rng: &mut impl Rng) {
+fn apply_crimson_rules(grid: &mut Grid, x: u32, y: u32, config: &RuleConfig, rng: &mut TickRng<impl RngCore>) {
     let prey_count = grid.count_in_radius_isolated(x, y, CellType::Orange, 5)
         + grid.count_in_radius_isolated(x, y, CellType::Brown, 5);
-    
-    for dy in -1..=1i32 {
-        for dx in -1..=1i32 {
-            if dx == 0 && dy == 0 {
-                continue;
-            }
-            let nx = (x as i32 + dx) as u32;
-            let ny = (y as i32 + dy) as u32;
-            if let Some(neighbor) = grid.get_cell(nx, ny) {
-                if (neighbor.cell_type == CellType::Orange || neighbor.cell_type == CellType::Brown) 
-                    && rng.gen::<f64>() < 0.9 {
-                    grid.set_next_cell(nx, ny, Cell::new(CellType::Black));
-                }
+    let aggression = grid.get_cell(x, y).map_or(0.5, |cell| cell.genes.aggression);
+    let kill_rate = gene_rate(config.predator.crimson_kill_rate, aggression);
+    
+    for_each_neighbor(grid, x, y, config.neighborhood, |grid, nx, ny| {
+        if let Some(neighbor) = grid.get_cell(nx, ny) {
+            if (neighbor.cell_type == CellType::Orange || neighbor.cell_type == CellType::Brown) 
+                && rng.chance(kill_rate) {
+                grid.set_next_cell(nx, ny, Cell::new_with_rng(grid.empty_type(), rng));
             }
         }
-    }
+    });
     
     if prey_count == 0 {
-        let mut cell = grid.get_next_cell(x, y).unwrap_or_else(|| grid.get_cell(x, y).unwrap());
-        cell.age += 1;
-        if cell.age >= 3 {
-            grid.set_next_cell(x, y, Cell::new(CellType::Black));
+        // Own next value: a kill written here earlier this tick must stick
+        let mut cell = grid.get_next_cell(x, y).unwrap();
+        cell.age = cell.age.saturating_add(rng.age_step());
+        if cell.age >= config.predator.crimson_starvation_ticks {
+            grid.set_next_cell(x, y, Cell::new_with_rng(grid.empty_type(), rng));
         } else {
             grid.set_next_cell(x, y, cell);
         }
     }
 }
 
-fn apply_maroon_rules(grid: &mut Grid, x: u32, y: u32, rng: &mut impl Rng) {
+fn apply_maroon_rules(grid: &mut Grid, x: u32, y: u32, config: &RuleConfig, rng: &mut TickRng<impl RngCore>) {
     let prey_count = grid.count_in_radius_isolated(x, y, CellType::Orange, 5)
         + grid.count_in_radius_isolated(x, y, CellType::Crimson, 5);
     
-    for dy in -1..=1i32 {
-        for dx in -1..=1i32 {
-            if dx == 0 && dy == 0 {
-                continue;
-            }
-            let nx = (x as i32 + dx) as u32;
-            let ny = (y as i32 + dy) as u32;
-            if let Some(neighbor) = grid.get_cell(nx, ny) {
-                if (neighbor.cell_type == CellType::Orange || neighbor.cell_type == CellType::Crimson) 
-                    && rng.gen::<f64>() < 0.9 {
-                    grid.set_next_cell(nx, ny, Cell::new(CellType::Black));
-                }
+    for_each_neighbor(grid, x, y, config.neighborhood, |grid, nx, ny| {
+        if let Some(neighbor) = grid.get_cell(nx, ny) {
+            if (neighbor.cell_type == CellType::Orange || neighbor.cell_type == CellType::Crimson) 
+                && rng.chance(config.predator.maroon_kill_rate) {
+                grid.set_next_cell(nx, ny, Cell::new_with_rng(grid.empty_type(), rng));
             }
         }
-    }
+    });
     
     if prey_count == 0 {
-        let mut cell = grid.get_next_cell(x, y).unwrap_or_else(|| grid.get_cell(x, y).unwrap());
-        cell.age += 1;
-        if cell.age >= 2 {
-            grid.set_next_cell(x, y, Cell::new(CellType::Black));
+        // Own next value: a kill written here earlier this tick must stick
+        let mut cell = grid.get_next_cell(x, y).unwrap();
+        cell.age = cell.age.saturating_add(rng.age_step());
+        if cell.age >= config.predator.maroon_starvation_ticks {
+            grid.set_next_cell(x, y, Cell::new_with_rng(grid.empty_type(), rng));
         } else {
             grid.set_next_cell(x, y, cell);
         }
     }
 }
 
-fn apply_coral_rules(grid: &mut Grid, x: u32, y: u32, rng: &mut impl Rng) {
+fn apply_coral_rules(grid: &mut Grid, x: u32, y: u32, config: &RuleConfig, rng: &mut TickRng<impl RngCore>) {
     const SPREAD_RATE: f64 = 0.40;
     
-    let white_neighbors = grid.count_neighbors_isolated(x, y, CellType::White);
-    let red_neighbors = grid.count_neighbors_isolated(x, y, CellType::Red);
-    let indigo_neighbors = grid.count_neighbors_isolated(x, y, CellType::Indigo);
-    let pearl_neighbors = grid.count_neighbors_isolated(x, y, CellType::Pearl);
+    let white_neighbors = grid.count_neighbors_isolated(x, y, CellType::White, config.neighborhood);
+    let red_neighbors = grid.count_neighbors_isolated(x, y, CellType::Red, config.neighborhood);
+    let indigo_neighbors = grid.count_neighbors_isolated(x, y, CellType::Indigo, config.neighborhood);
+    let pearl_neighbors = grid.count_neighbors_isolated(x, y, CellType::Pearl, config.neighborhood);
     
     if white_neighbors + red_neighbors + indigo_neighbors + pearl_neighbors > 0 {
-        for dy in -1..=1i32 {
-            for dx in -1..=1i32 {
-                if dx == 0 && dy == 0 {
-                    continue;
-                }
-                let nx = (x as i32 + dx) as u32;
-                let ny = (y as i32 + dy) as u32;
-                if let Some(neighbor) = grid.get_cell(nx, ny) {
-                    if neighbor.cell_type == CellType::Black && rng.gen::<f64>() < 0.1 {
-                        grid.set_next_cell(nx, ny, Cell::new(CellType::Coral));
-                    }
+        for_each_neighbor(grid, x, y, config.neighborhood, |grid, nx, ny| {
+            if let Some(neighbor) = grid.get_cell(nx, ny) {
+                if neighbor.cell_type == grid.empty_type() && rng.chance(0.1) {
+                    grid.set_next_cell(nx, ny, Cell::new_with_rng(CellType::Coral, rng));
                 }
             }
-        }
+        });
     } else {
-        for dy in -1..=1i32 {
-            for dx in -1..=1i32 {
-                if dx == 0 && dy == 0 {
-                    continue;
-                }
-                let nx = (x as i32 + dx) as u32;
-                let ny = (y as i32 + dy) as u32;
-                if let Some(neighbor) = grid.get_cell(nx, ny) {
-                    if neighbor.cell_type == CellType::Black && rng.gen::<f64>() < SPREAD_RATE {
-                        grid.set_next_cell(nx, ny, Cell::new(CellType::Coral));
-                    }
+        for_each_neighbor(grid, x, y, config.neighborhood, |grid, nx, ny| {
+            if let Some(neighbor) = grid.get_cell(nx, ny) {
+                if neighbor.cell_type == grid.empty_type() && rng.chance(SPREAD_RATE) {
+                    grid.set_next_cell(nx, ny, Cell::new_with_rng(CellType::Coral, rng));
                 }
             }
-        }
+        });
     }
 }
 
-fn apply_pink_rules(grid: &mut Grid, x: u32, y: u32, rng: &mut impl Rng) {
-    let orange_count = grid.count_in_radius_isolated(x, y, CellType::Orange, 5);
-    
-    for dy in -1..=1i32 {
-        for dx in -1..=1i32 {
-            if dx == 0 && dy == 0 {
-                continue;
-            }
-            let nx = (x as i32 + dx) as u32;
-            let ny = (y as i32 + dy) as u32;
-            if let Some(neighbor) = grid.get_cell(nx, ny) {
-                if neighbor.cell_type == CellType::Orange && rng.gen::<f64>() < 0.15 {
-                    grid.set_next_cell(nx, ny, Cell::new(CellType::Pink));
+fn apply_pink_rules(grid: &mut Grid, x: u32, y: u32, config: &RuleConfig, rng: &mut TickRng<impl RngCore>) {
+    let pink = &config.pink;
+    let host_count = grid.count_in_radius_isolated(x, y, pink.host, pink.host_radius.min(BOUNDARY_RADIUS));
+    
+    for_each_neighbor(grid, x, y, config.neighborhood, |grid, nx, ny| {
+        if let Some(neighbor) = grid.get_cell(nx, ny) {
+            if neighbor.cell_type == pink.host && rng.chance(pink.infection_rate) {
+                if pink.converts_host {
+                    grid.set_next_cell(nx, ny, Cell::new_with_rng(CellType::Pink, rng));
+                } else {
+                    let mut host = neighbor;
+                    host.genes.vitality -= pink.harm;
+                    if host.genes.vitality <= 0.0 {
+                        grid.set_next_cell(nx, ny, Cell::new_with_rng(grid.empty_type(), rng));
+                    } else {
+                        grid.set_next_cell(nx, ny, host);
+                    }
                 }
             }
         }
-    }
+    });
     
-    if orange_count == 0 {
-        grid.set_next_cell(x, y, Cell::new(CellType::Black));
+    if host_count == 0 {
+        grid.set_next_cell(x, y, Cell::new_with_rng(grid.empty_type(), rng));
     }
 }
 
-fn apply_magenta_rules(grid: &mut Grid, x: u32, y: u32, rng: &mut impl Rng) {
-    let same_count = grid.count_neighbors_isolated(x, y, CellType::Magenta);
+fn apply_magenta_rules(grid: &mut Grid, x: u32, y: u32, config: &RuleConfig, rng: &mut TickRng<impl RngCore>) {
+    let same_count = grid.count_neighbors_isolated(x, y, CellType::Magenta, config.neighborhood);
     
-    if same_count == 0 && rng.gen::<f64>() < 0.3 {
-        grid.set_next_cell(x, y, Cell::new(CellType::Black));
+    if same_count == 0 && rng.chance(0.3) {
+        grid.set_next_cell(x, y, Cell::new_with_rng(grid.empty_type(), rng));
         return;
     }
     
-    for dy in -1..=1i32 {
-        for dx in -1..=1i32 {
-            if dx == 0 && dy == 0 {
-                continue;
-            }
-            let nx = (x as i32 + dx) as u32;
-            let ny = (y as i32 + dy) as u32;
-            if let Some(_neighbor) = grid.get_cell(nx, ny) {
-                if rng.gen::<f64>() < 0.40 {
-                    let rand_type = (rng.gen::<u8>() % 37) as u8;
-                    if let Some(new_type) = CellType::from_u8(rand_type) {
-                        grid.set_next_cell(nx, ny, Cell::new(new_type));
-                    }
+    for_each_neighbor(grid, x, y, config.neighborhood, |grid, nx, ny| {
+        if let Some(_neighbor) = grid.get_cell(nx, ny) {
+            if rng.chance(0.40) {
+                let species = SpeciesRegistry::builtin();
+                let rand_type = rng.gen::<u8>() % species.len() as u8;
+                if let Some(SpeciesRule::Builtin(new_type)) = species.get(rand_type).map(|s| s.rule) {
+                    grid.set_next_cell(nx, ny, Cell::new_with_rng(new_type, rng));
                 }
             }
         }
-    }
+    });
 }
 
-fn apply_cyan_rules(grid: &mut Grid, x: u32, y: u32, rng: &mut impl Rng) {
+fn apply_cyan_rules(grid: &mut Grid, x: u32, y: u32, config: &RuleConfig, rng: &mut TickRng<impl RngCore>) {
     const SPREAD_RATE: f64 = 0.10;
     
-    for dy in -1..=1i32 {
-        for dx in -1..=1i32 {
-            if dx == 0 && dy == 0 {
-                continue;
-            }
-            let nx = (x as i32 + dx) as u32;
-            let ny = (y as i32 + dy) as u32;
-            if let Some(neighbor) = grid.get_cell(nx, ny) {
-                if neighbor.cell_type == CellType::Black && rng.gen::<f64>() < SPREAD_RATE {
-                    grid.set_next_cell(nx, ny, Cell::new(CellType::Cyan));
-                }
+    for_each_neighbor(grid, x, y, config.neighborhood, |grid, nx, ny| {
+        if let Some(neighbor) = grid.get_cell(nx, ny) {
+            if neighbor.cell_type == grid.empty_type() && rng.chance(SPREAD_RATE) {
+                grid.set_next_cell(nx, ny, Cell::new_with_rng(CellType::Cyan, rng));
             }
         }
-    }
+    });
 }
 
-fn apply_yellow_rules(grid: &mut Grid, x: u32, y: u32, rng: &mut impl Rng) {
-    const SPREAD_RATE: f64 = 0.15;
+fn apply_yellow_rules(grid: &mut Grid, x: u32, y: u32, config: &RuleConfig, rng: &mut TickRng<impl RngCore>) {
+    let yellow = &config.yellow;
     
     let mut cell = grid.get_cell(x, y).unwrap();
-    cell.age += 1;
+    cell.age = cell.age.saturating_add(rng.age_step());
     
-    if cell.age >= 15 {
-        let neighbor_count = grid.count_neighbors_isolated(x, y, CellType::Yellow);
-        if neighbor_count == 0 && rng.gen::<f64>() < 0.3 {
-            grid.set_next_cell(x, y, Cell::new(CellType::Black));
+    if cell.age >= yellow.lifespan {
+        let neighbor_count = grid.count_neighbors_isolated(x, y, CellType::Yellow, config.neighborhood);
+        if neighbor_count == 0 && rng.chance(yellow.burnout_chance) {
+            grid.set_next_cell(x, y, Cell::new_with_rng(grid.empty_type(), rng));
             return;
         }
     }
     
     grid.set_next_cell(x, y, cell);
     
-    for dy in -1..=1i32 {
-        for dx in -1..=1i32 {
-            if dx == 0 && dy == 0 {
-                continue;
-            }
-            let nx = (x as i32 + dx) as u32;
-            let ny = (y as i32 + dy) as u32;
-            if let Some(neighbor) = grid.get_cell(nx, ny) {
-                if neighbor.cell_type == CellType::Black && rng.gen::<f64>() < SPREAD_RATE {
-                    grid.set_next_cell(nx, ny, Cell::new(CellType::Yellow));
-                }
+    for_each_neighbor(grid, x, y, config.neighborhood, |grid, nx, ny| {
+        if let Some(neighbor) = grid.get_cell(nx, ny) {
+            if neighbor.cell_type == grid.empty_type() && rng.chance(yellow.spread_rate) {
+                grid.set_next_cell(nx, ny, Cell::new_with_rng(CellType::Yellow, rng));
             }
         }
-    }
+    });
 }
 
-fn apply_teal_rules(grid: &mut Grid, x: u32, y: u32, rng: &mut impl Rng) {
+fn apply_teal_rules(grid: &mut Grid, x: u32, y: u32, config: &RuleConfig, rng: &mut TickRng<impl RngCore>) {
+    let teal = &config.teal;
     let mut cell = grid.get_cell(x, y).unwrap();
-    cell.age += 1;
+    cell.age = cell.age.saturating_add(rng.age_step());
     
-    if cell.age >= 12 && rng.gen::<f64>() < 0.2 {
-        grid.set_next_cell(x, y, Cell::new(CellType::Black));
+    if cell.age >= teal.lifespan && rng.chance(teal.evaporation_chance) {
+        grid.set_next_cell(x, y, Cell::new_with_rng(grid.empty_type(), rng));
     } else {
         grid.set_next_cell(x, y, cell);
     }
     
-    for dy in -1..=1i32 {
-        for dx in -1..=1i32 {
-            if dx == 0 && dy == 0 {
-                continue;
-            }
-            let nx = (x as i32 + dx) as u32;
-            let ny = (y as i32 + dy) as u32;
-            if let Some(neighbor) = grid.get_cell(nx, ny) {
-                if neighbor.cell_type == CellType::Black && rng.gen::<f64>() < 0.05 {
-                    grid.set_next_cell(nx, ny, Cell::new(CellType::Teal));
-                }
+    for_each_neighbor(grid, x, y, config.neighborhood, |grid, nx, ny| {
+        if let Some(neighbor) = grid.get_cell(nx, ny) {
+            if neighbor.cell_type == grid.empty_type() && rng.chance(teal.spread_rate) {
+                grid.set_next_cell(nx, ny, Cell::new_with_rng(CellType::Teal, rng));
             }
         }
-    }
+    });
 }
 
-fn apply_navy_rules(grid: &mut Grid, x: u32, y: u32, rng: &mut impl Rng) {
-    const SPREAD_RATE: f64 = 0.05;
+fn apply_navy_rules(grid: &mut Grid, x: u32, y: u32, config: &RuleConfig, rng: &mut TickRng<impl RngCore>) {
+    let navy = &config.navy;
     
-    for dy in -1..=1i32 {
-        for dx in -1..=1i32 {
-            if dx == 0 && dy == 0 {
-                continue;
-            }
-            let nx = (x as i32 + dx) as u32;
-            let ny = (y as i32 + dy) as u32;
-            if let Some(neighbor) = grid.get_cell(nx, ny) {
-                if neighbor.cell_type == CellType::Blue && rng.gen::<f64>() < 0.25 {
-                    grid.set_next_cell(nx, ny, Cell::new(CellType::Navy));
-                } else if neighbor.cell_type == CellType::Black && rng.gen::<f64>() < SPREAD_RATE {
-                    grid.set_next_cell(nx, ny, Cell::new(CellType::Navy));
-                }
+    if navy.reversion_rate > 0.0 && rng.chance(navy.reversion_rate) {
+        grid.set_next_cell(x, y, Cell::new_with_rng(CellType::Blue, rng));
+    }
+    
+    for_each_neighbor(grid, x, y, config.neighborhood, |grid, nx, ny| {
+        if let Some(neighbor) = grid.get_cell(nx, ny) {
+            if (neighbor.cell_type == CellType::Blue && rng.chance(navy.blue_conversion_rate))
+                || (neighbor.cell_type == grid.empty_type() && rng.chance(navy.spread_rate))
+            {
+                grid.set_next_cell(nx, ny, Cell::new_with_rng(CellType::Navy, rng));
             }
         }
-    }
+    });
 }
 
-fn apply_olive_rules(grid: &mut Grid, x: u32, y: u32, rng: &mut impl Rng) {
+fn apply_olive_rules(grid: &mut Grid, x: u32, y: u32, config: &RuleConfig, rng: &mut TickRng<impl RngCore>) {
     let mut cell = grid.get_cell(x, y).unwrap();
-    cell.age += 1;
-    
-    if cell.age >= 10 && rng.gen::<f64>() < 0.5 {
-        grid.set_next_cell(x, y, Cell::new(CellType::Black));
-        for dy in -1..=1i32 {
-            for dx in -1..=1i32 {
-                let nx = (x as i32 + dx) as u32;
-                let ny = (y as i32 + dy) as u32;
-                if let Some(neighbor) = grid.get_cell(nx, ny) {
-                    if neighbor.cell_type == CellType::Black && rng.gen::<f64>() < 0.5 {
-                        grid.set_next_cell(nx, ny, Cell::new(CellType::Green));
-                    }
+    cell.age = cell.age.saturating_add(rng.age_step());
+    
+    // Hardier olives (higher vitality) outlive their 50% decay roll more often
+    if cell.age >= 10 && rng.chance(gene_rate(0.5, 1.0 - cell.genes.vitality)) {
+        grid.set_next_cell(x, y, Cell::new_with_rng(grid.empty_type(), rng));
+        for_each_neighbor(grid, x, y, config.neighborhood, |grid, nx, ny| {
+            if let Some(neighbor) = grid.get_cell(nx, ny) {
+                if neighbor.cell_type == grid.empty_type() && rng.chance(0.5) {
+                    grid.set_next_cell(nx, ny, Cell::new_with_rng(CellType::Green, rng));
                 }
             }
-        }
+        });
     } else {
         grid.set_next_cell(x, y, cell);
     }
 }
 
-fn apply_indigo_rules(_grid: &mut Grid, _x: u32, _y: u32) {
-    // Inert
+fn apply_indigo_rules(grid: &mut Grid, x: u32, y: u32, config: &RuleConfig, rng: &mut TickRng<impl RngCore>) {
+    // Inert unless nutrient release is enabled
+    let release_rate = config.indigo.nutrient_release_rate;
+    if release_rate > 0.0 && rng.chance(release_rate) {
+        grid.set_next_cell(x, y, Cell::new_with_rng(CellType::Cyan, rng));
+    }
 }
 
-fn apply_khaki_rules(grid: &mut Grid, x: u32, y: u32, rng: &mut impl Rng) {
+fn apply_khaki_rules(grid: &mut Grid, x: u32, y: u32, config: &RuleConfig, rng: &mut TickRng<impl RngCore>) {
     const SPREAD_RATE: f64 = 0.30;
     
     let green_count = grid.count_in_radius_isolated(x, y, CellType::Green, 5);
     
     if green_count > 4 {
-        grid.set_next_cell(x, y, Cell::new(CellType::Black));
+        grid.set_next_cell(x, y, Cell::new_with_rng(grid.empty_type(), rng));
         return;
     }
     
-    for dy in -1..=1i32 {
-        for dx in -1..=1i32 {
-            if dx == 0 && dy == 0 {
-                continue;
-            }
-            let nx = (x as i32 + dx) as u32;
-            let ny = (y as i32 + dy) as u32;
-            if let Some(neighbor) = grid.get_cell(nx, ny) {
-                if (neighbor.cell_type == CellType::Gray || neighbor.cell_type == CellType::Black)
-                    && rng.gen::<f64>() < SPREAD_RATE
-                {
-                    if neighbor.cell_type == CellType::Gray {
-                        grid.set_next_cell(nx, ny, Cell::new(CellType::Cyan));
-                    } else {
-                        grid.set_next_cell(nx, ny, Cell::new(CellType::Khaki));
-                    }
+    for_each_neighbor(grid, x, y, config.neighborhood, |grid, nx, ny| {
+        if let Some(neighbor) = grid.get_cell(nx, ny) {
+            if (neighbor.cell_type == CellType::Gray || neighbor.cell_type == grid.empty_type())
+                && rng.chance(SPREAD_RATE)
+            {
+                if neighbor.cell_type == CellType::Gray {
+                    grid.set_next_cell(nx, ny, Cell::new_with_rng(CellType::Cyan, rng));
+                } else {
+                    grid.set_next_cell(nx, ny, Cell::new_with_rng(CellType::Khaki, rng));
                 }
             }
         }
-    }
+    });
 }
 
-fn apply_slate_rules(_grid: &mut Grid, _x: u32, _y: u32) {
-    // Invisible
+fn apply_slate_rules(grid: &mut Grid, x: u32, y: u32, config: &RuleConfig, rng: &mut TickRng<impl RngCore>) {
+    // Invisible; inert unless decomposition is enabled
+    let slate = &config.slate;
+    if slate.decompose_rate <= 0.0 {
+        return;
+    }
+
+    for_each_neighbor(grid, x, y, config.neighborhood, |grid, nx, ny| {
+        if let Some(neighbor) = grid.get_cell(nx, ny) {
+            if neighbor.cell_type == slate.decomposes && rng.chance(slate.decompose_rate) {
+                grid.set_next_cell(nx, ny, Cell::new_with_rng(slate.decomposes_into, rng));
+            }
+        }
+    });
 }
 
-fn apply_rust_rules(grid: &mut Grid, x: u32, y: u32, rng: &mut impl Rng) {
+fn apply_rust_rules(grid: &mut Grid, x: u32, y: u32, config: &RuleConfig, rng: &mut TickRng<impl RngCore>) {
     const SPREAD_RATE: f64 = 0.25;
     
-    let black_count = grid.count_in_radius_isolated(x, y, CellType::Black, 5);
+    let empty_count = grid.count_in_radius_isolated(x, y, grid.empty_type(), 5);
     
-    for dy in -1..=1i32 {
-        for dx in -1..=1i32 {
-            if dx == 0 && dy == 0 {
-                continue;
-            }
-            let nx = (x as i32 + dx) as u32;
-            let ny = (y as i32 + dy) as u32;
-            if let Some(neighbor) = grid.get_cell(nx, ny) {
-                if neighbor.cell_type == CellType::Black && rng.gen::<f64>() < SPREAD_RATE {
-                    grid.set_next_cell(nx, ny, Cell::new(CellType::Olive));
-                }
+    for_each_neighbor(grid, x, y, config.neighborhood, |grid, nx, ny| {
+        if let Some(neighbor) = grid.get_cell(nx, ny) {
+            if neighbor.cell_type == grid.empty_type() && rng.chance(SPREAD_RATE) {
+                grid.set_next_cell(nx, ny, Cell::new_with_rng(CellType::Olive, rng));
             }
         }
-    }
+    });
     
-    if black_count == 0 {
-        grid.set_next_cell(x, y, Cell::new(CellType::Black));
+    if empty_count == 0 {
+        grid.set_next_cell(x, y, Cell::new_with_rng(grid.empty_type(), rng));
     }
 }
 
-fn apply_mint_rules(grid: &mut Grid, x: u32, y: u32, rng: &mut impl Rng) {
-    const SPREAD_RATE: f64 = 0.15;
-    
-    for dy in -1..=1i32 {
-        for dx in -1..=1i32 {
-            if dx == 0 && dy == 0 {
-                continue;
-            }
-            let nx = (x as i32 + dx) as u32;
-            let ny = (y as i32 + dy) as u32;
-            if let Some(neighbor) = grid.get_cell(nx, ny) {
-                if neighbor.cell_type == CellType::Gray && rng.gen::<f64>() < SPREAD_RATE {
-                    grid.set_next_cell(nx, ny, Cell::new(CellType::Orange));
-                }
-            }
+fn apply_mint_rules(grid: &mut Grid, x: u32, y: u32, config: &RuleConfig, rng: &mut TickRng<impl RngCore>) {
+    let mint = &config.mint;
+    let radius = mint.radius.min(BOUNDARY_RADIUS);
+
+    for_each_neighbor_within(grid, x, y, config.neighborhood, radius, |grid, nx, ny| {
+        if grid.get_cell(nx, ny).is_some_and(|n| n.cell_type == mint.heals) && rng.chance(mint.heal_rate) {
+            grid.set_next_cell(nx, ny, Cell::new_with_rng(mint.heals_into, rng));
         }
-    }
+    });
 }
 
-fn apply_peach_rules(grid: &mut Grid, x: u32, y: u32, rng: &mut impl Rng) {
-    const SPREAD_RATE: f64 = 0.20;
+fn apply_peach_rules(grid: &mut Grid, x: u32, y: u32, config: &RuleConfig, rng: &mut TickRng<impl RngCore>) {
+    let peach = &config.peach;
+    let radius = peach.exposure_radius.min(BOUNDARY_RADIUS);
     
-    let purple_count = grid.count_in_radius_isolated(x, y, CellType::Purple, 5);
+    let purple_count = grid.count_in_radius_isolated(x, y, CellType::Purple, radius);
     
-    if purple_count >= 5 && rng.gen::<f64>() < 0.5 {
-        grid.set_next_cell(x, y, Cell::new(CellType::Black));
+    if purple_count >= peach.death_threshold && rng.chance(peach.death_chance) {
+        grid.set_next_cell(x, y, Cell::new_with_rng(grid.empty_type(), rng));
         return;
     }
     
-    for dy in -1..=1i32 {
-        for dx in -1..=1i32 {
-            if dx == 0 && dy == 0 {
-                continue;
-            }
-            let nx = (x as i32 + dx) as u32;
-            let ny = (y as i32 + dy) as u32;
-            if let Some(neighbor) = grid.get_cell(nx, ny) {
-                if neighbor.cell_type == CellType::Black && rng.gen::<f64>() < SPREAD_RATE {
-                    grid.set_next_cell(nx, ny, Cell::new(CellType::Peach));
-                }
+    for_each_neighbor(grid, x, y, config.neighborhood, |grid, nx, ny| {
+        if let Some(neighbor) = grid.get_cell(nx, ny) {
+            if neighbor.cell_type == grid.empty_type() && rng.chance(peach.spread_rate) {
+                grid.set_next_cell(nx, ny, Cell::new_with_rng(CellType::Peach, rng));
             }
         }
-    }
+    });
 }
 
-fn apply_aqua_rules(grid: &mut Grid, x: u32, y: u32, rng: &mut impl Rng) {
-    const SPREAD_RATE: f64 = 0.12;
+fn apply_aqua_rules(grid: &mut Grid, x: u32, y: u32, config: &RuleConfig, rng: &mut TickRng<impl RngCore>) {
+    const CHAOS_TYPES: [CellType; 3] = [CellType::Magenta, CellType::Crimson, CellType::Purple];
+    let aqua = &config.aqua;
     
-    let chaos = grid.count_neighbors_isolated(x, y, CellType::Magenta)
-        + grid.count_neighbors_isolated(x, y, CellType::Crimson)
-        + grid.count_neighbors_isolated(x, y, CellType::Purple);
+    let chaos: usize = CHAOS_TYPES
+        .iter()
+        .map(|&t| grid.count_neighbors_isolated(x, y, t, config.neighborhood))
+        .sum();
     
-    if chaos > 6 && rng.gen::<f64>() < 0.5 {
-        grid.set_next_cell(x, y, Cell::new(CellType::Black));
+    if chaos > aqua.chaos_threshold && rng.chance(aqua.death_chance) {
+        grid.set_next_cell(x, y, Cell::new_with_rng(grid.empty_type(), rng));
         return;
     }
     
-    for dy in -1..=1i32 {
-        for dx in -1..=1i32 {
-            if dx == 0 && dy == 0 {
-                continue;
-            }
-            let nx = (x as i32 + dx) as u32;
-            let ny = (y as i32 + dy) as u32;
-            if let Some(neighbor) = grid.get_cell(nx, ny) {
-                if neighbor.cell_type == CellType::Black && rng.gen::<f64>() < SPREAD_RATE {
-                    grid.set_next_cell(nx, ny, Cell::new(CellType::Aqua));
-                }
+    for_each_neighbor(grid, x, y, config.neighborhood, |grid, nx, ny| {
+        if let Some(neighbor) = grid.get_cell(nx, ny) {
+            if neighbor.cell_type == grid.empty_type() && rng.chance(aqua.spread_rate) {
+                grid.set_next_cell(nx, ny, Cell::new_with_rng(CellType::Aqua, rng));
+            } else if CHAOS_TYPES.contains(&neighbor.cell_type)
+                && aqua.stabilize_rate > 0.0
+                && rng.chance(aqua.stabilize_rate)
+            {
+                grid.set_next_cell(nx, ny, Cell::new_with_rng(grid.empty_type(), rng));
             }
         }
-    }
+    });
 }
 
-fn apply_silver_rules(grid: &mut Grid, x: u32, y: u32, rng: &mut impl Rng) {
-    let white_neighbors = grid.count_neighbors_isolated(x, y, CellType::White);
+fn apply_silver_rules(grid: &mut Grid, x: u32, y: u32, config: &RuleConfig, rng: &mut TickRng<impl RngCore>) {
+    let silver = &config.silver;
+    let white_neighbors = grid.count_neighbors_isolated(x, y, CellType::White, config.neighborhood);
     
-    if white_neighbors < 2 && rng.gen::<f64>() < 0.1 {
-        grid.set_next_cell(x, y, Cell::new(CellType::White));
+    if white_neighbors < silver.white_neighbor_threshold && rng.chance(silver.white_transform_rate) {
+        grid.set_next_cell(x, y, Cell::new_with_rng(CellType::White, rng));
         return;
     }
     
-    let threats = grid.count_in_radius_isolated(x, y, CellType::Purple, 10)
-        + grid.count_in_radius_isolated(x, y, CellType::Crimson, 10);
+    let threat_radius = silver.threat_radius.min(BOUNDARY_RADIUS);
+    let threats = grid.count_in_radius_isolated(x, y, CellType::Purple, threat_radius)
+        + grid.count_in_radius_isolated(x, y, CellType::Crimson, threat_radius);
     
     if threats > 0 {
-        for dy in -1..=1i32 {
-            for dx in -1..=1i32 {
-                if dx == 0 && dy == 0 {
-                    continue;
-                }
-                let nx = (x as i32 + dx) as u32;
-                let ny = (y as i32 + dy) as u32;
-                if let Some(neighbor) = grid.get_cell(nx, ny) {
-                    if (neighbor.cell_type == CellType::Orange || neighbor.cell_type == CellType::Gray)
-                        && rng.gen::<f64>() < 0.5
-                    {
-                        grid.set_next_cell(nx, ny, Cell::new(CellType::Red));
-                    }
+        for_each_neighbor(grid, x, y, config.neighborhood, |grid, nx, ny| {
+            if let Some(neighbor) = grid.get_cell(nx, ny) {
+                if (neighbor.cell_type == CellType::Orange || neighbor.cell_type == CellType::Gray)
+                    && rng.chance(silver.defense_rate)
+                {
+                    grid.set_next_cell(nx, ny, Cell::new_with_rng(CellType::Red, rng));
                 }
             }
-        }
+        });
     }
 }
 
-fn apply_violet_rules(grid: &mut Grid, x: u32, y: u32, rng: &mut impl Rng) {
-    const SPREAD_RATE: f64 = 0.08;
+fn apply_violet_rules(grid: &mut Grid, x: u32, y: u32, config: &RuleConfig, rng: &mut TickRng<impl RngCore>) {
+    let violet = &config.violet;
     
-    let pearl_count = grid.count_neighbors_isolated(x, y, CellType::Pearl);
-    let white_count = grid.count_neighbors_isolated(x, y, CellType::White);
-    let indigo_count = grid.count_neighbors_isolated(x, y, CellType::Indigo);
+    let immune_count: usize = violet
+        .immune
+        .iter()
+        .map(|&t| grid.count_neighbors_isolated(x, y, t, config.neighborhood))
+        .sum();
     
-    if pearl_count + white_count + indigo_count > 4 && rng.gen::<f64>() < 0.5 {
-        grid.set_next_cell(x, y, Cell::new(CellType::Black));
+    if immune_count > violet.containment_threshold && rng.chance(violet.containment_death_chance) {
+        grid.set_next_cell(x, y, Cell::new_with_rng(grid.empty_type(), rng));
         return;
     }
     
-    for dy in -1..=1i32 {
-        for dx in -1..=1i32 {
-            if dx == 0 && dy == 0 {
-                continue;
-            }
-            let nx = (x as i32 + dx) as u32;
-            let ny = (y as i32 + dy) as u32;
-            if let Some(neighbor) = grid.get_cell(nx, ny) {
-                if neighbor.cell_type != CellType::Pearl && neighbor.cell_type != CellType::White
-                    && neighbor.cell_type != CellType::Indigo && rng.gen::<f64>() < SPREAD_RATE
-                {
-                    grid.set_next_cell(nx, ny, Cell::new(CellType::Black));
-                }
+    for_each_neighbor(grid, x, y, config.neighborhood, |grid, nx, ny| {
+        if let Some(neighbor) = grid.get_cell(nx, ny) {
+            if !violet.immune.contains(&neighbor.cell_type) && rng.chance(violet.destruction_rate) {
+                grid.set_next_cell(nx, ny, Cell::new_with_rng(grid.empty_type(), rng));
             }
         }
-    }
+    });
 }
 
-fn apply_amber_rules(grid: &mut Grid, x: u32, y: u32, rng: &mut impl Rng) {
+fn apply_amber_rules(grid: &mut Grid, x: u32, y: u32, rng: &mut TickRng<impl RngCore>) {
     let mut cell = grid.get_cell(x, y).unwrap();
-    cell.age += 1;
+    cell.age = cell.age.saturating_add(rng.age_step());
     
-    if cell.age >= 5 && rng.gen::<f64>() < 0.5 {
-        grid.set_next_cell(x, y, Cell::new(CellType::Black));
+    if cell.age >= 5 && rng.chance(0.5) {
+        grid.set_next_cell(x, y, Cell::new_with_rng(grid.empty_type(), rng));
         return;
     }
     
@@ -916,38 +1062,53 @@ fn apply_pearl_rules(_grid: &mut Grid, _x: u32, _y: u32) {
     // Immobile
 }
 
-fn apply_smoke_rules(grid: &mut Grid, x: u32, y: u32, rng: &mut impl Rng) {
-    const SPREAD_RATE: f64 = 0.25;
+fn apply_smoke_rules(grid: &mut Grid, x: u32, y: u32, config: &RuleConfig, rng: &mut TickRng<impl RngCore>) {
+    let smoke = &config.smoke;
+
+    // Aging only matters once dissipation is on
+    if smoke.dissipation_rate > 0.0 {
+        let mut cell = grid.get_cell(x, y).unwrap();
+        cell.age = cell.age.saturating_add(rng.age_step());
+        if cell.age >= smoke.lifespan && rng.chance(smoke.dissipation_rate) {
+            grid.set_next_cell(x, y, Cell::new_with_rng(grid.empty_type(), rng));
+            return;
+        }
+        grid.set_next_cell(x, y, cell);
+    }
     
-    for dy in -1..=1i32 {
-        for dx in -1..=1i32 {
-            if dx == 0 && dy == 0 {
-                continue;
-            }
-            let nx = (x as i32 + dx) as u32;
-            let ny = (y as i32 + dy) as u32;
-            if let Some(_neighbor) = grid.get_cell(nx, ny) {
-                if rng.gen::<f64>() < SPREAD_RATE {
-                    grid.set_next_cell(nx, ny, Cell::new(CellType::Smoke));
-                }
+    for_each_neighbor(grid, x, y, config.neighborhood, |grid, nx, ny| {
+        if let Some(_neighbor) = grid.get_cell(nx, ny) {
+            if rng.chance(smoke.spread_rate) {
+                grid.set_next_cell(nx, ny, Cell::new_with_rng(CellType::Smoke, rng));
             }
         }
-    }
+    });
     
-    let yellow_count = grid.count_neighbors_isolated(x, y, CellType::Yellow);
-    let red_count = grid.count_neighbors_isolated(x, y, CellType::Red);
+    let yellow_count = grid.count_neighbors_isolated(x, y, CellType::Yellow, config.neighborhood);
+    let red_count = grid.count_neighbors_isolated(x, y, CellType::Red, config.neighborhood);
     
-    if yellow_count + red_count > 3 && rng.gen::<f64>() < 0.5 {
-        grid.set_next_cell(x, y, Cell::new(CellType::Black));
+    if yellow_count + red_count > 3 && rng.chance(0.5) {
+        grid.set_next_cell(x, y, Cell::new_with_rng(grid.empty_type(), rng));
     }
 }
 
-fn apply_glint_rules(grid: &mut Grid, x: u32, y: u32, rng: &mut impl Rng) {
+/// Smoke emitted by the configured source type into empty neighbors
+fn emit_smoke(grid: &mut Grid, x: u32, y: u32, config: &RuleConfig, rng: &mut TickRng<impl RngCore>) {
+    for_each_neighbor(grid, x, y, config.neighborhood, |grid, nx, ny| {
+        if let Some(neighbor) = grid.get_cell(nx, ny) {
+            if neighbor.cell_type == grid.empty_type() && rng.chance(config.smoke.emission_rate) {
+                grid.set_next_cell(nx, ny, Cell::new_with_rng(CellType::Smoke, rng));
+            }
+        }
+    });
+}
+
+fn apply_glint_rules(grid: &mut Grid, x: u32, y: u32, rng: &mut TickRng<impl RngCore>) {
     let mut cell = grid.get_cell(x, y).unwrap();
-    cell.age += 1;
+    cell.age = cell.age.saturating_add(rng.age_step());
     
-    if cell.age >= 2 && rng.gen::<f64>() < 0.8 {
-        grid.set_next_cell(x, y, Cell::new(CellType::Black));
+    if cell.age >= 2 && rng.chance(0.8) {
+        grid.set_next_cell(x, y, Cell::new_with_rng(grid.empty_type(), rng));
         return;
     }
     
@@ -964,82 +1125,899 @@ fn apply_glint_rules(grid: &mut Grid, x: u32, y: u32, rng: &mut impl Rng) {
             if dx == 0 && dy == 0 {
                 continue;
             }
-            let nx = (x as i32 + dx) as u32;
-            let ny = (y as i32 + dy) as u32;
+            let Some((nx, ny)) = grid.neighbor_coord(x, y, dx, dy) else {
+                continue;
+            };
             if let Some(neighbor) = grid.get_cell(nx, ny) {
-                if neighbor.cell_type == CellType::Black && rng.gen::<f64>() < 0.05 {
-                    grid.set_next_cell(nx, ny, Cell::new(CellType::Green));
+                if neighbor.cell_type == grid.empty_type() && rng.chance(0.05) {
+                    grid.set_next_cell(nx, ny, Cell::new_with_rng(CellType::Green, rng));
                 }
             }
         }
     }
 }
 
-fn apply_tint_rules(grid: &mut Grid, x: u32, y: u32, rng: &mut impl Rng) {
-    const SPREAD_RATE: f64 = 0.30;
-    
-    let tint_neighbors = grid.count_neighbors_isolated(x, y, CellType::Tint);
-    
-    if tint_neighbors == 0 && rng.gen::<f64>() < 0.3 {
-        grid.set_next_cell(x, y, Cell::new(CellType::Black));
+fn apply_tint_rules(grid: &mut Grid, x: u32, y: u32, config: &RuleConfig, rng: &mut TickRng<impl RngCore>) {
+    let behavioral = &config.behavioral;
+    let tint_neighbors = grid.count_neighbors_isolated(x, y, CellType::Tint, config.neighborhood);
+    
+    if tint_neighbors < behavioral.tint_min_neighbors
+        && rng.chance(1.0 - behavioral.tint_isolation_tolerance)
+    {
+        grid.set_next_cell(x, y, Cell::new_with_rng(grid.empty_type(), rng));
         return;
     }
+    grid.set_next_cell(x, y, grid.get_cell(x, y).unwrap());
     
-    let spread_rate = if tint_neighbors >= 2 { 0.40 } else { SPREAD_RATE };
+    let spread_rate = if tint_neighbors >= behavioral.tint_flock_size {
+        behavioral.tint_flock_spread_rate
+    } else {
+        behavioral.tint_spread_rate
+    };
     
-    for dy in -1..=1i32 {
-        for dx in -1..=1i32 {
-            if dx == 0 && dy == 0 {
-                continue;
-            }
-            let nx = (x as i32 + dx) as u32;
-            let ny = (y as i32 + dy) as u32;
-            if let Some(neighbor) = grid.get_cell(nx, ny) {
-                if neighbor.cell_type == CellType::Black && rng.gen::<f64>() < spread_rate {
-                    grid.set_next_cell(nx, ny, Cell::new(CellType::Tint));
-                }
+    for_each_neighbor(grid, x, y, config.neighborhood, |grid, nx, ny| {
+        if let Some(neighbor) = grid.get_cell(nx, ny) {
+            if neighbor.cell_type == grid.empty_type() && rng.chance(spread_rate) {
+                grid.set_next_cell(nx, ny, Cell::new_with_rng(CellType::Tint, rng));
             }
         }
-    }
+    });
 }
 
-fn apply_shade_rules(grid: &mut Grid, x: u32, y: u32, rng: &mut impl Rng) {
-    const SPREAD_RATE: f64 = 0.20;
-    
-    let green_count = grid.count_in_radius_isolated(x, y, CellType::Green, 5);
-    let threat_count = grid.count_in_radius_isolated(x, y, CellType::Crimson, 5)
-        + grid.count_in_radius_isolated(x, y, CellType::Purple, 5);
-    
-    if threat_count > 5 && green_count == 0 && rng.gen::<f64>() < 0.5 {
-        grid.set_next_cell(x, y, Cell::new(CellType::Black));
+fn apply_shade_rules(grid: &mut Grid, x: u32, y: u32, config: &RuleConfig, rng: &mut TickRng<impl RngCore>) {
+    let behavioral = &config.behavioral;
+    let radius = behavioral.shade_scan_radius.min(BOUNDARY_RADIUS);
+    
+    let green_count = grid.count_in_radius_isolated(x, y, CellType::Green, radius);
+    let threat_count = grid.count_in_radius_isolated(x, y, CellType::Crimson, radius)
+        + grid.count_in_radius_isolated(x, y, CellType::Purple, radius);
+    
+    if threat_count > behavioral.shade_threat_threshold
+        && green_count == 0
+        && rng.chance(behavioral.shade_flee_rate)
+    {
+        grid.set_next_cell(x, y, Cell::new_with_rng(grid.empty_type(), rng));
         return;
     }
     
     let mut target_dirs = Vec::new();
     
-    for dy in -1..=1i32 {
-        for dx in -1..=1i32 {
-            if dx == 0 && dy == 0 {
-                continue;
-            }
-            let nx = (x as i32 + dx) as u32;
-            let ny = (y as i32 + dy) as u32;
-            if let Some(neighbor) = grid.get_cell(nx, ny) {
-                if neighbor.cell_type == CellType::Black {
-                    let local_green = grid.count_neighbors_isolated(nx, ny, CellType::Green);
-                    let local_threat = grid.count_neighbors_isolated(nx, ny, CellType::Crimson)
-                        + grid.count_neighbors_isolated(nx, ny, CellType::Purple);
+    for_each_neighbor(grid, x, y, config.neighborhood, |grid, nx, ny| {
+        if let Some(neighbor) = grid.get_cell(nx, ny) {
+            if neighbor.cell_type == grid.empty_type() {
+                let local_green = grid.count_neighbors_isolated(nx, ny, CellType::Green, config.neighborhood);
+                let local_threat = grid.count_neighbors_isolated(nx, ny, CellType::Crimson, config.neighborhood)
+                    + grid.count_neighbors_isolated(nx, ny, CellType::Purple, config.neighborhood);
                     
-                    if local_threat == 0 || local_green > 2 {
-                        target_dirs.push((nx, ny));
-                    }
+                if local_threat == 0 || local_green > behavioral.shade_green_cover {
+                    target_dirs.push((nx, ny));
                 }
             }
         }
-    }
+    });
     
-    if !target_dirs.is_empty() && rng.gen::<f64>() < SPREAD_RATE {
+    // Targets are collected in neighborhood order and picked with the tick
+    // RNG, so a seeded run always spreads into the same one
+    if !target_dirs.is_empty() && rng.chance(behavioral.shade_spread_rate) {
         let (nx, ny) = target_dirs[rng.gen_range(0..target_dirs.len())];
-        grid.set_next_cell(nx, ny, Cell::new(CellType::Shade));
+        grid.set_next_cell(nx, ny, Cell::new_with_rng(CellType::Shade, rng));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::Genes;
+    use crate::testutil::{lattice_points, seeded_lattice};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn count_type(grid: &Grid, cell_type: CellType) -> usize {
+        (0..grid.height)
+            .flat_map(|y| (0..grid.width).map(move |x| (x, y)))
+            .filter(|&(x, y)| grid.get_cell(x, y).map(|c| c.cell_type) == Some(cell_type))
+            .count()
+    }
+
+    /// A lattice rate test: every `lattice_points` site gets `host` plus
+    /// `neighbors` at the given offsets, then one run under `baseline` and
+    /// one under `variant`, each ticked `ticks` times from the same seed
+    struct LatticeCase {
+        name: &'static str,
+        host: CellType,
+        neighbors: &'static [(u32, u32, CellType)],
+        ticks: usize,
+        baseline: fn(&mut RuleConfig),
+        variant: fn(&mut RuleConfig),
+        count: fn(&Grid) -> usize,
+        /// Holds for (baseline count, variant count)
+        expect: fn(usize, usize) -> bool,
+    }
+
+    fn run_lattice_case(case: &LatticeCase, tweak: fn(&mut RuleConfig)) -> usize {
+        let mut config = RuleConfig::default();
+        tweak(&mut config);
+        let mut grid = seeded_lattice(Cell::new(case.host));
+        for (x, y) in lattice_points() {
+            for &(dx, dy, cell_type) in case.neighbors {
+                grid.set_cell(x + dx, y + dy, cell_type);
+            }
+        }
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+        for _ in 0..case.ticks {
+            apply_rules_with_rng(&mut grid, &config, &mut rng);
+        }
+        (case.count)(&grid)
+    }
+
+    /// Lattice sites still holding `cell_type`
+    fn count_at_sites(grid: &Grid, cell_type: CellType) -> usize {
+        lattice_points()
+            .filter(|&(x, y)| grid.get_cell(x, y).unwrap().cell_type == cell_type)
+            .count()
+    }
+
+    #[test]
+    fn test_lattice_rate_parameters() {
+        let cases = [
+            // ~280 new green without light vs ~630 with it
+            LatticeCase {
+                name: "yellow photosynthesis boost",
+                host: CellType::Green,
+                neighbors: &[(1, 0, CellType::Yellow)],
+                ticks: 1,
+                baseline: |c| c.yellow.photosynthesis_boost = 0.0,
+                variant: |c| c.yellow.photosynthesis_boost = 0.5,
+                count: |g| count_type(g, CellType::Green),
+                expect: |dim, bright| bright > dim + 150,
+            },
+            // ~320 green after a default tick; none new without spreading
+            LatticeCase {
+                name: "zero green spread rate",
+                host: CellType::Green,
+                neighbors: &[],
+                ticks: 1,
+                baseline: |_| {},
+                variant: |c| {
+                    c.green.spread_rate = 0.0;
+                    c.reproduction.chance = 0.0;
+                },
+                count: |g| count_type(g, CellType::Green),
+                expect: |default, barren| default > 200 && barren == 100,
+            },
+            // ~10 white by default vs ~90 when eager
+            LatticeCase {
+                name: "silver white transform rate",
+                host: CellType::Silver,
+                neighbors: &[],
+                ticks: 1,
+                baseline: |_| {},
+                variant: |c| c.silver.white_transform_rate = 0.9,
+                count: |g| count_type(g, CellType::White),
+                expect: |default, eager| eager > default + 40,
+            },
+            LatticeCase {
+                name: "zero blue green spawn rate",
+                host: CellType::Blue,
+                neighbors: &[],
+                ticks: 1,
+                baseline: |_| {},
+                variant: |c| c.blue.green_spawn_rate = 0.0,
+                count: |g| count_type(g, CellType::Green),
+                expect: |default, dry| default > 0 && dry == 0,
+            },
+            // Blue sits to the left so it is processed first and navy's write wins
+            LatticeCase {
+                name: "zero navy conversion rate",
+                host: CellType::Blue,
+                neighbors: &[(1, 0, CellType::Navy)],
+                ticks: 1,
+                baseline: |_| {},
+                variant: |c| c.navy.blue_conversion_rate = 0.0,
+                count: |g| count_type(g, CellType::Blue),
+                expect: |default, shallow| default < 95 && shallow == 100,
+            },
+            // Gray sits to the left so it is processed first and mint's heal
+            // isn't overwritten by gray aging itself
+            LatticeCase {
+                name: "mint heal rate",
+                host: CellType::Gray,
+                neighbors: &[(1, 0, CellType::Mint)],
+                ticks: 1,
+                baseline: |_| {},
+                variant: |c| c.mint.heal_rate = 0.9,
+                count: |g| count_type(g, CellType::Gray),
+                expect: |default, strong| strong + 40 < default,
+            },
+            LatticeCase {
+                name: "aqua stabilize rate",
+                host: CellType::Magenta,
+                neighbors: &[(1, 0, CellType::Aqua)],
+                ticks: 3,
+                baseline: |_| {},
+                variant: |c| c.aqua.stabilize_rate = 0.8,
+                count: |g| count_at_sites(g, CellType::Magenta),
+                expect: |default, calming| calming + 15 < default,
+            },
+            LatticeCase {
+                name: "lime host spread bonus",
+                host: CellType::Green,
+                neighbors: &[(1, 0, CellType::Lime)],
+                ticks: 1,
+                baseline: |_| {},
+                variant: |c| c.lime.host_spread_bonus = 0.9,
+                count: |g| count_type(g, CellType::Green),
+                expect: |default, generous| generous > default + 60,
+            },
+            // A teal/navy pair three cells from each green, moist or not
+            LatticeCase {
+                name: "water moisture boost",
+                host: CellType::Green,
+                neighbors: &[(3, 3, CellType::Teal), (3, 2, CellType::Navy)],
+                ticks: 1,
+                baseline: |c| {
+                    c.water.moisture_radius = 3;
+                    c.water.moisture_boost = 0.0;
+                },
+                variant: |c| {
+                    c.water.moisture_radius = 3;
+                    c.water.moisture_boost = 0.4;
+                },
+                count: |g| count_type(g, CellType::Green),
+                expect: |dry, wet| wet > dry + 150,
+            },
+            LatticeCase {
+                name: "tint isolation tolerance",
+                host: CellType::Tint,
+                neighbors: &[],
+                ticks: 1,
+                baseline: |_| {},
+                variant: |c| c.behavioral.tint_isolation_tolerance = 1.0,
+                count: |g| count_at_sites(g, CellType::Tint),
+                expect: |default, hardy| default < 90 && hardy == 100,
+            },
+            LatticeCase {
+                name: "indigo nutrient release keeps deposits",
+                host: CellType::Indigo,
+                neighbors: &[],
+                ticks: 5,
+                baseline: |_| {},
+                variant: |c| c.indigo.nutrient_release_rate = 0.2,
+                count: |g| count_type(g, CellType::Indigo),
+                expect: |default, releasing| default == 100 && releasing < 80,
+            },
+            LatticeCase {
+                name: "indigo nutrient release yields cyan",
+                host: CellType::Indigo,
+                neighbors: &[],
+                ticks: 5,
+                baseline: |_| {},
+                variant: |c| c.indigo.nutrient_release_rate = 0.2,
+                count: |g| count_type(g, CellType::Cyan),
+                expect: |default, releasing| default == 0 && releasing > 20,
+            },
+            // No prey anywhere
+            LatticeCase {
+                name: "crimson starvation ticks",
+                host: CellType::Crimson,
+                neighbors: &[],
+                ticks: 5,
+                baseline: |_| {},
+                variant: |c| c.predator.crimson_starvation_ticks = 8,
+                count: |g| count_type(g, CellType::Crimson),
+                expect: |default, patient| default == 0 && patient == 100,
+            },
+            // Pairs where both the green and its brown grazer remain
+            LatticeCase {
+                name: "brown graze rate",
+                host: CellType::Green,
+                neighbors: &[(1, 0, CellType::Brown)],
+                ticks: 3,
+                baseline: |_| {},
+                variant: |c| c.herbivore.brown.graze_rate = 0.05,
+                count: |g| {
+                    lattice_points()
+                        .filter(|&(x, y)| {
+                            g.get_cell(x, y).unwrap().cell_type == CellType::Green
+                                && g.get_cell(x + 1, y).unwrap().cell_type == CellType::Brown
+                        })
+                        .count()
+                },
+                expect: |default, gentle| default < 35 && gentle > default + 40,
+            },
+        ];
+
+        for case in &cases {
+            let baseline = run_lattice_case(case, case.baseline);
+            let variant = run_lattice_case(case, case.variant);
+            assert!((case.expect)(baseline, variant), "{}: baseline {} vs variant {}", case.name, baseline, variant);
+        }
+    }
+
+    #[test]
+    fn test_lime_needs_a_host_in_range() {
+        let mut config = RuleConfig::default();
+        config.lime.host_spread_bonus = 0.9;
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+
+        // Without a host in range, lime dies...
+        let mut grid = Grid::new(16, 16);
+        grid.set_cell(8, 8, CellType::Lime);
+        apply_rules_with_rng(&mut grid, &config, &mut rng);
+        assert_eq!(grid.get_cell(8, 8).unwrap().cell_type, CellType::Black);
+
+        // ...and with one, it lives on
+        let mut grid = Grid::new(16, 16);
+        grid.set_cell(8, 8, CellType::Lime);
+        grid.set_cell(11, 8, CellType::Green);
+        apply_rules_with_rng(&mut grid, &config, &mut rng);
+        assert_eq!(grid.get_cell(8, 8).unwrap().cell_type, CellType::Lime);
+    }
+
+    #[test]
+    fn test_silver_sees_threat_at_full_radius_across_chunk_edge() {
+        // Silver on the right edge of chunk (0, 1), threat in chunk (1, 1)
+        let defended = |threat_x: u32| {
+            let mut config = RuleConfig::default();
+            config.silver.white_transform_rate = 0.0;
+            config.silver.defense_rate = 1.0;
+            config.reproduction.chance = 0.0;
+
+            let mut grid = Grid::new(96, 96);
+            grid.set_cell(31, 40, CellType::Silver);
+            grid.set_cell(30, 40, CellType::Orange);
+            grid.set_cell(threat_x, 40, CellType::Purple);
+
+            grid.copy_chunk_boundary(0, 1);
+            let isolated = grid.count_in_radius_isolated(31, 40, CellType::Purple, config.silver.threat_radius);
+            assert_eq!(isolated, grid.count_in_radius(31, 40, CellType::Purple, config.silver.threat_radius));
+
+            apply_rules(&mut grid, &config);
+            grid.get_cell(30, 40).unwrap().cell_type
+        };
+
+        assert_eq!(RuleConfig::default().silver.threat_radius, BOUNDARY_RADIUS);
+        assert_eq!(defended(41), CellType::Red);
+        // One cell further out: no threat, so the starving orange just grays
+        assert_eq!(defended(42), CellType::Gray);
+    }
+
+    #[test]
+    fn test_white_cure_is_independent_of_chunk_order() {
+        // White and a starving orange (or gray) on either side of the edge
+        // between chunks (0, 1) and (1, 1); the two chunks run in different
+        // layers, so swapping them swaps which rule writes last
+        let cured = |white_x: u32, target_x: u32, target: CellType| {
+            let mut config = RuleConfig::default();
+            config.reproduction.chance = 0.0;
+
+            let mut grid = Grid::new(96, 96);
+            grid.set_cell(white_x, 40, CellType::White);
+            grid.set_cell(target_x, 40, target);
+            apply_rules(&mut grid, &config);
+            grid.get_cell(target_x, 40).unwrap().cell_type
+        };
+
+        for target in [CellType::Orange, CellType::Gray] {
+            assert_eq!(cured(31, 32, target), CellType::Red);
+            assert_eq!(cured(32, 31, target), CellType::Red);
+        }
+    }
+
+    #[test]
+    fn test_von_neumann_green_skips_diagonals() {
+        let seeded = |neighborhood: Neighborhood| {
+            let mut config = RuleConfig::default();
+            config.green.spread_rate = 1.0;
+            config.reproduction.chance = 0.0;
+            config.neighborhood = neighborhood;
+
+            let mut grid = Grid::new(9, 9);
+            grid.set_cell(4, 4, CellType::Green);
+            apply_rules(&mut grid, &config);
+            (0..9)
+                .flat_map(|y| (0..9).map(move |x| (x, y)))
+                .filter(|&(x, y)| (x, y) != (4, 4) && grid.get_cell(x, y).unwrap().cell_type == CellType::Green)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(seeded(Neighborhood::Moore).len(), 8);
+        assert_eq!(seeded(Neighborhood::VonNeumann), vec![(4, 3), (3, 4), (5, 4), (4, 5)]);
+    }
+
+    #[test]
+    fn test_ml_spread_modifier_speeds_green_growth() {
+        use crate::ml_layer::StubRuleGenerator;
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        // The stub raises spread_modifier to 1.3 while green covers < 20%
+        let green_after = |ml: bool, seed: u64| {
+            let mut grid = Grid::new(64, 64);
+            for y in (4..64).step_by(12) {
+                for x in (4..64).step_by(12) {
+                    grid.set_cell(x, y, CellType::Green);
+                }
+            }
+            let config = RuleConfig::default();
+            let mut rng = StdRng::seed_from_u64(seed);
+            for _ in 0..10 {
+                if ml {
+                    apply_rules_ml_with_rng(&mut grid, &config, &StubRuleGenerator, &mut rng);
+                } else {
+                    apply_rules_with_rng(&mut grid, &config, &mut rng);
+                }
+            }
+            count_type(&grid, CellType::Green)
+        };
+
+        let baseline: usize = (0..4).map(|seed| green_after(false, seed)).sum();
+        let boosted: usize = (0..4).map(|seed| green_after(true, seed)).sum();
+        assert!(boosted > baseline, "boosted {} vs baseline {}", boosted, baseline);
+    }
+
+    #[test]
+    fn test_blue_green_saturation_caps_oasis() {
+        // Blue at the center of a ring of 16 green, four cells out
+        let oasis_neighbors_green = |config: &RuleConfig| {
+            let mut grid = Grid::new(21, 21);
+            grid.set_cell(10, 10, CellType::Blue);
+            for i in 6..=14 {
+                if i % 2 == 0 {
+                    grid.set_cell(i, 6, CellType::Green);
+                    grid.set_cell(i, 14, CellType::Green);
+                    grid.set_cell(6, i, CellType::Green);
+                    grid.set_cell(14, i, CellType::Green);
+                }
+            }
+            apply_rules(&mut grid, config);
+            (9..=11)
+                .flat_map(|y| (9..=11).map(move |x| (x, y)))
+                .filter(|&(x, y)| grid.get_cell(x, y).unwrap().cell_type == CellType::Green)
+                .count()
+        };
+
+        let mut lush = RuleConfig::default();
+        lush.blue.green_spawn_rate = 1.0;
+        let mut capped = lush.clone();
+        capped.blue.green_saturation = Some(12);
+
+        assert_eq!(oasis_neighbors_green(&lush), 8);
+        assert_eq!(oasis_neighbors_green(&capped), 0);
+    }
+
+    #[test]
+    fn test_neighbors_within_radius_extend_the_neighborhood() {
+        let mut grid = Grid::new(16, 16);
+        for neighborhood in [Neighborhood::Moore, Neighborhood::VonNeumann] {
+            let mut adjacent = Vec::new();
+            for_each_neighbor(&mut grid, 8, 8, neighborhood, |_, nx, ny| adjacent.push((nx, ny)));
+            let mut within = Vec::new();
+            for_each_neighbor_within(&mut grid, 8, 8, neighborhood, 1, |_, nx, ny| within.push((nx, ny)));
+            assert_eq!(within, adjacent, "{:?}", neighborhood);
+        }
+
+        let mut counts = Vec::new();
+        for neighborhood in [Neighborhood::Moore, Neighborhood::VonNeumann] {
+            let mut n = 0;
+            for_each_neighbor_within(&mut grid, 8, 8, neighborhood, 2, |_, _, _| n += 1);
+            counts.push(n);
+        }
+        assert_eq!(counts, vec![24, 12]);
+    }
+
+    #[test]
+    fn test_von_neumann_mint_heals_only_orthogonal_cells() {
+        let mut config = RuleConfig::default();
+        config.mint.heal_rate = 1.0;
+        // Pearl never rewrites itself, so heals after mint's turn stick
+        config.mint.heals = CellType::Pearl;
+        config.neighborhood = Neighborhood::VonNeumann;
+
+        let mut grid = Grid::new(9, 9);
+        for (dx, dy) in Neighborhood::Moore.offsets() {
+            grid.set_cell((4 + dx) as u32, (4 + dy) as u32, CellType::Pearl);
+        }
+        grid.set_cell(4, 4, CellType::Mint);
+        apply_rules(&mut grid, &config);
+
+        let healed: Vec<_> = (0..9)
+            .flat_map(|y| (0..9).map(move |x| (x, y)))
+            .filter(|&(x, y)| grid.get_cell(x, y).unwrap().cell_type == CellType::Orange)
+            .collect();
+        assert_eq!(healed, vec![(4, 3), (3, 4), (5, 4), (4, 5)]);
+    }
+
+    #[test]
+    fn test_gold_retargeted_scavenging_and_starvation() {
+        let mut config = RuleConfig::default();
+        config.gold.scavenges = vec![CellType::Blue];
+        config.gold.scavenge_rate = 1.0;
+
+        // Fed gold clears the blue to its left and survives
+        let mut grid = Grid::new(16, 16);
+        grid.set_cell(4, 4, CellType::Blue);
+        grid.set_cell(5, 4, CellType::Gold);
+        apply_rules(&mut grid, &config);
+        assert_eq!(grid.get_cell(4, 4).unwrap().cell_type, CellType::Black);
+        assert_eq!(grid.get_cell(5, 4).unwrap().cell_type, CellType::Gold);
+
+        // Gray is no longer food, so gold next to it starves
+        let mut grid = Grid::new(16, 16);
+        grid.set_cell(4, 4, CellType::Gray);
+        grid.set_cell(5, 4, CellType::Gold);
+        apply_rules(&mut grid, &config);
+        assert_eq!(grid.get_cell(5, 4).unwrap().cell_type, CellType::Black);
+
+        // ...unless starvation is switched off
+        config.gold.starvation_chance = 0.0;
+        let mut grid = Grid::new(16, 16);
+        grid.set_cell(5, 4, CellType::Gold);
+        apply_rules(&mut grid, &config);
+        assert_eq!(grid.get_cell(5, 4).unwrap().cell_type, CellType::Gold);
+    }
+
+    #[test]
+    fn test_peach_insulation_radius_blocks_purple() {
+        // Columns: peach at x=4, orange at x=6, purple at x=7. Orange is
+        // two cells from the peach wall, so only a radius-2 barrier covers it.
+        let infected_after_tick = |config: &RuleConfig| {
+            let mut grid = Grid::new(32, 32);
+            for y in 1..31 {
+                grid.set_cell(4, y, CellType::Peach);
+                grid.set_cell(6, y, CellType::Orange);
+                grid.set_cell(7, y, CellType::Purple);
+            }
+            apply_rules_with_rng(&mut grid, config, &mut ChaCha8Rng::seed_from_u64(2));
+            (1..31)
+                .filter(|&y| grid.get_cell(6, y).unwrap().cell_type == CellType::Purple)
+                .count()
+        };
+
+        let mut wide = RuleConfig::default();
+        wide.peach.insulation_radius = 2;
+
+        assert!(infected_after_tick(&RuleConfig::default()) > 0);
+        assert_eq!(infected_after_tick(&wide), 0);
+    }
+
+    #[test]
+    fn test_delta_time_step_matches_two_unit_steps() {
+        // 441 lone tints with spreading disabled, so the only effect is the
+        // per-tick isolation death roll
+        let lone_survivors = |delta_time: Option<f64>, ticks: usize| {
+            let mut config = RuleConfig::default();
+            config.behavioral.tint_spread_rate = 0.0;
+            config.behavioral.tint_flock_spread_rate = 0.0;
+            config.time.delta_time = delta_time;
+
+            let mut grid = Grid::new(128, 128);
+            let positions: Vec<(u32, u32)> = (2..126)
+                .step_by(6)
+                .flat_map(|y| (2..126).step_by(6).map(move |x| (x, y)))
+                .collect();
+            for &(x, y) in &positions {
+                grid.set_cell(x, y, CellType::Tint);
+            }
+            let mut rng = ChaCha8Rng::seed_from_u64(6);
+            for _ in 0..ticks {
+                apply_rules_with_rng(&mut grid, &config, &mut rng);
+            }
+            count_type(&grid, CellType::Tint)
+        };
+
+        let one_step = lone_survivors(Some(1.0), 1);
+        let two_steps = lone_survivors(Some(1.0), 2);
+        let double_step = lone_survivors(Some(2.0), 1);
+        assert!(
+            two_steps.abs_diff(double_step) < 60,
+            "two dt=1 ticks {} vs one dt=2 tick {}",
+            two_steps,
+            double_step
+        );
+        assert!(double_step + 50 < one_step, "dt=2 {} vs dt=1 {}", double_step, one_step);
+    }
+
+    #[test]
+    fn test_pink_weakens_host_without_converting() {
+        // 100 green-supported oranges, each with a pink on its right
+        let run = |config: &RuleConfig, ticks: usize| {
+            let mut grid = Grid::new(64, 64);
+            let positions: Vec<(u32, u32)> = (4..60)
+                .step_by(6)
+                .flat_map(|y| (4..58).step_by(6).map(move |x| (x, y)))
+                .collect();
+            for &(x, y) in &positions {
+                for gy in y - 1..=y + 1 {
+                    grid.set_cell(x - 2, gy, CellType::Green);
+                }
+                grid.set_cell(x, y, CellType::Orange);
+                grid.set_cell(x + 1, y, CellType::Pink);
+            }
+            let mut rng = ChaCha8Rng::seed_from_u64(8);
+            for _ in 0..ticks {
+                apply_rules_with_rng(&mut grid, config, &mut rng);
+            }
+            let surviving: Vec<Cell> = positions
+                .iter()
+                .map(|&(x, y)| grid.get_cell(x, y).unwrap())
+                .filter(|c| c.cell_type == CellType::Orange)
+                .collect();
+            (surviving, count_type(&grid, CellType::Pink), positions.len())
+        };
+
+        let mut weakening = RuleConfig::default();
+        weakening.pink.infection_rate = 1.0;
+        weakening.pink.converts_host = false;
+        weakening.pink.harm = 0.3;
+
+        let (_, converted, pairs) = run(&RuleConfig::default(), 1);
+        assert!(converted > pairs, "default pink should convert some hosts");
+
+        let (weakened, pink, pairs) = run(&weakening, 1);
+        assert_eq!(weakened.len(), pairs);
+        assert_eq!(pink, pairs);
+        assert!(weakened.iter().all(|c| c.genes.vitality < 0.5));
+
+        let (survivors, pink, pairs) = run(&weakening, 2);
+        assert!(survivors.is_empty());
+        assert_eq!(pink, pairs);
+    }
+
+    #[test]
+    fn test_smoke_cloud_dissipates_without_source() {
+        let mut config = RuleConfig::default();
+        config.smoke.spread_rate = 0.0;
+        config.smoke.dissipation_rate = 0.3;
+
+        let mut grid = Grid::new(32, 32);
+        for y in 8..24 {
+            for x in 8..24 {
+                grid.set_cell(x, y, CellType::Smoke);
+            }
+        }
+
+        let mut rng = ChaCha8Rng::seed_from_u64(10);
+        let mut sizes = vec![count_type(&grid, CellType::Smoke)];
+        for _ in 0..3 {
+            for _ in 0..2 {
+                apply_rules_with_rng(&mut grid, &config, &mut rng);
+            }
+            sizes.push(count_type(&grid, CellType::Smoke));
+        }
+
+        assert!(sizes.windows(2).all(|w| w[1] < w[0]), "cloud sizes {:?}", sizes);
+        assert!(sizes[3] < sizes[0] / 4, "cloud sizes {:?}", sizes);
+    }
+
+    #[test]
+    fn test_violet_immune_set_spares_neighbors() {
+        // 25 violets, each ringed by 8 greens, one tick at full destruction
+        let ring_survivors = |config: &RuleConfig| {
+            let mut grid = Grid::new(64, 64);
+            let centers: Vec<(u32, u32)> = (5..60)
+                .step_by(12)
+                .flat_map(|y| (5..60).step_by(12).map(move |x| (x, y)))
+                .collect();
+            for &(x, y) in &centers {
+                for ny in y - 1..=y + 1 {
+                    for nx in x - 1..=x + 1 {
+                        grid.set_cell(nx, ny, CellType::Green);
+                    }
+                }
+                grid.set_cell(x, y, CellType::Violet);
+            }
+            apply_rules(&mut grid, config);
+            centers
+                .iter()
+                .flat_map(|&(x, y)| (y - 1..=y + 1).flat_map(move |ny| (x - 1..=x + 1).map(move |nx| (nx, ny))))
+                .filter(|&(nx, ny)| grid.get_cell(nx, ny).unwrap().cell_type == CellType::Green)
+                .count()
+        };
+
+        let mut voracious = RuleConfig::default();
+        voracious.violet.destruction_rate = 1.0;
+        let mut shielded = voracious.clone();
+        shielded.violet.immune.push(CellType::Green);
+
+        assert_eq!(ring_survivors(&voracious), 0);
+        assert_eq!(ring_survivors(&shielded), 25 * 8);
+    }
+
+    #[test]
+    fn test_indigo_persists_across_ticks() {
+        // Indigo is inert by default, so it must carry itself forward
+        let config = RuleConfig::default();
+        let mut grid = Grid::new(40, 40);
+        let mut deposit = Cell::new(CellType::Indigo);
+        deposit.age = 9;
+        grid.put_cell(33, 7, deposit.clone());
+
+        for _ in 0..50 {
+            apply_rules(&mut grid, &config);
+            let cell = grid.get_cell(33, 7).unwrap();
+            assert_eq!((cell.cell_type, cell.age, cell.rng_seed), (CellType::Indigo, 9, deposit.rng_seed));
+        }
+        assert_eq!(grid.population(CellType::Indigo), 1);
+    }
+
+    #[test]
+    fn test_ticks_skip_all_black_chunks() {
+        let config = RuleConfig::default();
+        let mut grid = Grid::new(256, 256);
+        grid.set_cell(140, 140, CellType::Green);
+        let mut rng = ChaCha8Rng::seed_from_u64(12);
+
+        // Only the green's own chunk is visited, not the other 63
+        assert_eq!(run_chunk_layers(&mut grid, None, &mut rng, |_, _| &config), 32 * 32);
+        for _ in 0..5 {
+            let visited = run_chunk_layers(&mut grid, None, &mut rng, |_, _| &config);
+            assert!(visited <= 9 * 32 * 32, "visited {} cells", visited);
+        }
+        assert!(grid.is_chunk_active(4, 4));
+        assert!(!grid.is_chunk_active(0, 7));
+    }
+
+    #[test]
+    fn test_shade_target_choice_follows_seed() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let mut config = RuleConfig::default();
+        config.behavioral.shade_spread_rate = 1.0;
+        config.reproduction.chance = 0.0;
+
+        // Pearl walls in the shade except for the black cells left and right
+        let spread_to = |seed: u64| {
+            let mut grid = Grid::new(12, 12);
+            for y in 4..=6 {
+                for x in 4..=6 {
+                    grid.set_cell(x, y, CellType::Pearl);
+                }
+            }
+            grid.set_cell(5, 5, CellType::Shade);
+            grid.set_cell(4, 5, CellType::Black);
+            grid.set_cell(6, 5, CellType::Black);
+            apply_rules_with_rng(&mut grid, &config, &mut StdRng::seed_from_u64(seed));
+
+            let targets: Vec<u32> = [4, 6]
+                .into_iter()
+                .filter(|&x| grid.get_cell(x, 5).unwrap().cell_type == CellType::Shade)
+                .collect();
+            assert_eq!(targets.len(), 1, "seed {} spread into {:?}", seed, targets);
+            targets[0]
+        };
+
+        let choices: Vec<u32> = (0..16).map(spread_to).collect();
+        assert_eq!(choices, (0..16).map(spread_to).collect::<Vec<_>>());
+        // Both targets get picked across seeds
+        assert!(choices.contains(&4) && choices.contains(&6));
+    }
+
+    #[test]
+    fn test_green_spread_follows_forced_draws() {
+        use crate::testutil::SequenceRng;
+
+        let green_after = |draws: &[f64]| {
+            let mut grid = Grid::new(5, 5);
+            grid.set_cell(2, 2, CellType::Green);
+            let config = RuleConfig::default();
+            let mut rng = TickRng::new(SequenceRng::new(draws), None);
+            for_each_chunk_layered(&mut grid, |grid, _, _| apply_green_rules(grid, 2, 2, &config, &mut rng));
+            grid
+        };
+
+        // A 0.0 draw passes every spread roll: exactly the Moore ring turns Green
+        let grid = green_after(&[0.0]);
+        for y in 0..5u32 {
+            for x in 0..5u32 {
+                let expected = if x.abs_diff(2) <= 1 && y.abs_diff(2) <= 1 { CellType::Green } else { CellType::Black };
+                assert_eq!(grid.get_cell(x, y).unwrap().cell_type, expected, "({}, {})", x, y);
+            }
+        }
+
+        // A 0.99 draw fails them all
+        assert_eq!(count_type(&green_after(&[0.99]), CellType::Green), 1);
+    }
+
+    #[test]
+    fn test_slate_decomposes_on_forced_draws() {
+        use crate::testutil::SequenceRng;
+
+        // Slate with three gray neighbors, visited (1, 2), (3, 2), (2, 3)
+        let after = |config: &RuleConfig, draws: &[f64]| {
+            let mut grid = Grid::new(5, 5);
+            grid.set_cell(2, 2, CellType::Slate);
+            for (x, y) in [(1, 2), (3, 2), (2, 3)] {
+                grid.set_cell(x, y, CellType::Gray);
+            }
+            let mut rng = TickRng::new(SequenceRng::new(draws), None);
+            for_each_chunk_layered(&mut grid, |grid, _, _| apply_slate_rules(grid, 2, 2, config, &mut rng));
+            [(1, 2), (3, 2), (2, 3)].map(|(x, y)| grid.get_cell(x, y).unwrap().cell_type)
+        };
+
+        let mut config = RuleConfig::default();
+        config.slate.decompose_rate = 0.5;
+        config.slate.decomposes_into = CellType::Olive;
+
+        // Roll, new cell's seed, roll, roll, seed: the middle gray survives
+        assert_eq!(
+            after(&config, &[0.0, 0.0, 0.9, 0.0, 0.0]),
+            [CellType::Olive, CellType::Gray, CellType::Olive]
+        );
+        // At the default rate of 0 even passing draws do nothing
+        assert_eq!(after(&RuleConfig::default(), &[0.0]), [CellType::Gray; 3]);
+    }
+
+    #[test]
+    fn test_corner_visits_only_in_bounds_neighbors() {
+        let visited = |wrap: bool| {
+            let mut grid = Grid::with_topology(6, 6, wrap);
+            let mut seen = Vec::new();
+            for_each_neighbor(&mut grid, 0, 0, Neighborhood::Moore, |_, nx, ny| seen.push((nx, ny)));
+            seen
+        };
+
+        assert_eq!(visited(false), vec![(1, 0), (0, 1), (1, 1)]);
+        assert_eq!(
+            visited(true),
+            vec![(5, 5), (0, 5), (1, 5), (5, 0), (1, 0), (5, 1), (0, 1), (1, 1)]
+        );
+        assert_eq!(Grid::new(6, 6).neighbor_coord(0, 0, -1, 0), None);
+    }
+
+    #[test]
+    fn test_mint_heals_across_the_wrap_seam() {
+        let mut config = RuleConfig::default();
+        config.mint.heal_rate = 1.0;
+        config.mint.heals = CellType::Pearl;
+
+        let healed = |wrap: bool| {
+            let mut grid = Grid::with_topology(6, 6, wrap);
+            grid.set_cell(0, 0, CellType::Mint);
+            for (x, y) in [(5, 5), (5, 0), (0, 5)] {
+                grid.set_cell(x, y, CellType::Pearl);
+            }
+            apply_rules(&mut grid, &config);
+            count_type(&grid, CellType::Orange)
+        };
+
+        assert_eq!(healed(true), 3);
+        assert_eq!(healed(false), 0);
+    }
+
+    #[test]
+    fn test_spread_tendency_drives_green_growth() {
+        // 100 isolated greens sharing one spread tendency, three ticks each
+        let green_after = |spread_tendency: f64| {
+            let genes = Genes { spread_tendency, ..Genes::default() };
+            let mut grid = seeded_lattice(Cell::with_genes(CellType::Green, genes));
+            let mut rng = ChaCha8Rng::seed_from_u64(5);
+            for _ in 0..3 {
+                apply_rules_with_rng(&mut grid, &RuleConfig::default(), &mut rng);
+            }
+            count_type(&grid, CellType::Green)
+        };
+
+        // Zero-tendency plants never seed anything; keen ones outgrow the
+        // default even though their offspring carry default genes
+        assert_eq!(green_after(0.0), 100);
+        let (keen, default) = (green_after(1.0), green_after(0.5));
+        assert!(keen > default + 100, "keen {} vs default {}", keen, default);
+    }
+
+    #[test]
+    fn test_lone_pearl_is_unchanged_after_100_ticks() {
+        let mut grid = Grid::new(40, 40);
+        grid.set_cell(20, 20, CellType::Pearl);
+        let pearl = grid.get_cell(20, 20).unwrap();
+
+        for _ in 0..100 {
+            apply_rules(&mut grid, &RuleConfig::default());
+        }
+
+        assert_eq!(grid.get_cell(20, 20).unwrap(), pearl);
+        assert_eq!(count_type(&grid, CellType::Pearl), 1);
+        assert_eq!(count_type(&grid, CellType::Black), 40 * 40 - 1);
     }
 }
@@ -0,0 +1,197 @@
+//! Species Registry
+//!
+//! Data-driven description of every cell type: id, display name, color,
+//! category and which rule drives it, with O(1) lookups by id or name.
+//!
+//! The 37 built-in species come from `SpeciesRegistry::builtin()`, built
+//! once from `CellType`. Stats population names, the default render and
+//! GIF palettes, and Magenta's random conversions all read from it. Extra
+//! species can be registered on a cloned registry under new ids; they show
+//! up in `population_counts` and `render_rgb` on raw type ids
+//! (`Grid::to_bytes`, wire frames, NCA tensors). Grid cells are still
+//! `CellType` and rules still dispatch on it, so a registered species has
+//! no rule of its own until it gets an enum variant.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::OnceLock;
+
+use crate::cell::CellType;
+
+/// What drives a species each tick
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpeciesRule {
+    /// The hand-written rule for this `CellType`
+    Builtin(CellType),
+    /// No behavior; the cell stays as it is
+    Inert,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Species {
+    pub id: u8,
+    pub name: String,
+    pub color: (u8, u8, u8),
+    /// Ecological group, e.g. "Predator" or "Decomposer"
+    pub category: String,
+    pub rule: SpeciesRule,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpeciesError {
+    DuplicateId(u8),
+    DuplicateName(String),
+}
+
+impl fmt::Display for SpeciesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpeciesError::DuplicateId(id) => write!(f, "species id {} is already registered", id),
+            SpeciesError::DuplicateName(name) => write!(f, "species name {:?} is already registered", name),
+        }
+    }
+}
+
+impl std::error::Error for SpeciesError {}
+
+/// Species indexed by id for O(1) lookups
+#[derive(Debug, Clone, Default)]
+pub struct SpeciesRegistry {
+    by_id: Vec<Option<Species>>,
+    by_name: HashMap<String, u8>,
+}
+
+impl SpeciesRegistry {
+    /// An empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The shared registry of the 37 built-in species
+    pub fn builtin() -> &'static SpeciesRegistry {
+        static BUILTIN_REGISTRY: OnceLock<SpeciesRegistry> = OnceLock::new();
+        BUILTIN_REGISTRY.get_or_init(|| {
+            let mut registry = SpeciesRegistry::new();
+            for cell_type in CellType::all() {
+                registry
+                    .register(Species {
+                        id: cell_type.to_u8(),
+                        name: cell_type.as_name().to_string(),
+                        color: cell_type.get_color(),
+                        category: cell_type.category().as_name().to_string(),
+                        rule: SpeciesRule::Builtin(cell_type),
+                    })
+                    .expect("built-in species table has duplicates");
+            }
+            registry
+        })
+    }
+
+    /// Add a species; its id and name must both be unused
+    pub fn register(&mut self, species: Species) -> Result<(), SpeciesError> {
+        let id = species.id as usize;
+        if self.by_id.get(id).is_some_and(Option::is_some) {
+            return Err(SpeciesError::DuplicateId(species.id));
+        }
+        if self.by_name.contains_key(&species.name) {
+            return Err(SpeciesError::DuplicateName(species.name));
+        }
+        if self.by_id.len() <= id {
+            self.by_id.resize(id + 1, None);
+        }
+        self.by_name.insert(species.name.clone(), species.id);
+        self.by_id[id] = Some(species);
+        Ok(())
+    }
+
+    pub fn get(&self, id: u8) -> Option<&Species> {
+        self.by_id.get(id as usize)?.as_ref()
+    }
+
+    pub fn by_name(&self, name: &str) -> Option<&Species> {
+        self.get(*self.by_name.get(name)?)
+    }
+
+    pub fn name(&self, id: u8) -> Option<&str> {
+        self.get(id).map(|s| s.name.as_str())
+    }
+
+    /// Registered species in id order
+    pub fn iter(&self) -> impl Iterator<Item = &Species> {
+        self.by_id.iter().flatten()
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_name.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_name.is_empty()
+    }
+
+    /// Count raw type ids by species name; unregistered ids are skipped
+    pub fn population_counts(&self, ids: &[u8]) -> HashMap<String, u32> {
+        let mut tally = vec![0u32; self.by_id.len()];
+        for &id in ids {
+            if let Some(count) = tally.get_mut(id as usize) {
+                *count += 1;
+            }
+        }
+        self.iter()
+            .filter(|s| tally[s.id as usize] > 0)
+            .map(|s| (s.name.clone(), tally[s.id as usize]))
+            .collect()
+    }
+
+    /// RGB bytes for raw type ids; unregistered ids render black
+    pub fn render_rgb(&self, ids: &[u8]) -> Vec<u8> {
+        let mut pixels = Vec::with_capacity(ids.len() * 3);
+        for &id in ids {
+            let (r, g, b) = self.get(id).map_or((0, 0, 0), |s| s.color);
+            pixels.extend_from_slice(&[r, g, b]);
+        }
+        pixels
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+
+    #[test]
+    fn test_registered_species_counts_and_renders() {
+        let builtin = SpeciesRegistry::builtin();
+        assert_eq!(builtin.len(), CellType::COUNT);
+        assert_eq!(builtin.name(CellType::Crimson.to_u8()), Some("Crimson"));
+        assert_eq!(builtin.by_name("Slate").unwrap().category, "Decomposer");
+
+        let mut registry = builtin.clone();
+        let ember = Species {
+            id: CellType::COUNT as u8,
+            name: "Ember".to_string(),
+            color: (255, 80, 10),
+            category: "Exotic".to_string(),
+            rule: SpeciesRule::Inert,
+        };
+        registry.register(ember.clone()).unwrap();
+        assert_eq!(registry.register(ember), Err(SpeciesError::DuplicateId(37)));
+
+        // A 2x2 grid's ids with two cells replaced by the new species
+        let mut grid = Grid::new(2, 2);
+        grid.set_cell(0, 0, CellType::Green);
+        let mut ids = grid.to_bytes();
+        ids[2] = 37;
+        ids[3] = 37;
+
+        let counts = registry.population_counts(&ids);
+        assert_eq!(counts.get("Ember"), Some(&2));
+        assert_eq!(counts.get("Green"), Some(&1));
+        assert_eq!(counts.get("Black"), Some(&1));
+        assert!(!builtin.population_counts(&ids).contains_key("Ember"));
+
+        let pixels = registry.render_rgb(&ids);
+        assert_eq!(&pixels[6..9], &[255, 80, 10]);
+        assert_eq!(&pixels[0..3], &[0, 204, 0]);
+    }
+}
@@ -1,6 +1,12 @@
-use crate::cell::CellType;
-use crate::grid::Grid;
-use std::collections::HashMap;
+use crate::cell::{Cell, CellCategory, CellType};
+use crate::grid::{Grid, TileView};
+use crate::species::SpeciesRegistry;
+use cellular_core::ecology;
+use rayon::prelude::*;
+use std::collections::{HashMap, VecDeque};
+
+// Lives next to `BatchRunner` in `headless`; re-exported beside `EnsembleSummary`
+pub use crate::headless::ensemble_run;
 
 #[derive(Debug, Clone)]
 pub struct EcosystemStats {
@@ -8,24 +14,104 @@ pub struct EcosystemStats {
     pub health_score: f64,
     pub green_coverage: f64,
     pub orange_population: u32,
+    /// Crimson, Maroon, Coral, Brown and Tan: everything that eats other cells
     pub predator_count: u32,
+    /// Cells in `CellCategory::Predator` (Crimson, Maroon, Coral, Pink, Magenta)
+    pub predator_category_count: u32,
     pub disease_pressure: f64,
     pub diversity_index: f64,
     pub stability: f64,
+    /// Deepest lineage among non-empty cells (0 when all are originals)
+    pub max_generation: u8,
+    /// Mean generation of non-empty cells (0 when there are none)
+    pub avg_generation: f64,
+    /// Sliding-window stability from a `StabilityTracker`; None from `calculate_stats`
+    pub temporal_stability: Option<f64>,
 }
 
 pub fn calculate_stats(grid: &Grid) -> EcosystemStats {
-    let mut populations: HashMap<String, u32> = HashMap::new();
-    let total_cells = (grid.width * grid.height) as f64;
-    
-    for y in 0..grid.height {
-        for x in 0..grid.width {
-            if let Some(cell) = grid.get_cell(x, y) {
-                let name = get_cell_name(cell.cell_type);
-                *populations.entry(name).or_insert(0) += 1;
+    stats_for_tile(&grid.tile(0..grid.width, 0..grid.height), grid.empty_type())
+}
+
+/// Stats for each `region_size` square of the grid
+///
+/// Regions are returned row-major, in the same index order that
+/// `ml_layer::get_region_params` uses, so entry `i` describes the cells
+/// governed by `region_params[i]`. Edge regions may be smaller than
+/// `region_size`; their fractions are relative to their own area.
+pub fn calculate_region_stats(grid: &Grid, region_size: u32) -> Vec<EcosystemStats> {
+    grid.tiles(region_size).map(|tile| stats_for_tile(&tile, grid.empty_type())).collect()
+}
+
+/// `calculate_stats` with the counting spread over rayon's thread pool
+///
+/// The grid is split into tiles whose tallies are summed, so the result is
+/// identical to the serial version whatever order the tiles finish in.
+pub fn calculate_stats_parallel(grid: &Grid) -> EcosystemStats {
+    let tiles: Vec<TileView> = grid.tiles(PARALLEL_TILE_SIZE).collect();
+    let tally = tiles
+        .par_iter()
+        .map(|tile| Tally::of(tile.cells(), grid.empty_type()))
+        .reduce(Tally::default, Tally::merge);
+    stats_from_tally(&tally, grid.width as usize * grid.height as usize, grid.empty_type())
+}
+
+/// Tile edge for `calculate_stats_parallel`, in cells
+const PARALLEL_TILE_SIZE: u32 = 128;
+
+/// Raw counts behind `EcosystemStats`; merging two tallies is associative
+#[derive(Debug, Clone, Copy)]
+struct Tally {
+    counts: [u32; CellType::COUNT],
+    max_generation: u8,
+    generation_sum: u64,
+}
+
+impl Default for Tally {
+    fn default() -> Self {
+        Tally { counts: [0; CellType::COUNT], max_generation: 0, generation_sum: 0 }
+    }
+}
+
+impl Tally {
+    fn of<'a>(cells: impl Iterator<Item = &'a Cell>, empty: CellType) -> Self {
+        let mut tally = Tally::default();
+        for cell in cells {
+            tally.counts[cell.cell_type.to_u8() as usize] += 1;
+            if cell.cell_type != empty {
+                tally.max_generation = tally.max_generation.max(cell.genes.generation);
+                tally.generation_sum += cell.genes.generation as u64;
             }
         }
+        tally
     }
+
+    fn merge(mut self, other: Tally) -> Tally {
+        for (count, other) in self.counts.iter_mut().zip(other.counts) {
+            *count += other;
+        }
+        self.max_generation = self.max_generation.max(other.max_generation);
+        self.generation_sum += other.generation_sum;
+        self
+    }
+}
+
+/// Count populations in one tile and derive stats relative to its area
+fn stats_for_tile(tile: &TileView, empty: CellType) -> EcosystemStats {
+    stats_from_tally(&Tally::of(tile.cells(), empty), tile.len(), empty)
+}
+
+/// Derive stats from the tally of an area of `area` cells
+fn stats_from_tally(tally: &Tally, area: usize, empty: CellType) -> EcosystemStats {
+    let Tally { counts, max_generation, generation_sum } = *tally;
+    let total_cells = area as f64;
+    let living = total_cells as u32 - counts[empty.to_u8() as usize];
+    let avg_generation = if living > 0 { generation_sum as f64 / living as f64 } else { 0.0 };
+    // Every type gets an entry, extinct ones included
+    let populations: HashMap<String, u32> = SpeciesRegistry::builtin()
+        .iter()
+        .map(|species| (species.name.clone(), counts[species.id as usize]))
+        .collect();
     
     // Calculate health metrics
     let green = *populations.get("Green").unwrap_or(&0) as f64;
@@ -39,13 +125,12 @@ pub fn calculate_stats(grid: &Grid) -> EcosystemStats {
     // Green coverage percentage
     let green_coverage = (green / total_cells) * 100.0;
     
-    // Predator count (Crimson, Maroon, Coral, Brown, Tan)
-    let crimson = *populations.get("Crimson").unwrap_or(&0);
-    let maroon = *populations.get("Maroon").unwrap_or(&0);
-    let coral = *populations.get("Coral").unwrap_or(&0);
-    let brown = *populations.get("Brown").unwrap_or(&0);
-    let tan = *populations.get("Tan").unwrap_or(&0);
-    let predator_count = crimson + maroon + coral + brown + tan;
+    // Predator count (Crimson, Maroon, Coral + Brown, Tan)
+    let predator_count = [CellType::Crimson, CellType::Maroon, CellType::Coral, CellType::Brown, CellType::Tan]
+        .iter()
+        .map(|t| counts[t.to_u8() as usize])
+        .sum();
+    let predator_category_count = CellCategory::Predator.members().map(|t| counts[t.to_u8() as usize]).sum();
     
     // Disease pressure (Purple / (Purple + Red + Orange))
     let disease_pressure = if purple + red + orange > 0.0 {
@@ -63,44 +148,229 @@ pub fn calculate_stats(grid: &Grid) -> EcosystemStats {
     };
     
     // Diversity: Shannon index across top 10 populations
-    let mut diversity = 0.0;
-    let mut pop_list: Vec<_> = populations.iter().collect();
-    pop_list.sort_by_key(|(_, count)| std::cmp::Reverse(**count));
-    
-    for (_, count) in pop_list.iter().take(10) {
-        let p = **count as f64 / total_cells;
-        if p > 0.0 {
-            diversity -= p * p.log2();
-        }
-    }
+    let mut pop_list: Vec<u32> = populations.values().copied().collect();
+    pop_list.sort_by_key(|&count| std::cmp::Reverse(count));
+    let diversity = ecology::shannon_bits(pop_list.into_iter().take(10), total_cells);
     
     // Normalize diversity (max is log2(37))
-    diversity = (diversity / 5.0).min(1.0);
+    let diversity = (diversity / 5.0).min(1.0);
     
     // Overall health score
-    // Green coverage (0-50 optimal at 30-50%) + Orange (0-30) + Stability (0-20)
-    let green_score = if green_coverage >= 20.0 && green_coverage <= 60.0 {
-        50.0
-    } else if green_coverage < 20.0 {
-        green_coverage * 2.5
-    } else {
-        50.0 - ((green_coverage - 60.0) * 0.5)
-    };
-    
-    let orange_score = (orange / total_cells) * 30.0 * 100.0;
-    let stability_score = stability * 20.0;
-    let health_score = (green_score + orange_score.min(30.0) + stability_score) / 100.0;
+    // Green coverage (0-50 optimal at 20-60%) + Orange (0-30) + Stability (0-20)
+    let health_score = ecology::health_score(green_coverage, orange / total_cells, stability);
     
     EcosystemStats {
         populations,
-        health_score: health_score.min(1.0).max(0.0),
+        health_score,
         green_coverage,
         orange_population: orange as u32,
         predator_count,
+        predator_category_count,
         disease_pressure,
         diversity_index: diversity,
         stability,
+        max_generation,
+        avg_generation,
+        temporal_stability: None,
+    }
+}
+
+/// `calculate_stats`, also feeding the grid to `tracker` and filling in `temporal_stability`
+pub fn calculate_stats_tracked(grid: &Grid, tracker: &mut StabilityTracker) -> EcosystemStats {
+    let mut stats = calculate_stats(grid);
+    stats.temporal_stability = Some(tracker.record(&stats.populations));
+    stats
+}
+
+/// Stability of the living (non-Black) population over recent samples
+///
+/// Unlike the instantaneous `EcosystemStats::stability`, this looks at how
+/// much the number of living cells churns from one sample to the next. The
+/// score is `1 - mean(|change|) / mean(living)` over the window, clamped to
+/// 0-1: a population that holds steady scores 1.0 and one that swings by
+/// its own size every sample scores 0.
+#[derive(Debug, Clone)]
+pub struct StabilityTracker {
+    window: usize,
+    living: VecDeque<u32>,
+}
+
+impl StabilityTracker {
+    /// Keep the last `window` samples (at least 2)
+    pub fn new(window: usize) -> Self {
+        let window = window.max(2);
+        StabilityTracker { window, living: VecDeque::with_capacity(window) }
+    }
+
+    /// Add a population snapshot and return the updated score
+    pub fn record(&mut self, populations: &HashMap<String, u32>) -> f64 {
+        let living = populations
+            .iter()
+            .filter(|(name, _)| name.as_str() != CellType::Black.as_name())
+            .map(|(_, &count)| count)
+            .sum();
+        if self.living.len() == self.window {
+            self.living.pop_front();
+        }
+        self.living.push_back(living);
+        self.score()
+    }
+
+    /// Score over the samples held; 1.0 with fewer than two
+    pub fn score(&self) -> f64 {
+        if self.living.len() < 2 {
+            return 1.0;
+        }
+        let churn: u64 = self
+            .living
+            .iter()
+            .zip(self.living.iter().skip(1))
+            .map(|(&a, &b)| a.abs_diff(b) as u64)
+            .sum();
+        if churn == 0 {
+            return 1.0;
+        }
+        let mean_churn = churn as f64 / (self.living.len() - 1) as f64;
+        let mean_living = self.living.iter().map(|&n| n as f64).sum::<f64>() / self.living.len() as f64;
+        (1.0 - mean_churn / mean_living).clamp(0.0, 1.0)
+    }
+
+    pub fn clear(&mut self) {
+        self.living.clear();
+    }
+}
+
+/// Mean (x, y) coordinate of every cell of `cell_type`, or None if there are none
+pub fn center_of_mass(grid: &Grid, cell_type: CellType) -> Option<(f64, f64)> {
+    let mut sum_x = 0.0;
+    let mut sum_y = 0.0;
+    let mut count = 0.0;
+
+    for y in 0..grid.height {
+        for x in 0..grid.width {
+            if grid.get_cell(x, y).map(|c| c.cell_type) == Some(cell_type) {
+                sum_x += x as f64;
+                sum_y += y as f64;
+                count += 1.0;
+            }
+        }
+    }
+
+    (count > 0.0).then(|| (sum_x / count, sum_y / count))
+}
+
+/// Average movement of a type's center of mass, in cells per tick
+///
+/// `ticks` is the number of ticks between the two snapshots (treated as at
+/// least 1). None if the type is absent from either snapshot.
+pub fn center_of_mass_velocity(
+    before: &Grid,
+    after: &Grid,
+    cell_type: CellType,
+    ticks: u64,
+) -> Option<(f64, f64)> {
+    let (x0, y0) = center_of_mass(before, cell_type)?;
+    let (x1, y1) = center_of_mass(after, cell_type)?;
+    let ticks = ticks.max(1) as f64;
+    Some(((x1 - x0) / ticks, (y1 - y0) / ticks))
+}
+
+/// Per-type population counts over a run, one sample per `record`
+///
+/// With a capacity set, only the most recent `capacity` samples are kept.
+#[derive(Debug, Clone)]
+pub struct PopulationHistory {
+    series: Vec<VecDeque<u32>>,
+    capacity: Option<usize>,
+}
+
+impl Default for PopulationHistory {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl PopulationHistory {
+    pub fn new(capacity: Option<usize>) -> Self {
+        PopulationHistory {
+            series: vec![VecDeque::new(); CellType::COUNT],
+            capacity,
+        }
     }
+
+    /// Append the grid's current count of every type
+    pub fn record(&mut self, grid: &Grid) {
+        if self.capacity == Some(0) {
+            return;
+        }
+        let full = self.capacity.is_some_and(|cap| self.len() >= cap);
+        for (series, count) in self.series.iter_mut().zip(grid.type_counts()) {
+            if full {
+                series.pop_front();
+            }
+            series.push_back(count);
+        }
+    }
+
+    /// One type's counts, oldest sample first
+    ///
+    /// Takes `&mut self` because a bounded history is a ring buffer: the
+    /// samples are rotated into one slice on demand, so `record` stays O(1)
+    /// per type and the copy is only paid when a series is read.
+    pub fn series(&mut self, cell_type: CellType) -> &[u32] {
+        self.series[cell_type.to_u8() as usize].make_contiguous()
+    }
+
+    /// Number of samples held
+    pub fn len(&self) -> usize {
+        self.series[0].len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.series.iter_mut().for_each(VecDeque::clear);
+    }
+}
+
+/// Mean and sample standard deviation of one metric across runs
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MetricSummary {
+    pub mean: f64,
+    /// Sample (n - 1) standard deviation; 0 with fewer than two values
+    pub std_dev: f64,
+}
+
+impl MetricSummary {
+    pub fn from_values(values: &[f64]) -> Self {
+        if values.is_empty() {
+            return Self::default();
+        }
+        let n = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / n;
+        let std_dev = if values.len() < 2 {
+            0.0
+        } else {
+            (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt()
+        };
+        MetricSummary { mean, std_dev }
+    }
+}
+
+/// Final-tick stats of several seeded runs of one preset, summarized per metric
+#[derive(Debug, Clone, Default)]
+pub struct EnsembleSummary {
+    /// Number of runs that went into each metric
+    pub samples: usize,
+    pub health_score: MetricSummary,
+    pub green_coverage: MetricSummary,
+    pub diversity_index: MetricSummary,
+    pub stability: MetricSummary,
+    pub disease_pressure: MetricSummary,
+    pub orange_population: MetricSummary,
+    pub predator_count: MetricSummary,
 }
 
 pub fn get_ecosystem_status(stats: &EcosystemStats) -> String {
@@ -117,49 +387,6 @@ pub fn get_ecosystem_status(stats: &EcosystemStats) -> String {
     }
 }
 
-fn get_cell_name(cell_type: CellType) -> String {
-    match cell_type {
-        CellType::Black => "Black",
-        CellType::Green => "Green",
-        CellType::Orange => "Orange",
-        CellType::Gray => "Gray",
-        CellType::Purple => "Purple",
-        CellType::Red => "Red",
-        CellType::White => "White",
-        CellType::Blue => "Blue",
-        CellType::Brown => "Brown",
-        CellType::Tan => "Tan",
-        CellType::Gold => "Gold",
-        CellType::Lime => "Lime",
-        CellType::Crimson => "Crimson",
-        CellType::Maroon => "Maroon",
-        CellType::Coral => "Coral",
-        CellType::Pink => "Pink",
-        CellType::Magenta => "Magenta",
-        CellType::Cyan => "Cyan",
-        CellType::Yellow => "Yellow",
-        CellType::Teal => "Teal",
-        CellType::Navy => "Navy",
-        CellType::Olive => "Olive",
-        CellType::Indigo => "Indigo",
-        CellType::Khaki => "Khaki",
-        CellType::Slate => "Slate",
-        CellType::Rust => "Rust",
-        CellType::Mint => "Mint",
-        CellType::Peach => "Peach",
-        CellType::Aqua => "Aqua",
-        CellType::Silver => "Silver",
-        CellType::Violet => "Violet",
-        CellType::Amber => "Amber",
-        CellType::Pearl => "Pearl",
-        CellType::Smoke => "Smoke",
-        CellType::Glint => "Glint",
-        CellType::Tint => "Tint",
-        CellType::Shade => "Shade",
-    }
-    .to_string()
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -170,4 +397,136 @@ mod tests {
         let stats = calculate_stats(&grid);
         assert_eq!(stats.populations.get("Black").unwrap(), &10000);
     }
+
+    #[test]
+    fn test_region_stats_sum_to_whole_grid() {
+        let mut grid = Grid::new(100, 100);
+        grid.initialize_random(&crate::presets::load_preset("predator_heavy").unwrap());
+
+        let whole = calculate_stats(&grid);
+        let regions = calculate_region_stats(&grid, 50);
+        assert_eq!(regions.len(), 4);
+
+        let mut summed: HashMap<String, u32> = HashMap::new();
+        for region in &regions {
+            for (name, count) in &region.populations {
+                *summed.entry(name.clone()).or_insert(0) += count;
+            }
+        }
+        assert_eq!(summed, whole.populations);
+        assert_eq!(regions.iter().map(|r| r.orange_population).sum::<u32>(), whole.orange_population);
+        assert_eq!(regions.iter().map(|r| r.predator_count).sum::<u32>(), whole.predator_count);
+
+        // Green coverage is a percentage; equal-area regions average to the whole
+        let mean_coverage = regions.iter().map(|r| r.green_coverage).sum::<f64>() / 4.0;
+        assert!((mean_coverage - whole.green_coverage).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_center_of_mass_and_velocity() {
+        let mut before = Grid::new(100, 100);
+        for y in 0..10 {
+            for x in 0..10 {
+                before.set_cell(x, y, CellType::Purple);
+            }
+        }
+        let (cx, cy) = center_of_mass(&before, CellType::Purple).unwrap();
+        assert!(cx < 10.0 && cy < 10.0, "center ({}, {})", cx, cy);
+        assert!((cx - 4.5).abs() < 1e-9 && (cy - 4.5).abs() < 1e-9);
+        assert_eq!(center_of_mass(&before, CellType::Green), None);
+
+        // Same cluster shifted 20 cells right over 4 ticks
+        let mut after = Grid::new(100, 100);
+        for y in 0..10 {
+            for x in 20..30 {
+                after.set_cell(x, y, CellType::Purple);
+            }
+        }
+        let (vx, vy) = center_of_mass_velocity(&before, &after, CellType::Purple, 4).unwrap();
+        assert!((vx - 5.0).abs() < 1e-9 && vy.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_population_history_records_each_tick() {
+        let mut sim = crate::Simulator::new_seeded(32, 32, 11);
+        assert!(sim.load_preset("balanced"));
+        sim.enable_population_history(None);
+        for _ in 0..10 {
+            sim.tick();
+        }
+        let green = sim.grid.population(CellType::Green);
+        let history = sim.population_history_mut().unwrap();
+        assert_eq!(history.series(CellType::Green).len(), 10);
+        assert_eq!(history.series(CellType::Green)[9], green);
+
+        let mut bounded = PopulationHistory::new(Some(4));
+        let mut grid = Grid::new(8, 8);
+        for i in 0..6 {
+            grid.set_cell(i, 0, CellType::Blue);
+            bounded.record(&grid);
+        }
+        assert_eq!(bounded.series(CellType::Blue), &[3, 4, 5, 6]);
+        assert_eq!(bounded.series(CellType::Black), &[61, 60, 59, 58]);
+    }
+
+    #[test]
+    fn test_metric_summary_uses_sample_std_dev() {
+        assert_eq!(MetricSummary::from_values(&[2.0, 4.0]), MetricSummary { mean: 3.0, std_dev: 2f64.sqrt() });
+        assert_eq!(MetricSummary::from_values(&[5.0]), MetricSummary { mean: 5.0, std_dev: 0.0 });
+    }
+
+    #[test]
+    fn test_temporal_stability_separates_static_from_oscillating() {
+        let mut grid = Grid::new(20, 20);
+        grid.fill_rect(0, 0, 10, 20, CellType::Green);
+        let mut tracker = StabilityTracker::new(8);
+        for _ in 0..8 {
+            let stats = calculate_stats_tracked(&grid, &mut tracker);
+            assert!((stats.temporal_stability.unwrap() - 1.0).abs() < 1e-9);
+        }
+        assert_eq!(calculate_stats(&grid).temporal_stability, None);
+
+        let mut swinging = StabilityTracker::new(8);
+        let populations = |green: u32| HashMap::from([("Green".to_string(), green), ("Black".to_string(), 400 - green)]);
+        for i in 0..8 {
+            swinging.record(&populations(if i % 2 == 0 { 10 } else { 390 }));
+        }
+        assert!(swinging.score() < 0.1, "oscillating score {}", swinging.score());
+    }
+
+    #[test]
+    fn test_parallel_stats_match_serial() {
+        let mut grid = Grid::new(300, 200);
+        grid.initialize_random(&crate::presets::load_preset("balanced").unwrap());
+        grid.put_cell(7, 9, Cell::with_genes(CellType::Green, crate::cell::Genes { generation: 6, ..Default::default() }));
+
+        let serial = calculate_stats(&grid);
+        let parallel = calculate_stats_parallel(&grid);
+        assert_eq!(parallel.populations, serial.populations);
+        assert_eq!(parallel.max_generation, 6);
+        assert_eq!(parallel.avg_generation, serial.avg_generation);
+        assert_eq!(parallel.health_score, serial.health_score);
+        assert_eq!(grid.tiles(128).map(|tile| tile.len()).sum::<usize>(), 300 * 200);
+    }
+
+    #[test]
+    fn test_predator_counts_by_hand_list_and_category() {
+        let mut grid = Grid::new(10, 10);
+        grid.fill_rect(0, 0, 2, 2, CellType::Crimson);
+        grid.fill_rect(0, 2, 3, 3, CellType::Maroon);
+        grid.set_cell(9, 9, CellType::Coral);
+
+        // Only types on both lists: the counts agree
+        let stats = calculate_stats(&grid);
+        assert_eq!(stats.predator_count, 8);
+        assert_eq!(stats.predator_category_count, 8);
+
+        // Grazers count as predators but aren't in the category; Pink is the reverse
+        grid.set_cell(5, 5, CellType::Brown);
+        grid.set_cell(6, 6, CellType::Tan);
+        grid.set_cell(7, 7, CellType::Pink);
+        let stats = calculate_stats(&grid);
+        assert_eq!((stats.predator_count, stats.predator_category_count), (10, 9));
+        assert!(CellType::Crimson.category() == CellCategory::Predator);
+    }
 }
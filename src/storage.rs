@@ -0,0 +1,305 @@
+//! Cell Storage Backends
+//!
+//! `Grid` keeps each of its buffers behind the `CellStorage` trait, so
+//! alternative memory layouts (palette-indexed, sparse, chunked) can be
+//! swapped in with `Grid::with_storage` without touching the rules.
+//!
+//! Storage is indexed row-major (`y * width + x`); bounds checking against
+//! the grid dimensions is done by `Grid`, not the backend.
+
+use std::collections::HashMap;
+
+use crate::cell::{Cell, CellType};
+
+pub trait CellStorage: Send + Sync {
+    /// Cell at `idx`. Panics if `idx >= len()`.
+    fn get(&self, idx: usize) -> &Cell;
+
+    /// Overwrite the cell at `idx`. Panics if `idx >= len()`.
+    fn set(&mut self, idx: usize, cell: Cell);
+
+    /// Number of cells held (always width * height for a grid buffer)
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Cells actually held in memory; backends with an implicit default
+    /// store fewer than `len()`
+    fn stored_len(&self) -> usize {
+        self.len()
+    }
+
+    /// Overwrite every cell with `cell`
+    fn fill(&mut self, cell: Cell) {
+        for idx in 0..self.len() {
+            self.set(idx, cell.clone());
+        }
+    }
+
+    /// All cells in index order
+    fn iter(&self) -> Box<dyn Iterator<Item = &Cell> + '_> {
+        Box::new((0..self.len()).map(move |idx| self.get(idx)))
+    }
+
+    /// Cells not of type `empty`, with their indices, in index order
+    fn iter_live(&self, empty: CellType) -> Box<dyn Iterator<Item = (usize, &Cell)> + '_> {
+        Box::new(self.iter().enumerate().filter(move |(_, cell)| cell.cell_type != empty))
+    }
+
+    /// Tell the backend which cell now stands for "nothing here"
+    ///
+    /// `Grid` only calls this once no cell of the previous empty type is
+    /// left, so backends with an implicit default can switch to `cell`.
+    fn set_empty(&mut self, _cell: Cell) {}
+}
+
+/// Dense storage: one `Cell` per slot in a `Vec` (the default backend)
+pub struct VecStorage {
+    cells: Vec<Cell>,
+}
+
+impl VecStorage {
+    /// `len` Black cells
+    pub fn new(len: usize) -> Self {
+        VecStorage {
+            cells: vec![Cell::blank(CellType::Black); len],
+        }
+    }
+}
+
+impl CellStorage for VecStorage {
+    #[inline]
+    fn get(&self, idx: usize) -> &Cell {
+        &self.cells[idx]
+    }
+
+    #[inline]
+    fn set(&mut self, idx: usize, cell: Cell) {
+        self.cells[idx] = cell;
+    }
+
+    fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    fn fill(&mut self, cell: Cell) {
+        self.cells.fill(cell);
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = &Cell> + '_> {
+        Box::new(self.cells.iter())
+    }
+}
+
+/// Sparse storage: only non-empty cells are kept, keyed by `(x, y)`
+///
+/// The empty type (Black unless the grid says otherwise) is the implicit
+/// default, so a mostly-empty grid (e.g. the `sparse_genesis` preset)
+/// costs memory proportional to its live cells. Setting a cell to the
+/// empty type removes its entry. Neighbor scans still go through `get`,
+/// so each probe is a hash lookup rather than an index.
+pub struct SparseStorage {
+    width: u32,
+    len: usize,
+    live: HashMap<(u32, u32), Cell>,
+    empty: Cell,
+}
+
+impl SparseStorage {
+    /// `len` implicit Black cells laid out in rows of `width`
+    pub fn new(width: u32, len: usize) -> Self {
+        SparseStorage {
+            width: width.max(1),
+            len,
+            live: HashMap::new(),
+            empty: Cell::blank(CellType::Black),
+        }
+    }
+
+    /// Number of non-empty cells actually stored
+    pub fn live_count(&self) -> usize {
+        self.live.len()
+    }
+
+    /// Non-empty cells with their coordinates, in no particular order
+    pub fn live_cells(&self) -> impl Iterator<Item = ((u32, u32), &Cell)> {
+        self.live.iter().map(|(&pos, cell)| (pos, cell))
+    }
+
+    #[inline]
+    fn key(&self, idx: usize) -> (u32, u32) {
+        assert!(idx < self.len, "index {} out of bounds for sparse storage of {}", idx, self.len);
+        let idx = idx as u32;
+        (idx % self.width, idx / self.width)
+    }
+
+    /// Stored cells sorted by index
+    fn sorted_live(&self) -> Vec<(usize, &Cell)> {
+        let mut live: Vec<_> = self.live
+            .iter()
+            .map(|(&(x, y), cell)| ((y * self.width + x) as usize, cell))
+            .collect();
+        live.sort_unstable_by_key(|&(idx, _)| idx);
+        live
+    }
+}
+
+impl CellStorage for SparseStorage {
+    #[inline]
+    fn get(&self, idx: usize) -> &Cell {
+        self.live.get(&self.key(idx)).unwrap_or(&self.empty)
+    }
+
+    #[inline]
+    fn set(&mut self, idx: usize, cell: Cell) {
+        let key = self.key(idx);
+        if cell.cell_type == self.empty.cell_type {
+            self.live.remove(&key);
+        } else {
+            self.live.insert(key, cell);
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn stored_len(&self) -> usize {
+        self.live.len()
+    }
+
+    fn fill(&mut self, cell: Cell) {
+        self.live.clear();
+        if cell.cell_type != self.empty.cell_type {
+            for idx in 0..self.len {
+                self.set(idx, cell.clone());
+            }
+        }
+    }
+
+    /// Walks the sorted live entries alongside the index range, so no
+    /// slot costs a hash probe
+    fn iter(&self) -> Box<dyn Iterator<Item = &Cell> + '_> {
+        let mut live = self.sorted_live().into_iter().peekable();
+        Box::new((0..self.len).map(move |idx| match live.next_if(|&(at, _)| at == idx) {
+            Some((_, cell)) => cell,
+            None => &self.empty,
+        }))
+    }
+
+    fn iter_live(&self, empty: CellType) -> Box<dyn Iterator<Item = (usize, &Cell)> + '_> {
+        if empty != self.empty.cell_type {
+            return Box::new(self.iter().enumerate().filter(move |(_, cell)| cell.cell_type != empty));
+        }
+        Box::new(self.sorted_live().into_iter())
+    }
+
+    fn set_empty(&mut self, cell: Cell) {
+        self.live.retain(|_, stored| stored.cell_type != cell.cell_type);
+        self.empty = cell;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RuleConfig;
+    use crate::grid::Grid;
+    use crate::rules::{apply_rules, apply_rules_with_rng};
+
+    /// Only rng-free rules: lime without green dies, red has nothing to
+    /// cure, and indigo/pearl are inert
+    fn deterministic_scene(grid: &mut Grid) {
+        grid.set_cell(5, 5, CellType::Red);
+        grid.set_cell(20, 20, CellType::Indigo);
+        grid.set_cell(40, 30, CellType::Lime);
+        grid.set_cell(47, 47, CellType::Pearl);
+    }
+
+    #[test]
+    fn test_vec_storage_matches_default_grid() {
+        let config = RuleConfig::default();
+        let mut default_grid = Grid::new(48, 48);
+        let mut custom_grid = Grid::with_storage(48, 48, VecStorage::new);
+        deterministic_scene(&mut default_grid);
+        deterministic_scene(&mut custom_grid);
+
+        for _ in 0..3 {
+            apply_rules(&mut default_grid, &config);
+            apply_rules(&mut custom_grid, &config);
+            assert_eq!(custom_grid.to_bytes(), default_grid.to_bytes());
+        }
+        assert_eq!(custom_grid.get_cell(40, 30).unwrap().cell_type, CellType::Black);
+    }
+
+    #[test]
+    fn test_sparse_storage_matches_dense_seeded_run() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let preset = crate::presets::load_preset("sparse_genesis").unwrap();
+        let config = RuleConfig::default();
+        let mut dense = Grid::new(64, 48);
+        let mut sparse = Grid::with_storage(64, 48, |len| SparseStorage::new(64, len));
+
+        dense.initialize_random_with_rng(&preset, &mut StdRng::seed_from_u64(11));
+        sparse.initialize_random_with_rng(&preset, &mut StdRng::seed_from_u64(11));
+        assert_eq!(sparse.to_bytes(), dense.to_bytes());
+
+        // Same seed, same rules: every type ticks identically on both
+        let mut dense_rng = StdRng::seed_from_u64(12);
+        let mut sparse_rng = StdRng::seed_from_u64(12);
+        for _ in 0..5 {
+            apply_rules_with_rng(&mut dense, &config, &mut dense_rng);
+            apply_rules_with_rng(&mut sparse, &config, &mut sparse_rng);
+            assert_eq!(sparse.to_bytes(), dense.to_bytes());
+        }
+    }
+
+    #[test]
+    fn test_sparse_storage_drops_black_cells() {
+        let mut storage = SparseStorage::new(10, 100);
+        assert_eq!(storage.get(42).cell_type, CellType::Black);
+
+        storage.set(42, Cell::new(CellType::Green));
+        assert_eq!(storage.live_count(), 1);
+        assert_eq!(storage.get(42).cell_type, CellType::Green);
+        assert_eq!(storage.live_cells().next().unwrap().0, (2, 4));
+
+        storage.set(7, Cell::new(CellType::Red));
+        let types: Vec<_> = storage.iter().map(|cell| cell.cell_type).collect();
+        assert_eq!(types.len(), 100);
+        assert_eq!((types[7], types[42]), (CellType::Red, CellType::Green));
+        assert_eq!(types.iter().filter(|&&t| t == CellType::Black).count(), 98);
+        let live: Vec<_> = storage.iter_live(CellType::Black).map(|(idx, cell)| (idx, cell.cell_type)).collect();
+        assert_eq!(live, vec![(7, CellType::Red), (42, CellType::Green)]);
+
+        storage.set(42, Cell::new(CellType::Black));
+        storage.set(7, Cell::new(CellType::Black));
+        assert_eq!(storage.live_count(), 0);
+    }
+
+    #[test]
+    fn test_sparse_grid_stores_only_live_cells() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let preset = crate::presets::load_preset("sparse_genesis").unwrap();
+        let mut grid = Grid::new_sparse(200, 150);
+        let mut rng = StdRng::seed_from_u64(3);
+        grid.initialize_random_with_rng(&preset, &mut rng);
+
+        let area = 200 * 150;
+        let live = area - grid.population(CellType::Black) as usize;
+        assert_eq!(grid.stored_cells(), live);
+        assert!(grid.stored_cells() * 20 < area, "stored {} of {}", grid.stored_cells(), area);
+        assert_eq!(Grid::new(200, 150).stored_cells(), area);
+
+        for _ in 0..5 {
+            crate::rules::apply_rules_with_rng(&mut grid, &RuleConfig::default(), &mut rng);
+        }
+        assert_eq!(grid.stored_cells(), area - grid.population(CellType::Black) as usize);
+    }
+}
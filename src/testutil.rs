@@ -0,0 +1,83 @@
+//! Test Utilities
+//!
+//! Helpers shared by unit tests across modules. Only compiled for tests.
+
+use rand::RngCore;
+
+use crate::cell::Cell;
+use crate::grid::Grid;
+
+/// The 100 points of `seeded_lattice`, six cells apart, row-major
+pub fn lattice_points() -> impl Iterator<Item = (u32, u32)> {
+    (2..62).step_by(6).flat_map(|y| (2..62).step_by(6).map(move |x| (x, y)))
+}
+
+/// A 64x64 grid with a copy of `cell` at each of `lattice_points`
+///
+/// The points are far enough apart that a single tick around one can't
+/// reach another, so rate tests can count 100 independent outcomes.
+pub fn seeded_lattice(cell: Cell) -> Grid {
+    let mut grid = Grid::new(64, 64);
+    for (x, y) in lattice_points() {
+        grid.put_cell(x, y, cell.clone());
+    }
+    grid
+}
+
+/// An RNG that replays a fixed list of `f64` draws, cycling when it runs out
+///
+/// Each `next_u64` is built so that `rng.gen::<f64>()` returns the next value
+/// in the list (to within 2^-53), which lets a test force a `chance(p)` roll
+/// either way: `0.0` always passes, `0.99` fails for any `p` at or below it.
+/// Integer draws (cell seeds, `gen_range`) consume values from the same list.
+#[derive(Debug, Clone)]
+pub struct SequenceRng {
+    values: Vec<f64>,
+    next: usize,
+}
+
+impl SequenceRng {
+    /// Values must be in `[0, 1)`; an empty list draws 0.0 forever
+    pub fn new(values: &[f64]) -> Self {
+        debug_assert!(values.iter().all(|v| (0.0..1.0).contains(v)), "draws must be in [0, 1)");
+        SequenceRng { values: values.to_vec(), next: 0 }
+    }
+}
+
+impl RngCore for SequenceRng {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let value = self.values.get(self.next).copied().unwrap_or(0.0);
+        self.next = (self.next + 1) % self.values.len().max(1);
+        // rand's f64 sampler keeps the top 53 bits and scales by 2^-53
+        ((value * (1u64 << 53) as f64) as u64) << 11
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_sequence_rng_replays_f64_draws() {
+        let mut rng = SequenceRng::new(&[0.0, 0.25, 0.99]);
+        let draws: Vec<f64> = (0..4).map(|_| rng.gen::<f64>()).collect();
+        assert_eq!(draws, vec![0.0, 0.25, 0.99, 0.0]);
+    }
+}
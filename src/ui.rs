@@ -1,96 +1,463 @@
-use iced::widget::{container, column, text, image as img_widget};
-use iced::{Element, Length};
-use std::sync::{Arc, Mutex};
-use image::{RgbImage, ImageBuffer, Rgb, ColorType, codecs::png::PngEncoder};
-use std::sync::atomic::{AtomicU64, Ordering};
+use iced::widget::{container, column, mouse_area, text, image as img_widget};
+use iced::widget::image::FilterMethod;
+use iced::{Element, Length, Point};
+use std::ops::Range;
+use std::sync::Arc;
 
-use crate::grid::Grid;
+use cellular_sim::cell::CellType;
+use cellular_sim::render;
+use cellular_sim::worker::SimFrame;
 
-pub struct GridDisplay {
-    grid: Arc<Mutex<Grid>>,
-    last_render_tick: Arc<AtomicU64>,
+/// How a block of `scale` x `scale` cells becomes one pixel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DownsampleMode {
+    /// Top-left cell of the block (fast, but aliases on dense grids)
+    #[default]
+    Nearest,
+    /// Most common type in the block; ties go to the lower type id
+    Majority,
+    /// Mean color of the block
+    Average,
 }
 
-impl GridDisplay {
-    pub fn new(grid: Arc<Mutex<Grid>>) -> Self {
-        GridDisplay { 
-            grid,
-            last_render_tick: Arc::new(AtomicU64::new(0)),
+/// Side length in screen pixels of the grid image
+pub const DISPLAY_SIZE: u32 = 800;
+
+/// Which part of the grid the display shows
+///
+/// `zoom` 1.0 fits the whole grid; at `zoom` z the view spans 1/z of the
+/// grid on each axis, starting at cell (`offset_x`, `offset_y`). Offsets are
+/// fractional so a slow drag still moves the view.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    pub offset_x: f32,
+    pub offset_y: f32,
+    pub zoom: f32,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Viewport { offset_x: 0.0, offset_y: 0.0, zoom: 1.0 }
+    }
+}
+
+impl Viewport {
+    /// Closest zoom shows 1/64 of the grid on each axis
+    pub const MAX_ZOOM: f32 = 64.0;
+
+    /// Cells visible along an axis `len` cells long
+    fn span(&self, len: u32) -> f32 {
+        (len as f32 / self.zoom).max(1.0).min(len as f32)
+    }
+
+    /// Limit zoom to 1.0..=MAX_ZOOM and keep the view inside the grid
+    pub fn clamped(self, grid_width: u32, grid_height: u32) -> Viewport {
+        let zoom = if self.zoom.is_finite() { self.zoom.clamp(1.0, Self::MAX_ZOOM) } else { 1.0 };
+        let view = Viewport { zoom, ..self };
+        let max_x = grid_width as f32 - view.span(grid_width);
+        let max_y = grid_height as f32 - view.span(grid_height);
+        Viewport {
+            offset_x: view.offset_x.clamp(0.0, max_x.max(0.0)),
+            offset_y: view.offset_y.clamp(0.0, max_y.max(0.0)),
+            zoom,
+        }
+    }
+
+    /// Whole cells in view, after clamping
+    pub fn visible(&self, grid_width: u32, grid_height: u32) -> (Range<u32>, Range<u32>) {
+        let view = self.clamped(grid_width, grid_height);
+        let axis = |offset: f32, len: u32| {
+            let start = (offset.floor() as u32).min(len.saturating_sub(1));
+            let end = ((offset + view.span(len)).ceil() as u32).clamp(start + 1, len.max(1));
+            start..end
+        };
+        (axis(view.offset_x, grid_width), axis(view.offset_y, grid_height))
+    }
+
+    /// Multiply the zoom by `factor`, keeping the center of the view in place
+    pub fn zoom_by(self, factor: f32, grid_width: u32, grid_height: u32) -> Viewport {
+        let view = self.clamped(grid_width, grid_height);
+        let center_x = view.offset_x + view.span(grid_width) / 2.0;
+        let center_y = view.offset_y + view.span(grid_height) / 2.0;
+        let zoomed = Viewport { zoom: view.zoom * factor, ..view }.clamped(grid_width, grid_height);
+        Viewport {
+            offset_x: center_x - zoomed.span(grid_width) / 2.0,
+            offset_y: center_y - zoomed.span(grid_height) / 2.0,
+            ..zoomed
+        }
+        .clamped(grid_width, grid_height)
+    }
+
+    /// Move the view so the grid follows a drag of (`dx`, `dy`) screen pixels
+    pub fn pan_by_pixels(self, dx: f32, dy: f32, grid_width: u32, grid_height: u32) -> Viewport {
+        let cells_per_pixel_x = self.span(grid_width) / DISPLAY_SIZE as f32;
+        let cells_per_pixel_y = self.span(grid_height) / DISPLAY_SIZE as f32;
+        Viewport {
+            offset_x: self.offset_x - dx * cells_per_pixel_x,
+            offset_y: self.offset_y - dy * cells_per_pixel_y,
+            ..self
+        }
+        .clamped(grid_width, grid_height)
+    }
+
+    /// Grid cell under image position (`px`, `py`), None outside the image
+    pub fn cell_at(&self, px: f32, py: f32, grid_width: u32, grid_height: u32) -> Option<(u32, u32)> {
+        let (view_x, view_y) = self.visible(grid_width, grid_height);
+        let view_size = (view_x.end - view_x.start, view_y.end - view_y.start);
+        let display = (DISPLAY_SIZE as f32, DISPLAY_SIZE as f32);
+        screen_to_grid(px, py, display, view_size).map(|(x, y)| (view_x.start + x, view_y.start + y))
+    }
+}
+
+/// Map a position in the image widget back to a cell of the rendered grid
+///
+/// The widget letterboxes the image to keep its aspect ratio, so a grid
+/// that isn't the widget's shape leaves bars on two sides; clicks there, or
+/// outside the widget, give None. `grid_size` is the size of whatever was
+/// rendered (the visible part of the grid when zoomed).
+pub fn screen_to_grid(px: f32, py: f32, display_size: (f32, f32), grid_size: (u32, u32)) -> Option<(u32, u32)> {
+    let (grid_width, grid_height) = grid_size;
+    if grid_width == 0 || grid_height == 0 {
+        return None;
+    }
+    let pixels_per_cell = (display_size.0 / grid_width as f32).min(display_size.1 / grid_height as f32);
+    let left = (display_size.0 - grid_width as f32 * pixels_per_cell) / 2.0;
+    let top = (display_size.1 - grid_height as f32 * pixels_per_cell) / 2.0;
+    let x = ((px - left) / pixels_per_cell).floor();
+    let y = ((py - top) / pixels_per_cell).floor();
+    if !(x >= 0.0 && y >= 0.0 && x < grid_width as f32 && y < grid_height as f32) {
+        return None;
+    }
+    Some((x as u32, y as u32))
+}
+
+pub struct GridDisplay<Message> {
+    frame: Arc<SimFrame>,
+    viewport: Viewport,
+    mode: DownsampleMode,
+    scale: u32,
+    on_press: Option<Message>,
+    on_release: Option<Message>,
+    on_move: Option<fn(Point) -> Message>,
+}
+
+impl<Message> GridDisplay<Message> {
+    /// Display a frame published by the `SimWorker`
+    pub fn new(frame: Arc<SimFrame>) -> Self {
+        GridDisplay {
+            frame,
+            viewport: Viewport::default(),
+            mode: DownsampleMode::default(),
+            scale: 1,
+            on_press: None,
+            on_release: None,
+            on_move: None,
         }
     }
+
+    /// Show only the part of the grid inside `viewport`
+    pub fn viewport(mut self, viewport: Viewport) -> Self {
+        self.viewport = viewport;
+        self
+    }
+
+    /// Render one pixel per `scale` x `scale` block, combined with `mode`
+    ///
+    /// A view wider than `DISPLAY_SIZE` cells is sampled down further so the
+    /// image never has more pixels than the screen shows.
+    pub fn downsample(mut self, mode: DownsampleMode, scale: u32) -> Self {
+        self.mode = mode;
+        self.scale = scale.max(1);
+        self
+    }
+
+    /// Messages for mouse input over the grid image
+    ///
+    /// Positions passed to `on_move` are relative to the image's top-left corner.
+    pub fn on_mouse(mut self, on_press: Message, on_release: Message, on_move: fn(Point) -> Message) -> Self {
+        self.on_press = Some(on_press);
+        self.on_release = Some(on_release);
+        self.on_move = Some(on_move);
+        self
+    }
+}
+
+/// Everything the view needs from a published frame, ready for the widget
+struct Frame {
+    info: String,
+    pixels: Vec<u8>,
+    width: u32,
+    height: u32,
 }
 
-impl<'a, Message: 'a> From<GridDisplay> for Element<'a, Message> {
-    fn from(grid_display: GridDisplay) -> Self {
-        let (pop_counts, img_handle, grid_width, grid_height) = {
-            let g = grid_display.grid.lock().unwrap();
-            
-            let width = g.width;
-            let height = g.height;
-            let pop_counts = g.get_population_counts();
-            
-            // Create image buffer with optimized scaling - render directly to RGB bytes
-            let scale = 1; // 1:1 mapping for 500x500 grid (no downscaling needed)
-            let display_width = width / scale;
-            let display_height = height / scale;
-            
-            // Pre-allocate buffer and fill in one pass (better cache locality)
-            let mut pixels: Vec<u8> = vec![0; (display_width * display_height * 3) as usize];
-            
-            for y in 0..display_height {
-                for x in 0..display_width {
-                    let grid_x = x * scale;
-                    let grid_y = y * scale;
-                    
-                    let idx = ((y * display_width + x) * 3) as usize;
-                    if let Some(cell) = g.get_cell(grid_x, grid_y) {
-                        let (r, g_val, b) = cell.cell_type.get_color();
-                        pixels[idx] = r;
-                        pixels[idx + 1] = g_val;
-                        pixels[idx + 2] = b;
-                    } else {
-                        pixels[idx] = 0;
-                        pixels[idx + 1] = 0;
-                        pixels[idx + 2] = 0;
-                    }
-                }
+impl Frame {
+    /// Render a published frame, or a blank one if the worker reported an error
+    ///
+    /// A panic inside a tick poisons the grid mutex; rendering must not take
+    /// the whole app down with it.
+    fn capture(sim: &SimFrame, viewport: Viewport, mode: DownsampleMode, scale: u32) -> Frame {
+        match &sim.error {
+            None => Frame::from_sim(sim, viewport, mode, scale),
+            Some(error) => Frame::fallback(&format!("Grid unavailable: {}", error)),
+        }
+    }
+
+    fn from_sim(g: &SimFrame, viewport: Viewport, mode: DownsampleMode, scale: u32) -> Frame {
+        let width = g.width;
+        let height = g.height;
+        let (view_x, view_y) = viewport.visible(width, height);
+        let view_width = view_x.end - view_x.start;
+        let view_height = view_y.end - view_y.start;
+
+        // Create image buffer with optimized scaling - render directly to RGB bytes.
+        // Views smaller than the display stay one pixel per cell; the image
+        // widget upscales them with nearest-neighbor filtering.
+        let scale = scale.max(1).max(view_width.max(view_height).div_ceil(DISPLAY_SIZE));
+        let display_width = (view_width / scale).max(1);
+        let display_height = (view_height / scale).max(1);
+
+        // Pre-allocate buffer and fill in one pass (better cache locality)
+        let mut pixels: Vec<u8> = vec![0; (display_width * display_height * 3) as usize];
+
+        for y in 0..display_height {
+            for x in 0..display_width {
+                let grid_x = view_x.start + x * scale;
+                let grid_y = view_y.start + y * scale;
+
+                let idx = ((y * display_width + x) * 3) as usize;
+                let (r, g_val, b) = match mode {
+                    DownsampleMode::Nearest => match g.cell_type(grid_x, grid_y) {
+                        Some(cell_type) => cell_type.get_color(),
+                        None => continue,
+                    },
+                    DownsampleMode::Majority => majority_type(g, grid_x, grid_y, scale).get_color(),
+                    DownsampleMode::Average => average_color(g, grid_x, grid_y, scale),
+                };
+                pixels[idx] = r;
+                pixels[idx + 1] = g_val;
+                pixels[idx + 2] = b;
             }
-            
-            // Encode to PNG in memory
-            let mut png_data = Vec::with_capacity(pixels.len() / 4); // Reserve reasonable space
-            let encoder = PngEncoder::new(&mut png_data);
-            encoder.encode(
-                &pixels,
-                display_width,
-                display_height,
-                ColorType::Rgb8,
-            ).ok();
-            
-            // Create image handle from bytes
-            let handle = iced::widget::image::Handle::from_memory(png_data);
-            
-            (pop_counts, handle, width, height)
-        };
-        
-        let info_text = text(format!(
-            "Grid: {}x{} | Population: {}",
-            grid_width, grid_height, pop_counts
-        )).size(12);
-        
+        }
+
+        Frame {
+            info: format!(
+                "Grid: {}x{} | View: ({}, {})-({}, {}) at {:.1}x | Population: {}",
+                width,
+                height,
+                view_x.start,
+                view_y.start,
+                view_x.end,
+                view_y.end,
+                viewport.clamped(width, height).zoom,
+                g.population
+            ),
+            pixels,
+            width: display_width,
+            height: display_height,
+        }
+    }
+
+    /// Single black pixel (stretched by the image widget) plus an error message
+    fn fallback(message: &str) -> Frame {
+        log::error!("{}", message);
+        Frame {
+            info: message.to_string(),
+            pixels: vec![0; 3],
+            width: 1,
+            height: 1,
+        }
+    }
+
+    fn to_png(&self) -> Vec<u8> {
+        render::encode_png(&self.pixels, self.width, self.height)
+    }
+}
+
+/// Cells of the `scale` x `scale` block at (x0, y0) that lie inside the grid
+fn block_cells(g: &SimFrame, x0: u32, y0: u32, scale: u32) -> impl Iterator<Item = CellType> + '_ {
+    (y0..y0 + scale)
+        .flat_map(move |y| (x0..x0 + scale).map(move |x| (x, y)))
+        .filter_map(move |(x, y)| g.cell_type(x, y))
+}
+
+fn majority_type(g: &SimFrame, x0: u32, y0: u32, scale: u32) -> CellType {
+    let mut counts = [0u32; CellType::COUNT];
+    for cell_type in block_cells(g, x0, y0, scale) {
+        counts[cell_type.to_u8() as usize] += 1;
+    }
+    let mut best = 0;
+    for (id, &count) in counts.iter().enumerate() {
+        if count > counts[best] {
+            best = id;
+        }
+    }
+    CellType::from_u8(best as u8).unwrap_or(CellType::Black)
+}
+
+fn average_color(g: &SimFrame, x0: u32, y0: u32, scale: u32) -> (u8, u8, u8) {
+    let (mut r, mut g_sum, mut b, mut n) = (0u32, 0u32, 0u32, 0u32);
+    for cell_type in block_cells(g, x0, y0, scale) {
+        let (cr, cg, cb) = cell_type.get_color();
+        r += cr as u32;
+        g_sum += cg as u32;
+        b += cb as u32;
+        n += 1;
+    }
+    if n == 0 {
+        return (0, 0, 0);
+    }
+    ((r / n) as u8, (g_sum / n) as u8, (b / n) as u8)
+}
+
+impl<'a, Message: Clone + 'static> From<GridDisplay<Message>> for Element<'a, Message> {
+    fn from(grid_display: GridDisplay<Message>) -> Self {
+        let frame = Frame::capture(&grid_display.frame, grid_display.viewport, grid_display.mode, grid_display.scale);
+
+        // Create image handle from bytes
+        let img_handle = iced::widget::image::Handle::from_memory(frame.to_png());
+
+        let info_text = text(frame.info).size(12);
+
         let grid_image = img_widget(img_handle)
-            .width(Length::Fixed(800.0))
-            .height(Length::Fixed(800.0));
-        
+            .width(Length::Fixed(DISPLAY_SIZE as f32))
+            .height(Length::Fixed(DISPLAY_SIZE as f32))
+            .filter_method(FilterMethod::Nearest);
+        let mut grid_image = mouse_area(grid_image);
+        if let Some(message) = grid_display.on_press {
+            grid_image = grid_image.on_press(message);
+        }
+        if let Some(message) = grid_display.on_release {
+            // Letting go outside the image ends a drag too
+            grid_image = grid_image.on_release(message.clone()).on_exit(message);
+        }
+        if let Some(on_move) = grid_display.on_move {
+            grid_image = grid_image.on_move(on_move);
+        }
+
         let content = column![
             info_text,
             grid_image
         ]
         .spacing(10)
         .padding(10);
-        
+
         container(content)
             .width(Length::Fill)
             .height(Length::Fill)
             .into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cellular_sim::grid::Grid;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_capture_renders_grid() {
+        let mut grid = Grid::new(4, 4);
+        grid.set_cell(1, 2, CellType::Green);
+        let frame = Frame::capture(&SimFrame::from_grid(&grid, 0), Viewport::default(), DownsampleMode::Nearest, 1);
+
+        assert_eq!((frame.width, frame.height), (4, 4));
+        let idx = ((2 * 4 + 1) * 3) as usize;
+        assert_eq!(&frame.pixels[idx..idx + 3], &[0, 204, 0]);
+    }
+
+    #[test]
+    fn test_poisoned_lock_renders_fallback() {
+        let grid = Arc::new(Mutex::new(Grid::new(4, 4)));
+
+        let poisoner = Arc::clone(&grid);
+        let result = std::thread::spawn(move || {
+            let _guard = poisoner.lock().unwrap();
+            panic!("rule bug mid-tick");
+        })
+        .join();
+        assert!(result.is_err());
+        assert!(grid.is_poisoned());
+
+        let frame = Frame::capture(&SimFrame::snapshot(&grid, 7), Viewport::default(), DownsampleMode::Nearest, 1);
+        assert_eq!((frame.width, frame.height), (1, 1));
+        assert!(frame.pixels.iter().all(|&p| p == 0));
+        assert!(frame.info.contains("poisoned"));
+        assert!(!frame.to_png().is_empty());
+    }
+
+    #[test]
+    fn test_majority_downsample_picks_common_type() {
+        // Nearest would sample the lone purple in the top-left corner
+        let mut grid = Grid::new(2, 2);
+        grid.set_cell(0, 0, CellType::Purple);
+        grid.set_cell(1, 0, CellType::Green);
+        grid.set_cell(0, 1, CellType::Green);
+        grid.set_cell(1, 1, CellType::Green);
+        let grid = SimFrame::from_grid(&grid, 0);
+
+        let majority = Frame::capture(&grid, Viewport::default(), DownsampleMode::Majority, 2);
+        assert_eq!((majority.width, majority.height), (1, 1));
+        let (r, g, b) = CellType::Green.get_color();
+        assert_eq!(majority.pixels, vec![r, g, b]);
+
+        let nearest = Frame::capture(&grid, Viewport::default(), DownsampleMode::Nearest, 2);
+        let (r, g, b) = CellType::Purple.get_color();
+        assert_eq!(nearest.pixels, vec![r, g, b]);
+    }
+
+    #[test]
+    fn test_zoomed_viewport_renders_only_visible_cells() {
+        let mut grid = Grid::new(8, 8);
+        grid.set_cell(0, 0, CellType::Purple);
+        grid.set_cell(4, 2, CellType::Green);
+        grid.set_cell(7, 5, CellType::Blue);
+        let sim = SimFrame::from_grid(&grid, 0);
+
+        let viewport = Viewport { offset_x: 4.0, offset_y: 2.0, zoom: 2.0 };
+        assert_eq!(viewport.visible(8, 8), (4..8, 2..6));
+        let frame = Frame::capture(&sim, viewport, DownsampleMode::Nearest, 1);
+        assert_eq!((frame.width, frame.height), (4, 4));
+
+        let pixel = |x: u32, y: u32| {
+            let idx = ((y * frame.width + x) * 3) as usize;
+            (frame.pixels[idx], frame.pixels[idx + 1], frame.pixels[idx + 2])
+        };
+        assert_eq!(pixel(0, 0), CellType::Green.get_color());
+        assert_eq!(pixel(3, 3), CellType::Blue.get_color());
+        // Purple at (0, 0) is out of view
+        let purple = CellType::Purple.get_color();
+        assert!(frame.pixels.chunks(3).all(|p| (p[0], p[1], p[2]) != purple));
+
+        // Panning past the edge stops at the grid bounds
+        let clamped = Viewport { offset_x: 7.0, offset_y: -3.0, zoom: 100.0 }.clamped(8, 8);
+        assert_eq!(clamped, Viewport { offset_x: 7.0, offset_y: 0.0, zoom: Viewport::MAX_ZOOM });
+        let panned = viewport.pan_by_pixels(-10_000.0, 0.0, 8, 8);
+        assert_eq!(panned.visible(8, 8), (4..8, 2..6));
+    }
+
+    #[test]
+    fn test_screen_to_grid_maps_corners_and_center() {
+        let display = (800.0, 800.0);
+        assert_eq!(screen_to_grid(0.0, 0.0, display, (500, 500)), Some((0, 0)));
+        assert_eq!(screen_to_grid(799.9, 799.9, display, (500, 500)), Some((499, 499)));
+        assert_eq!(screen_to_grid(799.9, 0.0, display, (500, 500)), Some((499, 0)));
+        assert_eq!(screen_to_grid(400.0, 400.0, display, (500, 500)), Some((250, 250)));
+        // 1.6 pixels per cell: 1.5 is still the first cell
+        assert_eq!(screen_to_grid(1.5, 1.7, display, (500, 500)), Some((0, 1)));
+
+        assert_eq!(screen_to_grid(-0.5, 10.0, display, (500, 500)), None);
+        assert_eq!(screen_to_grid(10.0, 800.0, display, (500, 500)), None);
+        assert_eq!(screen_to_grid(900.0, 900.0, display, (500, 500)), None);
+
+        // A wide grid is letterboxed: 200 px bars above and below
+        assert_eq!(screen_to_grid(400.0, 100.0, display, (100, 50)), None);
+        assert_eq!(screen_to_grid(0.0, 200.0, display, (100, 50)), Some((0, 0)));
+        assert_eq!(screen_to_grid(400.0, 400.0, display, (100, 50)), Some((50, 25)));
+        assert_eq!(screen_to_grid(400.0, 600.0, display, (100, 50)), None);
+
+        // Zoomed in, positions land in the visible part of the grid
+        let viewport = Viewport { offset_x: 100.0, offset_y: 200.0, zoom: 4.0 };
+        assert_eq!(viewport.cell_at(0.0, 0.0, 400, 400), Some((100, 200)));
+        assert_eq!(viewport.cell_at(799.0, 799.0, 400, 400), Some((199, 299)));
+    }
+}
@@ -0,0 +1,193 @@
+//! Binary Wire Format
+//!
+//! Compact, self-describing frames for streaming grid state to native clients.
+//!
+//! Layout (all integers little-endian):
+//!
+//! | Offset | Size | Field                         |
+//! |--------|------|-------------------------------|
+//! | 0      | 4    | magic `b"CAGF"`               |
+//! | 4      | 1    | format version (currently 1)  |
+//! | 5      | 4    | width (u32)                   |
+//! | 9      | 4    | height (u32)                  |
+//! | 13     | 8    | tick (u64)                    |
+//! | 21     | ..   | RLE cell data                 |
+//!
+//! Cell data is run-length encoded as `(count: u8, cell_type: u8)` pairs in
+//! row-major order. Runs longer than 255 are split across several pairs.
+
+use std::fmt;
+
+use crate::cell::CellType;
+use crate::grid::Grid;
+
+pub const FRAME_MAGIC: [u8; 4] = *b"CAGF";
+pub const FRAME_VERSION: u8 = 1;
+pub const FRAME_HEADER_LEN: usize = 21;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrameError {
+    /// Input is shorter than the fixed header
+    Truncated,
+    /// First four bytes are not `FRAME_MAGIC`
+    BadMagic([u8; 4]),
+    /// Frame was written by an unknown format version
+    UnsupportedVersion(u8),
+    /// RLE payload is malformed or doesn't cover exactly width*height cells
+    BadPayload,
+    /// A cell byte doesn't map to a `CellType`
+    InvalidCellType(u8),
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameError::Truncated => write!(f, "frame shorter than {}-byte header", FRAME_HEADER_LEN),
+            FrameError::BadMagic(magic) => write!(f, "bad frame magic {:?}", magic),
+            FrameError::UnsupportedVersion(v) => {
+                write!(f, "unsupported frame version {} (expected {})", v, FRAME_VERSION)
+            }
+            FrameError::BadPayload => write!(f, "RLE payload does not match grid dimensions"),
+            FrameError::InvalidCellType(t) => write!(f, "invalid cell type byte {}", t),
+        }
+    }
+}
+
+impl std::error::Error for FrameError {}
+
+/// Encode a grid and its tick count as a single frame
+pub fn encode_frame(grid: &Grid, tick: u64) -> Vec<u8> {
+    let payload = rle_encode(&grid.to_bytes());
+    let mut frame = Vec::with_capacity(FRAME_HEADER_LEN + payload.len());
+    frame.extend_from_slice(&FRAME_MAGIC);
+    frame.push(FRAME_VERSION);
+    frame.extend_from_slice(&grid.width.to_le_bytes());
+    frame.extend_from_slice(&grid.height.to_le_bytes());
+    frame.extend_from_slice(&tick.to_le_bytes());
+    frame.extend_from_slice(&payload);
+    frame
+}
+
+/// Decode a frame back into a grid and its tick count
+pub fn decode_frame(bytes: &[u8]) -> Result<(Grid, u64), FrameError> {
+    if bytes.len() < FRAME_HEADER_LEN {
+        return Err(FrameError::Truncated);
+    }
+
+    let magic: [u8; 4] = bytes[0..4].try_into().unwrap();
+    if magic != FRAME_MAGIC {
+        return Err(FrameError::BadMagic(magic));
+    }
+    if bytes[4] != FRAME_VERSION {
+        return Err(FrameError::UnsupportedVersion(bytes[4]));
+    }
+
+    let width = u32::from_le_bytes(bytes[5..9].try_into().unwrap());
+    let height = u32::from_le_bytes(bytes[9..13].try_into().unwrap());
+    let tick = u64::from_le_bytes(bytes[13..21].try_into().unwrap());
+
+    // The header is untrusted: check the cell count against what the
+    // payload can hold before allocating anything for it
+    let payload = &bytes[FRAME_HEADER_LEN..];
+    let max_cells = payload.len() / 2 * u8::MAX as usize;
+    let expected = (width as usize)
+        .checked_mul(height as usize)
+        .filter(|&cells| cells <= max_cells)
+        .ok_or(FrameError::BadPayload)?;
+    let types = rle_decode(payload, expected).ok_or(FrameError::BadPayload)?;
+
+    let mut grid = Grid::new(width, height);
+    for (i, &byte) in types.iter().enumerate() {
+        let cell_type = CellType::from_u8(byte).ok_or(FrameError::InvalidCellType(byte))?;
+        if cell_type != CellType::Black {
+            grid.set_cell(i as u32 % width, i as u32 / width, cell_type);
+        }
+    }
+
+    Ok((grid, tick))
+}
+
+/// Run-length encode a byte stream as `(count, value)` pairs
+pub(crate) fn rle_encode(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut iter = data.iter().copied();
+
+    if let Some(first) = iter.next() {
+        let mut value = first;
+        let mut count: u8 = 1;
+        for byte in iter {
+            if byte == value && count < u8::MAX {
+                count += 1;
+            } else {
+                out.push(count);
+                out.push(value);
+                value = byte;
+                count = 1;
+            }
+        }
+        out.push(count);
+        out.push(value);
+    }
+    out
+}
+
+/// Decode `(count, value)` pairs, requiring exactly `expected_len` output bytes
+pub(crate) fn rle_decode(data: &[u8], expected_len: usize) -> Option<Vec<u8>> {
+    if !data.len().is_multiple_of(2) {
+        return None;
+    }
+
+    let mut out = Vec::with_capacity(expected_len);
+    for pair in data.chunks_exact(2) {
+        let (count, value) = (pair[0] as usize, pair[1]);
+        if count == 0 || out.len() + count > expected_len {
+            return None;
+        }
+        out.extend(std::iter::repeat_n(value, count));
+    }
+
+    (out.len() == expected_len).then_some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_round_trip() {
+        let mut grid = Grid::new(40, 30);
+        grid.set_cell(0, 0, CellType::Green);
+        grid.set_cell(39, 29, CellType::Shade);
+        grid.set_cell(12, 7, CellType::Purple);
+
+        let frame = encode_frame(&grid, 4242);
+        assert_eq!(&frame[0..4], b"CAGF");
+        assert!(frame.len() < grid.to_bytes().len());
+
+        let (decoded, tick) = decode_frame(&frame).unwrap();
+        assert_eq!(tick, 4242);
+        assert_eq!((decoded.width, decoded.height), (40, 30));
+        assert_eq!(decoded.to_bytes(), grid.to_bytes());
+    }
+
+    #[test]
+    fn test_frame_rejects_bad_magic() {
+        let mut frame = encode_frame(&Grid::new(8, 8), 0);
+        frame[0] = b'X';
+        assert_eq!(decode_frame(&frame).err(), Some(FrameError::BadMagic(*b"XAGF")));
+    }
+
+    #[test]
+    fn test_frame_rejects_oversized_header() {
+        // Header-only frame claiming a u32::MAX x u32::MAX grid
+        let mut frame = encode_frame(&Grid::new(1, 1), 0);
+        frame.truncate(FRAME_HEADER_LEN);
+        frame[5..13].fill(0xFF);
+        assert_eq!(decode_frame(&frame).err(), Some(FrameError::BadPayload));
+
+        // One run can't cover more than 255 cells
+        let mut frame = encode_frame(&Grid::new(16, 16), 0);
+        frame.truncate(FRAME_HEADER_LEN + 2);
+        assert_eq!(decode_frame(&frame).err(), Some(FrameError::BadPayload));
+    }
+}
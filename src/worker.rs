@@ -0,0 +1,405 @@
+//! Background Ticking
+//!
+//! `SimWorker` runs the rules on its own thread so a slow tick never holds up
+//! the UI. The grid stays behind an `Arc<Mutex<Grid>>`; the UI edits it
+//! (reset, preset loads) through `SimWorker::edit`, which lands between two
+//! ticks and reports which one. For drawing it reads the latest
+//! `SimFrame`, a type-id snapshot the worker publishes after every tick.
+//! Publishing swaps an `Arc`, so a reader holds the frame lock just long
+//! enough to clone a pointer and never waits on a tick.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use rand_chacha::ChaCha8Rng;
+
+use crate::cell::CellType;
+use crate::config::RuleConfig;
+use crate::grid::Grid;
+use crate::logging;
+use crate::metrics::MetricsCollector;
+use crate::rules::apply_rules_with_rng;
+
+/// How long a paused worker sleeps between checks for new commands
+const IDLE_POLL: Duration = Duration::from_millis(20);
+
+/// Cell type ids of the grid as of one tick
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimFrame {
+    /// Worker tick count when the snapshot was taken
+    pub tick: u64,
+    pub width: u32,
+    pub height: u32,
+    /// Row-major type ids (`Grid::to_bytes`)
+    pub cells: Vec<u8>,
+    /// `Grid::get_population_counts` JSON
+    pub population: String,
+    /// Set instead of `cells` when the grid lock was poisoned by a failed tick
+    pub error: Option<String>,
+}
+
+impl SimFrame {
+    pub fn from_grid(grid: &Grid, tick: u64) -> SimFrame {
+        SimFrame {
+            tick,
+            width: grid.width,
+            height: grid.height,
+            cells: grid.to_bytes(),
+            population: grid.get_population_counts(),
+            error: None,
+        }
+    }
+
+    /// Snapshot a shared grid, or an error frame if its lock is poisoned
+    pub fn snapshot(grid: &Mutex<Grid>, tick: u64) -> SimFrame {
+        match grid.lock() {
+            Ok(g) => SimFrame::from_grid(&g, tick),
+            Err(_) => SimFrame {
+                tick,
+                width: 0,
+                height: 0,
+                cells: Vec::new(),
+                population: String::new(),
+                error: Some("simulation lock was poisoned by a failed tick".to_string()),
+            },
+        }
+    }
+
+    /// Type of the cell at (x, y), None outside the frame
+    pub fn cell_type(&self, x: u32, y: u32) -> Option<CellType> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        CellType::from_u8(self.cells[(y * self.width + x) as usize])
+    }
+}
+
+/// State shared between the worker thread and its handle
+struct Shared {
+    stop: AtomicBool,
+    running: AtomicBool,
+    tick_count: AtomicU64,
+    /// Target rate as `f64` bits
+    ticks_per_second: AtomicU64,
+    frame: Mutex<Arc<SimFrame>>,
+}
+
+impl Shared {
+    fn ticks_per_second(&self) -> f64 {
+        f64::from_bits(self.ticks_per_second.load(Ordering::Relaxed))
+    }
+
+    fn publish(&self, frame: SimFrame) {
+        let frame = Arc::new(frame);
+        if let Ok(mut slot) = self.frame.lock() {
+            *slot = frame;
+        }
+    }
+}
+
+/// Ticks a shared grid on a background thread at a set rate
+///
+/// Starts paused. Dropping the worker stops and joins its thread.
+pub struct SimWorker {
+    grid: Arc<Mutex<Grid>>,
+    shared: Arc<Shared>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl SimWorker {
+    /// Start the worker thread; `rng` drives every tick, `metrics` gets each tick's duration
+    pub fn spawn(
+        grid: Arc<Mutex<Grid>>,
+        config: RuleConfig,
+        rng: ChaCha8Rng,
+        ticks_per_second: f64,
+        metrics: Arc<Mutex<MetricsCollector>>,
+    ) -> SimWorker {
+        let shared = Arc::new(Shared {
+            stop: AtomicBool::new(false),
+            running: AtomicBool::new(false),
+            tick_count: AtomicU64::new(0),
+            ticks_per_second: AtomicU64::new(ticks_per_second.to_bits()),
+            frame: Mutex::new(Arc::new(SimFrame::snapshot(&grid, 0))),
+        });
+        let handle = {
+            let grid = Arc::clone(&grid);
+            let shared = Arc::clone(&shared);
+            thread::Builder::new()
+                .name("sim-worker".to_string())
+                .spawn(move || run(&grid, &shared, config, rng, &metrics))
+                .expect("spawn simulation worker thread")
+        };
+        SimWorker { grid, shared, handle: Some(handle) }
+    }
+
+    /// Resume or pause ticking; returns the tick count it took effect at
+    ///
+    /// Takes the grid lock, so no tick is in flight: after pausing, the
+    /// count stays at the returned value.
+    pub fn set_running(&self, running: bool) -> u64 {
+        let tick = {
+            let _grid = self.grid.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            self.shared.running.store(running, Ordering::Relaxed);
+            self.tick_count()
+        };
+        self.wake();
+        tick
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.shared.running.load(Ordering::Relaxed)
+    }
+
+    /// Change the target tick rate; takes effect from the next tick
+    pub fn set_ticks_per_second(&self, ticks_per_second: f64) {
+        self.shared.ticks_per_second.store(ticks_per_second.to_bits(), Ordering::Relaxed);
+        self.wake();
+    }
+
+    /// Ticks run since the worker started or the count was last reset
+    pub fn tick_count(&self) -> u64 {
+        self.shared.tick_count.load(Ordering::Relaxed)
+    }
+
+    /// The grid the worker ticks; lock it to read, change it through `edit`
+    pub fn grid(&self) -> &Arc<Mutex<Grid>> {
+        &self.grid
+    }
+
+    /// Most recently published frame
+    pub fn latest_frame(&self) -> Arc<SimFrame> {
+        match self.shared.frame.lock() {
+            Ok(frame) => Arc::clone(&frame),
+            Err(poisoned) => Arc::clone(&poisoned.into_inner()),
+        }
+    }
+
+    /// Apply `edit` to the grid between two ticks and republish it
+    ///
+    /// With `reset_ticks` the tick count starts again from 0, as after
+    /// loading a new preset. The edit, the reset and reading the tick count
+    /// all happen under one grid lock, so no tick can land in between.
+    /// Returns the tick count the edit applied at and its result, or None
+    /// if the grid lock was poisoned by a failed tick.
+    pub fn edit<R>(&self, reset_ticks: bool, edit: impl FnOnce(&mut Grid) -> R) -> Option<(u64, R)> {
+        let Ok(mut grid) = self.grid.lock() else {
+            self.shared.publish(SimFrame::snapshot(&self.grid, self.tick_count()));
+            return None;
+        };
+        let result = edit(&mut grid);
+        if reset_ticks {
+            self.shared.tick_count.store(0, Ordering::Relaxed);
+        }
+        let tick = self.tick_count();
+        self.shared.publish(SimFrame::from_grid(&grid, tick));
+        Some((tick, result))
+    }
+
+    /// Stop the thread and wait for it; Err if it panicked
+    pub fn stop(mut self) -> thread::Result<()> {
+        self.shutdown()
+    }
+
+    fn shutdown(&mut self) -> thread::Result<()> {
+        self.shared.stop.store(true, Ordering::Relaxed);
+        self.wake();
+        match self.handle.take() {
+            Some(handle) => handle.join(),
+            None => Ok(()),
+        }
+    }
+
+    fn wake(&self) {
+        if let Some(handle) = &self.handle {
+            handle.thread().unpark();
+        }
+    }
+}
+
+impl Drop for SimWorker {
+    fn drop(&mut self) {
+        let _ = self.shutdown();
+    }
+}
+
+/// Turns real elapsed time into whole ticks at a target rate
+///
+/// Fractions of a tick carry over between calls, so a slow rate like 0.1
+/// still ticks once every ten seconds however often it's polled. A stall
+/// (or ticks slower than the target) is not made up in one go: at most
+/// `MAX_LAG` worth of ticks are due at once and the rest are dropped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pacer {
+    pub target_tps: f64,
+    /// Ticks owed but not yet due, always below 1.0 after `ticks_due`
+    owed: f64,
+}
+
+impl Pacer {
+    /// Longest backlog worth of ticks `ticks_due` will hand out at once
+    pub const MAX_LAG: Duration = Duration::from_millis(100);
+
+    pub fn new(target_tps: f64) -> Pacer {
+        Pacer { target_tps, owed: 0.0 }
+    }
+
+    /// Ticks to run now that `elapsed` has passed since the last call
+    pub fn ticks_due(&mut self, elapsed: Duration) -> u64 {
+        if self.target_tps <= 0.0 || !self.target_tps.is_finite() {
+            self.owed = 0.0;
+            return 0;
+        }
+        self.owed += elapsed.as_secs_f64() * self.target_tps;
+        let max_burst = (Self::MAX_LAG.as_secs_f64() * self.target_tps).ceil().max(1.0);
+        if self.owed >= max_burst + 1.0 {
+            self.owed = max_burst;
+        }
+        let due = self.owed.floor();
+        self.owed -= due;
+        due as u64
+    }
+
+    /// Time until the next tick falls due, assuming no call in between
+    pub fn time_to_next(&self) -> Duration {
+        if self.target_tps <= 0.0 || !self.target_tps.is_finite() {
+            return Duration::MAX;
+        }
+        Duration::from_secs_f64(((1.0 - self.owed) / self.target_tps).max(0.0))
+    }
+
+    /// Forget any partial tick, e.g. when resuming from pause
+    pub fn reset(&mut self) {
+        self.owed = 0.0;
+    }
+}
+
+/// Worker thread body: tick on schedule until told to stop
+fn run(grid: &Mutex<Grid>, shared: &Shared, config: RuleConfig, mut rng: ChaCha8Rng, metrics: &Mutex<MetricsCollector>) {
+    let mut pacer = Pacer::new(shared.ticks_per_second());
+    let mut last_check = Instant::now();
+    while !shared.stop.load(Ordering::Relaxed) {
+        pacer.target_tps = shared.ticks_per_second();
+        if !shared.running.load(Ordering::Relaxed) || pacer.target_tps <= 0.0 {
+            thread::park_timeout(IDLE_POLL);
+            pacer.reset();
+            last_check = Instant::now();
+            continue;
+        }
+
+        let now = Instant::now();
+        let due = pacer.ticks_due(now - last_check);
+        last_check = now;
+        if due == 0 {
+            thread::park_timeout(pacer.time_to_next().min(IDLE_POLL));
+            continue;
+        }
+
+        // The grid lock is released between ticks so UI edits aren't held
+        // up by a long burst; the frame is published once per burst
+        let mut frame = None;
+        for _ in 0..due {
+            if shared.stop.load(Ordering::Relaxed) || !shared.running.load(Ordering::Relaxed) {
+                break;
+            }
+            let tick_start = Instant::now();
+            let Ok(mut g) = grid.lock() else {
+                // A tick panicked mid-update; show that and stop ticking
+                frame = Some(SimFrame::snapshot(grid, shared.tick_count.load(Ordering::Relaxed)));
+                shared.running.store(false, Ordering::Relaxed);
+                break;
+            };
+            // Paused while this thread waited for the lock
+            if !shared.running.load(Ordering::Relaxed) {
+                break;
+            }
+            apply_rules_with_rng(&mut g, &config, &mut rng);
+            let tick = shared.tick_count.fetch_add(1, Ordering::Relaxed) + 1;
+            let tick_duration = tick_start.elapsed();
+            if let Ok(mut metrics) = metrics.lock() {
+                metrics.record_tick(tick_duration);
+            }
+            let cells = g.width as u64 * g.height as u64;
+            logging::log_tick_performance(tick, tick_duration.as_secs_f64() * 1000.0, cells);
+            frame = Some(SimFrame::from_grid(&g, tick));
+        }
+        if let Some(frame) = frame {
+            shared.publish(frame);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_worker_ticks_in_background_and_joins() {
+        let mut grid = Grid::new(32, 32);
+        grid.fill_rect(4, 4, 12, 12, CellType::Green);
+        let grid = Arc::new(Mutex::new(grid));
+        let metrics = Arc::new(Mutex::new(MetricsCollector::new()));
+        let worker = SimWorker::spawn(
+            Arc::clone(&grid),
+            RuleConfig::default(),
+            ChaCha8Rng::seed_from_u64(1),
+            500.0,
+            Arc::clone(&metrics),
+        );
+
+        // Paused until told otherwise
+        thread::sleep(Duration::from_millis(30));
+        assert_eq!(worker.tick_count(), 0);
+
+        worker.set_running(true);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while worker.tick_count() < 5 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(5));
+        }
+        let ticks = worker.set_running(false);
+        assert!(ticks >= 5, "only {} ticks", ticks);
+        // Nothing lands after the pause took effect
+        thread::sleep(Duration::from_millis(30));
+        assert_eq!(worker.tick_count(), ticks);
+
+        let frame = worker.latest_frame();
+        assert!(frame.tick >= 5);
+        assert_eq!(frame.cells.len(), 32 * 32);
+        assert!(metrics.lock().unwrap().get_tps() > 0.0);
+
+        // Edits land between ticks; a reset edit reports tick 0
+        let (tick, ()) = worker.edit(true, |g| g.set_cell(0, 0, CellType::Blue)).unwrap();
+        assert_eq!((tick, worker.tick_count()), (0, 0));
+        assert_eq!(worker.latest_frame().cell_type(0, 0), Some(CellType::Blue));
+
+        worker.stop().expect("worker thread joins cleanly");
+        // The grid is free again once the worker has gone
+        assert!(grid.try_lock().is_ok());
+    }
+
+    #[test]
+    fn test_pacer_ticks_follow_elapsed_time() {
+        let mut pacer = Pacer::new(60.0);
+        assert_eq!(pacer.ticks_due(Duration::from_millis(50)), 3);
+        // 0.5 tick left over from 100ms at 5 TPS completes with the next 100ms
+        let mut pacer = Pacer::new(5.0);
+        assert_eq!(pacer.ticks_due(Duration::from_millis(100)), 0);
+        assert_eq!(pacer.ticks_due(Duration::from_millis(100)), 1);
+
+        // 0.1 TPS: nothing for 9.9s of 16ms frames, then one tick
+        let mut pacer = Pacer::new(0.1);
+        let frames: u64 = (0..619).map(|_| pacer.ticks_due(Duration::from_millis(16))).sum();
+        assert_eq!(frames, 0);
+        assert_eq!(pacer.ticks_due(Duration::from_millis(100)), 1);
+
+        // A 2s stall at 1000 TPS is capped at MAX_LAG worth of ticks
+        let mut pacer = Pacer::new(1000.0);
+        assert_eq!(pacer.ticks_due(Duration::from_secs(2)), 100);
+        assert_eq!(pacer.ticks_due(Duration::from_millis(1)), 1);
+
+        assert_eq!(Pacer::new(0.0).ticks_due(Duration::from_secs(10)), 0);
+    }
+}
@@ -0,0 +1,84 @@
+//! Seed-and-run fuzzing of the chunked triple-buffer tick
+//!
+//! Each case picks a small grid size (deliberately not a multiple of
+//! `CHUNK_SIZE`), seeds a random population, runs a few ticks and checks
+//! buffer invariants after every one. Iteration counts are kept low so the
+//! suite stays fast in CI.
+
+use cellular_sim::cell::CellType;
+use cellular_sim::grid::Grid;
+use cellular_sim::{apply_rules, load_preset, RuleConfig};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const SEEDS: u64 = 48;
+const TICKS_PER_SEED: usize = 6;
+const PRESETS: [&str; 4] = ["balanced", "dense_forest", "plague_outbreak", "predator_heavy"];
+
+fn seeded_grid(seed: u64) -> Grid {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let width = rng.gen_range(1..=48);
+    let height = rng.gen_range(1..=48);
+    let preset = load_preset(PRESETS[rng.gen_range(0..PRESETS.len())]).unwrap();
+
+    let mut grid = Grid::new(width, height);
+    grid.initialize_random_with_rng(&preset, &mut rng);
+    grid
+}
+
+fn assert_invariants(grid: &Grid, width: u32, height: u32, context: &str) {
+    assert_eq!((grid.width, grid.height), (width, height), "{}: dimensions changed", context);
+
+    // A write to a bad index would either panic or resize the buffers
+    let bytes = grid.to_bytes();
+    assert_eq!(bytes.len(), (width * height) as usize, "{}: buffer length changed", context);
+
+    for (i, &byte) in bytes.iter().enumerate() {
+        let cell_type = CellType::from_u8(byte)
+            .unwrap_or_else(|| panic!("{}: cell {} has out-of-range type {}", context, i, byte));
+        assert_eq!(cell_type.to_u8(), byte, "{}: type round trip", context);
+    }
+
+    assert!(grid.get_cell(width, 0).is_none(), "{}: read past right edge", context);
+    assert!(grid.get_cell(0, height).is_none(), "{}: read past bottom edge", context);
+    assert!(grid.get_next_cell(width - 1, height - 1).is_some(), "{}: next buffer too small", context);
+}
+
+#[test]
+fn fuzz_buffer_swap_invariants() {
+    let config = RuleConfig::default();
+
+    for seed in 0..SEEDS {
+        let mut grid = seeded_grid(seed);
+        let (width, height) = (grid.width, grid.height);
+        assert_invariants(&grid, width, height, &format!("seed {} init", seed));
+
+        for tick in 0..TICKS_PER_SEED {
+            apply_rules(&mut grid, &config);
+            assert_invariants(&grid, width, height, &format!("seed {} tick {}", seed, tick));
+        }
+    }
+}
+
+#[test]
+fn fuzz_inert_cells_not_overwritten_by_stale_buffer() {
+    let config = RuleConfig::default();
+
+    for seed in 0..SEEDS {
+        // Only the dimensions come from the seed; an inert cell on an
+        // otherwise empty grid has nothing that could legitimately replace it
+        let (width, height) = { let g = seeded_grid(seed); (g.width, g.height) };
+        let mut grid = Grid::new(width, height);
+        grid.set_cell(0, 0, CellType::Pearl);
+        for tick in 0..TICKS_PER_SEED {
+            apply_rules(&mut grid, &config);
+            assert_invariants(&grid, width, height, &format!("seed {} tick {}", seed, tick));
+            assert!(
+                grid.get_cell(0, 0).unwrap().cell_type == CellType::Pearl,
+                "seed {} tick {}: inert Pearl replaced by a leftover next_cells value",
+                seed,
+                tick
+            );
+        }
+    }
+}
@@ -0,0 +1,58 @@
+//! Golden-grid snapshot of the full rule set
+//!
+//! Seeds a simulator with `balanced`, runs `TICKS` ticks and compares the
+//! result against a wire frame committed under `tests/fixtures/`. Any change
+//! in rule behavior flips it. When a change is intentional, regenerate with
+//!
+//!     UPDATE_GOLDEN=1 cargo test --test golden -- --include-ignored
+//!
+//! and commit the new fixture alongside the rule change.
+
+use std::path::PathBuf;
+
+use cellular_sim::wire::decode_frame;
+use cellular_sim::Simulator;
+
+const WIDTH: u32 = 64;
+const HEIGHT: u32 = 64;
+const SEED: u64 = 7;
+const TICKS: u64 = 100;
+
+fn fixture_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/golden_balanced_100.frame")
+}
+
+fn run_golden() -> Simulator {
+    let mut sim = Simulator::new_seeded(WIDTH, HEIGHT, SEED);
+    assert!(sim.load_preset("balanced"));
+    for _ in 0..TICKS {
+        sim.tick();
+    }
+    sim
+}
+
+/// Overwrite the fixture with the current behavior
+fn regenerate(sim: &Simulator) {
+    std::fs::write(fixture_path(), sim.to_frame()).expect("write golden fixture");
+    eprintln!("golden fixture regenerated: {}", fixture_path().display());
+}
+
+#[test]
+fn golden_balanced_100_ticks() {
+    let sim = run_golden();
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        regenerate(&sim);
+        return;
+    }
+
+    let bytes = std::fs::read(fixture_path())
+        .expect("missing golden fixture; run with UPDATE_GOLDEN=1 to create it");
+    let (expected, tick) = decode_frame(&bytes).expect("golden fixture is not a valid frame");
+    assert_eq!(tick, TICKS);
+    assert_eq!(
+        sim.grid.content_hash(),
+        expected.content_hash(),
+        "grid after {} ticks differs from the golden fixture; if intentional, regenerate it",
+        TICKS
+    );
+}
@@ -0,0 +1,90 @@
+//! Per-preset regression fixtures
+//!
+//! Runs every built-in preset from a fixed seed for `TICKS` ticks and
+//! compares the final `Grid::content_hash` against the value recorded in
+//! `tests/fixtures/regression_200.txt` (one `<preset> <hash>` line each).
+//! Where the golden test pins one preset cell by cell, this one catches a
+//! rule change that alters any preset's dynamics. When a change is
+//! intentional, regenerate with
+//!
+//!     UPDATE_GOLDEN=1 cargo test --test regression
+//!
+//! and commit the new fixture alongside the rule change.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use cellular_sim::Simulator;
+
+const WIDTH: u32 = 48;
+const HEIGHT: u32 = 48;
+const SEED: u64 = 11;
+const TICKS: u64 = 200;
+
+fn fixture_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/regression_200.txt")
+}
+
+fn final_hash(preset: &str) -> u64 {
+    let mut sim = Simulator::new_seeded(WIDTH, HEIGHT, SEED);
+    assert!(sim.load_preset(preset), "unknown built-in preset {}", preset);
+    for _ in 0..TICKS {
+        sim.tick();
+    }
+    sim.grid.content_hash()
+}
+
+/// Every preset's final hash; presets run on their own threads
+fn final_hashes() -> BTreeMap<String, u64> {
+    std::thread::scope(|scope| {
+        let runs: Vec<_> = Simulator::list_presets()
+            .into_iter()
+            .map(|preset| scope.spawn(move || (final_hash(&preset), preset)))
+            .collect();
+        runs.into_iter()
+            .map(|run| {
+                let (hash, preset) = run.join().expect("preset run panicked");
+                (preset, hash)
+            })
+            .collect()
+    })
+}
+
+fn parse_fixture(text: &str) -> BTreeMap<String, u64> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let (preset, hash) = line.split_once(' ').expect("fixture line is `<preset> <hash>`");
+            let hash = u64::from_str_radix(hash.trim(), 16).expect("fixture hash is hex");
+            (preset.to_string(), hash)
+        })
+        .collect()
+}
+
+#[test]
+fn presets_match_regression_fixtures() {
+    let actual = final_hashes();
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        let text: String = actual.iter().map(|(preset, hash)| format!("{} {:016x}\n", preset, hash)).collect();
+        std::fs::write(fixture_path(), text).expect("write regression fixture");
+        eprintln!("regression fixture regenerated: {}", fixture_path().display());
+        return;
+    }
+
+    let text = std::fs::read_to_string(fixture_path())
+        .expect("missing regression fixture; run with UPDATE_GOLDEN=1 to create it");
+    let expected = parse_fixture(&text);
+    let changed: Vec<&String> = actual
+        .keys()
+        .chain(expected.keys())
+        .filter(|preset| actual.get(*preset) != expected.get(*preset))
+        .collect::<std::collections::BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    assert!(
+        changed.is_empty(),
+        "presets {:?} ended {} ticks in a different state than recorded; if intentional, regenerate the fixture",
+        changed,
+        TICKS
+    );
+}